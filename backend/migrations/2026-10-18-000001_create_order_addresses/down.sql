@@ -0,0 +1,2 @@
+-- 删除order_addresses表
+DROP TABLE IF EXISTS order_addresses;
\ No newline at end of file
@@ -0,0 +1,15 @@
+-- 创建order_addresses表，保存下单时的收货地址快照
+CREATE TABLE IF NOT EXISTS order_addresses (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    recipient_name VARCHAR(100) NOT NULL,
+    phone VARCHAR(20) NOT NULL,
+    province VARCHAR(100) NOT NULL DEFAULT '',
+    city VARCHAR(100) NOT NULL DEFAULT '',
+    district VARCHAR(100) NOT NULL DEFAULT '',
+    street TEXT NOT NULL,
+    postal_code VARCHAR(20),
+    created_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    UNIQUE KEY (order_id)
+);
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- 删除addresses表
+DROP TABLE IF EXISTS addresses;
\ No newline at end of file
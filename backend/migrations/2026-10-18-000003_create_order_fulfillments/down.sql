@@ -0,0 +1,6 @@
+-- 删除order_fulfillments表
+ALTER TABLE order_items
+    DROP FOREIGN KEY fk_order_items_fulfillment,
+    DROP COLUMN fulfillment_id;
+
+DROP TABLE IF EXISTS order_fulfillments;
\ No newline at end of file
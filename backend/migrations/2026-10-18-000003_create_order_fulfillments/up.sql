@@ -0,0 +1,32 @@
+-- 创建order_fulfillments表（按商家拆分的子订单/履约记录）
+CREATE TABLE IF NOT EXISTS order_fulfillments (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    vendor_id VARCHAR(36) NOT NULL,
+    status VARCHAR(50) NOT NULL DEFAULT 'pending',
+    subtotal DOUBLE NOT NULL,
+    shipping_fee DOUBLE NOT NULL DEFAULT 0,
+    total DOUBLE NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (vendor_id) REFERENCES users(id),
+    UNIQUE KEY order_vendor (order_id, vendor_id)
+);
+
+ALTER TABLE order_items
+    ADD COLUMN fulfillment_id VARCHAR(36) NULL,
+    ADD CONSTRAINT fk_order_items_fulfillment FOREIGN KEY (fulfillment_id) REFERENCES order_fulfillments(id);
+
+-- 为已有订单按商家补建履约记录
+INSERT INTO order_fulfillments (id, order_id, vendor_id, status, subtotal, shipping_fee, total, created_at, updated_at)
+SELECT UUID(), oi.order_id, p.vendor_id, o.status, SUM(oi.price * oi.quantity), 0, SUM(oi.price * oi.quantity), o.created_at, o.updated_at
+FROM order_items oi
+JOIN products p ON p.id = oi.product_id
+JOIN orders o ON o.id = oi.order_id
+GROUP BY oi.order_id, p.vendor_id, o.status, o.created_at, o.updated_at;
+
+UPDATE order_items oi
+JOIN products p ON p.id = oi.product_id
+JOIN order_fulfillments f ON f.order_id = oi.order_id AND f.vendor_id = p.vendor_id
+SET oi.fulfillment_id = f.id;
\ No newline at end of file
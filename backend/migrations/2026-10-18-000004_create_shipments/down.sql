@@ -0,0 +1,3 @@
+-- 删除shipment_items和shipments表
+DROP TABLE IF EXISTS shipment_items;
+DROP TABLE IF EXISTS shipments;
\ No newline at end of file
@@ -0,0 +1,28 @@
+-- 创建shipments表（商家发货记录：承运商、运单号及发货商品）
+CREATE TABLE IF NOT EXISTS shipments (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    fulfillment_id VARCHAR(36) NOT NULL,
+    vendor_id VARCHAR(36) NOT NULL,
+    carrier VARCHAR(50) NOT NULL,
+    tracking_number VARCHAR(100) NOT NULL,
+    status VARCHAR(50) NOT NULL DEFAULT 'shipped',
+    shipped_at TIMESTAMP NOT NULL,
+    delivered_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (fulfillment_id) REFERENCES order_fulfillments(id) ON DELETE CASCADE,
+    FOREIGN KEY (vendor_id) REFERENCES users(id),
+    UNIQUE KEY carrier_tracking_number (carrier, tracking_number)
+);
+
+-- 创建shipment_items表（每个包裹包含的订单项及数量）
+CREATE TABLE IF NOT EXISTS shipment_items (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    shipment_id VARCHAR(36) NOT NULL,
+    order_item_id VARCHAR(36) NOT NULL,
+    quantity INT NOT NULL,
+    FOREIGN KEY (shipment_id) REFERENCES shipments(id) ON DELETE CASCADE,
+    FOREIGN KEY (order_item_id) REFERENCES order_items(id) ON DELETE CASCADE
+);
\ No newline at end of file
@@ -0,0 +1,6 @@
+-- 删除订单号
+DROP TABLE IF EXISTS order_number_sequences;
+
+ALTER TABLE orders
+    DROP INDEX order_number,
+    DROP COLUMN order_number;
\ No newline at end of file
@@ -0,0 +1,28 @@
+-- 为orders表添加可读订单号，格式为 前缀+下单日期+当日序号，例如 ORD-20261017-000123
+ALTER TABLE orders ADD COLUMN order_number VARCHAR(50) NULL AFTER id;
+
+-- 按日期记录已分配的最大序号
+CREATE TABLE IF NOT EXISTS order_number_sequences (
+    sequence_date DATE NOT NULL PRIMARY KEY,
+    last_value BIGINT NOT NULL
+);
+
+-- 为已有订单按下单日期补全订单号
+UPDATE orders o
+JOIN (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY DATE(created_at) ORDER BY created_at, id) AS seq
+    FROM orders
+) numbered ON numbered.id = o.id
+SET o.order_number = CONCAT(
+    COALESCE((SELECT order_prefix FROM admin_profiles ORDER BY created_at LIMIT 1), 'ORD-'),
+    DATE_FORMAT(o.created_at, '%Y%m%d'),
+    '-',
+    LPAD(numbered.seq, 6, '0')
+);
+
+INSERT INTO order_number_sequences (sequence_date, last_value)
+SELECT DATE(created_at), COUNT(*) FROM orders GROUP BY DATE(created_at);
+
+ALTER TABLE orders
+    MODIFY order_number VARCHAR(50) NOT NULL,
+    ADD UNIQUE KEY order_number (order_number);
\ No newline at end of file
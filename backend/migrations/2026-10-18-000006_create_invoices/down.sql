@@ -0,0 +1,3 @@
+-- 删除invoices表
+DROP TABLE IF EXISTS invoice_number_sequences;
+DROP TABLE IF EXISTS invoices;
\ No newline at end of file
@@ -0,0 +1,17 @@
+-- 创建invoices表（每个订单最多开具一张发票）
+CREATE TABLE IF NOT EXISTS invoices (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    invoice_number VARCHAR(50) NOT NULL,
+    issued_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    UNIQUE KEY invoice_order (order_id),
+    UNIQUE KEY invoice_number (invoice_number)
+);
+
+-- 按年度记录已分配的最大发票序号
+CREATE TABLE IF NOT EXISTS invoice_number_sequences (
+    sequence_year INT NOT NULL PRIMARY KEY,
+    last_value BIGINT NOT NULL
+);
\ No newline at end of file
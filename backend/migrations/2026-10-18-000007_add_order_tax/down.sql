@@ -0,0 +1,15 @@
+-- 删除税务相关字段
+ALTER TABLE order_fulfillments DROP COLUMN tax;
+
+ALTER TABLE order_items
+    DROP COLUMN tax_rate,
+    DROP COLUMN tax_amount,
+    DROP COLUMN total;
+
+ALTER TABLE orders
+    DROP COLUMN subtotal,
+    DROP COLUMN tax;
+
+DROP TABLE IF EXISTS tax_rates;
+
+ALTER TABLE admin_profiles DROP COLUMN prices_include_tax;
\ No newline at end of file
@@ -0,0 +1,30 @@
+-- 商品价格是否含税
+ALTER TABLE admin_profiles ADD COLUMN prices_include_tax BOOLEAN NOT NULL DEFAULT FALSE AFTER log_level;
+
+-- 创建tax_rates表（按商品分类单独配置的税率，百分比）
+CREATE TABLE IF NOT EXISTS tax_rates (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    category VARCHAR(100) NOT NULL,
+    rate DOUBLE NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    UNIQUE KEY tax_rate_category (category)
+);
+
+-- 订单分别保存不含税金额、税额和价税合计（total）
+ALTER TABLE orders
+    ADD COLUMN subtotal DOUBLE NOT NULL DEFAULT 0 AFTER user_id,
+    ADD COLUMN tax DOUBLE NOT NULL DEFAULT 0 AFTER subtotal;
+
+UPDATE orders SET subtotal = total;
+
+-- 订单项保存适用税率、税额和含税金额
+ALTER TABLE order_items
+    ADD COLUMN tax_rate DOUBLE NOT NULL DEFAULT 0,
+    ADD COLUMN tax_amount DOUBLE NOT NULL DEFAULT 0,
+    ADD COLUMN total DOUBLE NOT NULL DEFAULT 0;
+
+UPDATE order_items SET total = price * quantity;
+
+-- 商家子订单保存税额
+ALTER TABLE order_fulfillments ADD COLUMN tax DOUBLE NOT NULL DEFAULT 0 AFTER subtotal;
\ No newline at end of file
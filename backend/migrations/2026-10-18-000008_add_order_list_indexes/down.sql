@@ -0,0 +1,3 @@
+DROP INDEX idx_orders_status_created_at ON orders;
+DROP INDEX idx_orders_total_id ON orders;
+DROP INDEX idx_orders_created_at_id ON orders;
\ No newline at end of file
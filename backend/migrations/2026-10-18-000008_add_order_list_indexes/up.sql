@@ -0,0 +1,4 @@
+-- 订单列表按创建时间、金额排序并用订单ID作为游标的第二排序键
+CREATE INDEX idx_orders_created_at_id ON orders (created_at, id);
+CREATE INDEX idx_orders_total_id ON orders (total, id);
+CREATE INDEX idx_orders_status_created_at ON orders (status, created_at);
\ No newline at end of file
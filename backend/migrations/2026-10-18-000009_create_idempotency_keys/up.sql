@@ -0,0 +1,16 @@
+-- 创建idempotency_keys表（记录带 Idempotency-Key 的下单请求指纹及其响应，用于重试时返回原订单）
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    endpoint VARCHAR(100) NOT NULL,
+    idempotency_key VARCHAR(255) NOT NULL,
+    request_fingerprint CHAR(64) NOT NULL,
+    order_id VARCHAR(36) NULL,
+    response_status INT NULL,
+    response_body MEDIUMTEXT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE SET NULL,
+    UNIQUE KEY user_endpoint_key (user_id, endpoint, idempotency_key)
+);
\ No newline at end of file
@@ -0,0 +1,12 @@
+-- 创建order_status_history表（订单状态变更记录，changed_by 为空表示系统自动变更）
+CREATE TABLE IF NOT EXISTS order_status_history (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    from_status VARCHAR(50) NULL,
+    to_status VARCHAR(50) NOT NULL,
+    reason VARCHAR(255) NULL,
+    changed_by VARCHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    INDEX order_history_order (order_id, created_at)
+);
\ No newline at end of file
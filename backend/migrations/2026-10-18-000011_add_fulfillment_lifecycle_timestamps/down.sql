@@ -0,0 +1,11 @@
+-- 删除商家子订单的履约时间字段
+UPDATE order_fulfillments SET status = 'delivered' WHERE status = 'completed';
+UPDATE orders SET status = 'delivered' WHERE status = 'completed';
+
+ALTER TABLE order_fulfillments
+    DROP INDEX idx_fulfillments_status_shipped_at,
+    DROP INDEX idx_fulfillments_status_return_window,
+    DROP COLUMN shipped_at,
+    DROP COLUMN delivered_at,
+    DROP COLUMN return_window_ends_at,
+    DROP COLUMN completed_at;
\ No newline at end of file
@@ -0,0 +1,14 @@
+-- 商家子订单记录发货、送达和完成时间，以及按商家退货政策计算的退货期截止时间
+ALTER TABLE order_fulfillments
+    ADD COLUMN shipped_at TIMESTAMP NULL AFTER total,
+    ADD COLUMN delivered_at TIMESTAMP NULL AFTER shipped_at,
+    ADD COLUMN return_window_ends_at TIMESTAMP NULL AFTER delivered_at,
+    ADD COLUMN completed_at TIMESTAMP NULL AFTER return_window_ends_at,
+    ADD INDEX idx_fulfillments_status_shipped_at (status, shipped_at),
+    ADD INDEX idx_fulfillments_status_return_window (status, return_window_ends_at);
+
+-- 已有数据以最后更新时间作为发货、送达时间，退货期按默认7天计算
+UPDATE order_fulfillments SET shipped_at = updated_at WHERE status IN ('shipped', 'delivered');
+UPDATE order_fulfillments
+SET delivered_at = updated_at, return_window_ends_at = DATE_ADD(updated_at, INTERVAL 7 DAY)
+WHERE status = 'delivered';
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- 删除订单留言相关表
+DROP TABLE IF EXISTS order_message_reads;
+DROP TABLE IF EXISTS order_message_attachments;
+DROP TABLE IF EXISTS order_messages;
\ No newline at end of file
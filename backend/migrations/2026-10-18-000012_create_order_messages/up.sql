@@ -0,0 +1,34 @@
+-- 创建order_messages表（订单留言，顾客、商家和管理员都可以在订单下发言）
+-- 时间精确到微秒，用于和已读位置比较
+CREATE TABLE IF NOT EXISTS order_messages (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    sender_id VARCHAR(36) NOT NULL,
+    sender_role VARCHAR(20) NOT NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMP(6) NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (sender_id) REFERENCES users(id),
+    INDEX order_messages_order (order_id, created_at)
+);
+
+-- 创建order_message_attachments表（留言附件，只保存文件地址）
+CREATE TABLE IF NOT EXISTS order_message_attachments (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    message_id VARCHAR(36) NOT NULL,
+    url VARCHAR(1024) NOT NULL,
+    file_name VARCHAR(255) NULL,
+    created_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (message_id) REFERENCES order_messages(id) ON DELETE CASCADE
+);
+
+-- 创建order_message_reads表（每个用户在每个订单留言中的已读位置）
+CREATE TABLE IF NOT EXISTS order_message_reads (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    user_id VARCHAR(36) NOT NULL,
+    last_read_at TIMESTAMP(6) NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE KEY order_message_reads_order_user (order_id, user_id)
+);
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- 删除游客结账相关字段
+ALTER TABLE orders DROP COLUMN contact_email;
+
+ALTER TABLE users DROP COLUMN is_guest;
\ No newline at end of file
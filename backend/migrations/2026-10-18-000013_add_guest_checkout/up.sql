@@ -0,0 +1,5 @@
+-- 游客结账：游客以临时用户身份下单，注册后转为正式用户
+ALTER TABLE users ADD COLUMN is_guest BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- 下单时填写的联系邮箱，游客凭订单号和该邮箱查询订单
+ALTER TABLE orders ADD COLUMN contact_email VARCHAR(255) NULL;
\ No newline at end of file
@@ -0,0 +1,6 @@
+-- 删除风控相关表和字段
+ALTER TABLE orders DROP COLUMN on_hold;
+
+DROP TABLE IF EXISTS order_risk_assessments;
+
+DROP TABLE IF EXISTS risk_rules;
\ No newline at end of file
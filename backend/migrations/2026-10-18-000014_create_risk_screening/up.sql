@@ -0,0 +1,54 @@
+-- 风控规则：每条规则可单独启用，threshold 为触发阈值（含义见各规则），score 为命中时累加的风险分
+CREATE TABLE IF NOT EXISTS risk_rules (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    rule VARCHAR(50) NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    threshold DOUBLE NOT NULL,
+    score INT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    UNIQUE KEY risk_rule_rule (rule)
+);
+
+-- 默认规则
+-- new_account：注册不满 threshold 小时
+-- first_order_value：首单金额超过 threshold
+-- order_value_vs_history：订单金额超过历史平均订单金额的 threshold 倍
+-- user_velocity：同一用户一小时内下单超过 threshold 次
+-- ip_velocity：同一 IP 一小时内下单超过 threshold 次
+-- address_mismatch：收货人和电话与地址簿中的地址均不一致
+INSERT INTO risk_rules (id, rule, enabled, threshold, score, created_at, updated_at) VALUES
+    (UUID(), 'new_account', TRUE, 24, 20, NOW(), NOW()),
+    (UUID(), 'first_order_value', TRUE, 3000, 30, NOW(), NOW()),
+    (UUID(), 'order_value_vs_history', TRUE, 5, 30, NOW(), NOW()),
+    (UUID(), 'user_velocity', TRUE, 3, 40, NOW(), NOW()),
+    (UUID(), 'ip_velocity', TRUE, 5, 40, NOW(), NOW()),
+    (UUID(), 'address_mismatch', TRUE, 0, 20, NOW(), NOW());
+
+-- 下单风控评估记录，每次结账一条；被拒绝的下单不会创建订单，order_id 为空
+-- review_status 仅待审核订单使用：Pending、Approved、Rejected
+CREATE TABLE IF NOT EXISTS order_risk_assessments (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    order_id VARCHAR(36) NULL,
+    user_id VARCHAR(36) NOT NULL,
+    client_ip VARCHAR(45) NULL,
+    order_total DOUBLE NOT NULL,
+    score INT NOT NULL,
+    decision VARCHAR(20) NOT NULL,
+    reasons TEXT NOT NULL,
+    review_status VARCHAR(20) NULL,
+    reviewed_by VARCHAR(36) NULL,
+    reviewed_at TIMESTAMP NULL,
+    review_note TEXT NULL,
+    created_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (reviewed_by) REFERENCES users(id) ON DELETE SET NULL,
+    UNIQUE KEY risk_assessment_order (order_id),
+    INDEX idx_risk_assessments_user_created_at (user_id, created_at),
+    INDEX idx_risk_assessments_ip_created_at (client_ip, created_at),
+    INDEX idx_risk_assessments_review_status (review_status, created_at)
+);
+
+-- 待风控审核的订单，审核通过前不能处理发货，也不会因超时未支付被自动取消
+ALTER TABLE orders ADD COLUMN on_hold BOOLEAN NOT NULL DEFAULT FALSE;
\ No newline at end of file
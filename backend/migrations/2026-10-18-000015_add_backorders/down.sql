@@ -0,0 +1,10 @@
+-- 删除缺货预订相关字段
+ALTER TABLE order_items
+    DROP INDEX idx_order_items_product_backordered,
+    DROP COLUMN backordered_quantity,
+    DROP COLUMN expected_ship_date;
+
+ALTER TABLE products
+    DROP COLUMN backorder_mode,
+    DROP COLUMN backorder_limit,
+    DROP COLUMN expected_ship_date;
\ No newline at end of file
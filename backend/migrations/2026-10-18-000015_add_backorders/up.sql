@@ -0,0 +1,14 @@
+-- 商品缺货预订设置
+-- backorder_mode：none（不允许超卖）、backorder（缺货预订）、preorder（新品预售）
+-- backorder_limit：允许预订的最大未到货数量，NULL 表示不限
+-- expected_ship_date：预计发货日期
+ALTER TABLE products
+    ADD COLUMN backorder_mode VARCHAR(20) NOT NULL DEFAULT 'none',
+    ADD COLUMN backorder_limit INT NULL,
+    ADD COLUMN expected_ship_date DATE NULL;
+
+-- 订单项中尚未分配到库存的数量及预计发货日期，到货后按下单先后顺序分配
+ALTER TABLE order_items
+    ADD COLUMN backordered_quantity INT NOT NULL DEFAULT 0,
+    ADD COLUMN expected_ship_date DATE NULL,
+    ADD INDEX idx_order_items_product_backordered (product_id, backordered_quantity);
\ No newline at end of file
@@ -0,0 +1,8 @@
+-- 删除定期订购及站内通知相关表
+DROP TABLE IF EXISTS notifications;
+
+DROP TABLE IF EXISTS subscription_runs;
+
+DROP TABLE IF EXISTS subscription_items;
+
+DROP TABLE IF EXISTS subscriptions;
\ No newline at end of file
@@ -0,0 +1,61 @@
+-- 定期订购：按 frequency（weekly、monthly）在 next_run_at 到期时根据保存的商品清单自动下单
+-- status：active（生效）、paused（暂停）、cancelled（已取消）
+-- address_id 为空或地址被删除时使用默认收货地址
+-- anchor_day：按月订购的下单日（1-31），当月没有该日时取月末，避免月末的下单日逐月前移
+CREATE TABLE IF NOT EXISTS subscriptions (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    frequency VARCHAR(20) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'active',
+    address_id VARCHAR(36) NULL,
+    next_run_at TIMESTAMP NOT NULL,
+    anchor_day INT NOT NULL DEFAULT 1,
+    last_run_at TIMESTAMP NULL,
+    last_order_id VARCHAR(36) NULL,
+    consecutive_failures INT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    FOREIGN KEY (address_id) REFERENCES addresses(id) ON DELETE SET NULL,
+    INDEX idx_subscriptions_user_id (user_id),
+    INDEX idx_subscriptions_status_next_run (status, next_run_at)
+);
+
+-- 定期订购的商品清单，商品删除后从清单中移除
+CREATE TABLE IF NOT EXISTS subscription_items (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    subscription_id VARCHAR(36) NOT NULL,
+    product_id VARCHAR(36) NOT NULL,
+    quantity INT NOT NULL,
+    FOREIGN KEY (subscription_id) REFERENCES subscriptions(id) ON DELETE CASCADE,
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE,
+    UNIQUE KEY subscription_product (subscription_id, product_id)
+);
+
+-- 每次执行的结果：succeeded（已下单）、failed（下单失败）、skipped（用户跳过）
+CREATE TABLE IF NOT EXISTS subscription_runs (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    subscription_id VARCHAR(36) NOT NULL,
+    scheduled_for TIMESTAMP NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    order_id VARCHAR(36) NULL,
+    failure_reason TEXT NULL,
+    created_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (subscription_id) REFERENCES subscriptions(id) ON DELETE CASCADE,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE SET NULL,
+    INDEX idx_subscription_runs_subscription_created_at (subscription_id, created_at)
+);
+
+-- 站内通知
+CREATE TABLE IF NOT EXISTS notifications (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    kind VARCHAR(50) NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    content TEXT NOT NULL,
+    related_id VARCHAR(36) NULL,
+    read_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_notifications_user_created_at (user_id, created_at)
+);
\ No newline at end of file
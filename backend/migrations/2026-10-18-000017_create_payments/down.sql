@@ -0,0 +1,2 @@
+-- 删除支付记录表
+DROP TABLE IF EXISTS payments;
\ No newline at end of file
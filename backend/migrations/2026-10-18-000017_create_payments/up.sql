@@ -0,0 +1,24 @@
+-- 支付记录：每次发起支付创建一条，gateway 为支付渠道代码，gateway_payment_id 为渠道侧的支付单号
+-- status：pending（待支付）、succeeded（已支付）、failed（支付失败）、refunded（已全额退款）
+-- 订单只有在收到验签通过的支付成功回调后才进入 processing 状态
+CREATE TABLE IF NOT EXISTS payments (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    user_id VARCHAR(36) NOT NULL,
+    gateway VARCHAR(30) NOT NULL,
+    gateway_payment_id VARCHAR(100) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    amount DOUBLE NOT NULL,
+    currency VARCHAR(10) NOT NULL DEFAULT 'CNY',
+    payment_url VARCHAR(500) NULL,
+    refunded_amount DOUBLE NOT NULL DEFAULT 0,
+    failure_reason TEXT NULL,
+    paid_at TIMESTAMP NULL,
+    refunded_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    UNIQUE KEY gateway_payment (gateway, gateway_payment_id),
+    INDEX idx_payments_order_id (order_id)
+);
\ No newline at end of file
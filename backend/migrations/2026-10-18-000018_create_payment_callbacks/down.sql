@@ -0,0 +1,4 @@
+-- 删除支付回调记录表及实际支付金额
+ALTER TABLE payments DROP COLUMN paid_amount;
+
+DROP TABLE IF EXISTS payment_callbacks;
\ No newline at end of file
@@ -0,0 +1,19 @@
+-- 支付回调记录：每条验签通过的渠道通知记录一次，(gateway, notification_id) 唯一，用于识别重放的通知
+-- outcome：paid（支付成功）、failed（支付失败）、refund_required（订单已取消或已支付，需退款）、mismatched（金额不符）、unchanged（未改变状态）
+CREATE TABLE IF NOT EXISTS payment_callbacks (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    payment_id VARCHAR(36) NOT NULL,
+    gateway VARCHAR(30) NOT NULL,
+    notification_id VARCHAR(100) NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    amount DOUBLE NOT NULL,
+    sent_at TIMESTAMP NOT NULL,
+    outcome VARCHAR(20) NOT NULL,
+    received_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (payment_id) REFERENCES payments(id) ON DELETE CASCADE,
+    UNIQUE KEY gateway_notification (gateway, notification_id),
+    INDEX idx_payment_callbacks_payment_id (payment_id)
+);
+
+-- 渠道通知的实际支付金额，金额与订单不符时支付状态为 mismatched
+ALTER TABLE payments ADD COLUMN paid_amount DOUBLE NULL AFTER amount;
\ No newline at end of file
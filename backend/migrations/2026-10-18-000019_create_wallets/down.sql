@@ -0,0 +1,6 @@
+-- 删除钱包相关表及订单的钱包支付金额
+ALTER TABLE orders DROP COLUMN wallet_amount;
+
+DROP TABLE IF EXISTS wallet_transactions;
+
+DROP TABLE IF EXISTS wallets;
\ No newline at end of file
@@ -0,0 +1,28 @@
+-- 用户钱包：balance 为当前余额，只能通过记账流水变动
+CREATE TABLE IF NOT EXISTS wallets (
+    user_id VARCHAR(36) NOT NULL PRIMARY KEY,
+    balance DOUBLE NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- 钱包流水：只追加不修改，amount 入账为正、出账为负，balance_after 为记账后的余额
+-- kind：admin_credit（管理员入账）、admin_debit（管理员扣减）、order_payment（下单抵扣）、order_refund（订单退款）
+CREATE TABLE IF NOT EXISTS wallet_transactions (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    kind VARCHAR(30) NOT NULL,
+    amount DOUBLE NOT NULL,
+    balance_after DOUBLE NOT NULL,
+    reason TEXT NULL,
+    order_id VARCHAR(36) NULL,
+    created_by VARCHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES wallets(user_id) ON DELETE CASCADE,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE SET NULL,
+    INDEX idx_wallet_transactions_user_created_at (user_id, created_at)
+);
+
+-- 订单中使用钱包余额支付的金额，其余部分通过支付渠道支付
+ALTER TABLE orders ADD COLUMN wallet_amount DOUBLE NOT NULL DEFAULT 0;
\ No newline at end of file
@@ -0,0 +1,10 @@
+-- 删除优惠券相关表及订单的优惠金额
+ALTER TABLE order_items DROP COLUMN discount;
+ALTER TABLE orders DROP COLUMN coupon_code;
+ALTER TABLE orders DROP COLUMN discount;
+
+DROP TABLE IF EXISTS cart_coupons;
+
+DROP TABLE IF EXISTS coupon_redemptions;
+
+DROP TABLE IF EXISTS coupons;
\ No newline at end of file
@@ -0,0 +1,51 @@
+-- 优惠券：discount_type 为 percentage（按百分比）或 fixed（固定金额），min_spend 按适用商品的金额计算
+-- usage_limit 为总使用次数上限，per_user_limit 为每个用户的使用次数上限，为空表示不限
+-- product_ids、categories、vendor_ids 为逗号分隔的适用范围，为空表示不限，同时设置时商品需全部满足
+CREATE TABLE IF NOT EXISTS coupons (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    code VARCHAR(50) NOT NULL UNIQUE,
+    discount_type VARCHAR(20) NOT NULL,
+    discount_value DOUBLE NOT NULL,
+    min_spend DOUBLE NOT NULL DEFAULT 0,
+    starts_at TIMESTAMP NULL,
+    ends_at TIMESTAMP NULL,
+    usage_limit INT NULL,
+    per_user_limit INT NULL,
+    used_count INT NOT NULL DEFAULT 0,
+    product_ids TEXT NULL,
+    categories TEXT NULL,
+    vendor_ids TEXT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by VARCHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+-- 优惠券使用记录，订单取消时删除并退回使用次数
+CREATE TABLE IF NOT EXISTS coupon_redemptions (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    coupon_id VARCHAR(36) NOT NULL,
+    user_id VARCHAR(36) NOT NULL,
+    order_id VARCHAR(36) NOT NULL,
+    discount DOUBLE NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (coupon_id) REFERENCES coupons(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    INDEX idx_coupon_redemptions_coupon_user (coupon_id, user_id),
+    INDEX idx_coupon_redemptions_order_id (order_id)
+);
+
+-- 购物车中已使用的优惠券，每个购物车最多一张
+CREATE TABLE IF NOT EXISTS cart_coupons (
+    user_id VARCHAR(36) NOT NULL PRIMARY KEY,
+    coupon_id VARCHAR(36) NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (coupon_id) REFERENCES coupons(id) ON DELETE CASCADE
+);
+
+-- 订单和订单项按商品标价计算的优惠金额，用于对账和报表
+ALTER TABLE orders ADD COLUMN discount DOUBLE NOT NULL DEFAULT 0;
+ALTER TABLE orders ADD COLUMN coupon_code VARCHAR(50) NULL;
+ALTER TABLE order_items ADD COLUMN discount DOUBLE NOT NULL DEFAULT 0;
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- 删除自动促销相关表
+DROP TABLE IF EXISTS order_item_promotions;
+
+DROP TABLE IF EXISTS promotions;
\ No newline at end of file
@@ -0,0 +1,45 @@
+-- 自动促销：kind 为 tiered（满减）、buy_x_get_y（买 X 送 Y）或 percentage_off（百分比折扣）
+-- tiers 为满减档位，格式为 "门槛:优惠金额"，多个档位逗号分隔，例如 "300:30,500:60"
+-- priority 越大越先计算；stackable 为 false 时享受该促销的商品不再参与满减和优惠券
+-- product_ids、categories、vendor_ids 为逗号分隔的适用范围，为空表示不限
+-- funded_by 为出资商家，设置后只适用于该商家的商品
+CREATE TABLE IF NOT EXISTS promotions (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    kind VARCHAR(20) NOT NULL,
+    priority INT NOT NULL DEFAULT 0,
+    stackable BOOLEAN NOT NULL DEFAULT TRUE,
+    tiers TEXT NULL,
+    buy_quantity INT NULL,
+    free_quantity INT NULL,
+    percentage DOUBLE NULL,
+    product_ids TEXT NULL,
+    categories TEXT NULL,
+    vendor_ids TEXT NULL,
+    funded_by VARCHAR(36) NULL,
+    starts_at TIMESTAMP NULL,
+    ends_at TIMESTAMP NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by VARCHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (funded_by) REFERENCES users(id),
+    INDEX idx_promotions_active (active)
+);
+
+-- 订单项享受的促销快照，用于对账和按出资方统计优惠
+CREATE TABLE IF NOT EXISTS order_item_promotions (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    order_item_id VARCHAR(36) NOT NULL,
+    promotion_id VARCHAR(36) NOT NULL,
+    promotion_name VARCHAR(100) NOT NULL,
+    kind VARCHAR(20) NOT NULL,
+    discount DOUBLE NOT NULL,
+    funded_by VARCHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (order_item_id) REFERENCES order_items(id) ON DELETE CASCADE,
+    INDEX idx_order_item_promotions_order_id (order_id),
+    INDEX idx_order_item_promotions_promotion_id (promotion_id)
+);
\ No newline at end of file
@@ -0,0 +1,332 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::mysql::MysqlConnection;
+use serde_json::json;
+
+use crate::middleware::get_user_id_from_request;
+use crate::models::address::{Address, NewAddress, UpdateAddress, normalize_postal_code, CreateAddressDto, UpdateAddressDto};
+use crate::schema::addresses;
+use crate::utils::validators::validate_shipping_address;
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+// 取消用户其他地址的默认标记，保证每个用户最多一个默认收货/账单地址
+fn clear_default_flags(
+    conn: &mut MysqlConnection,
+    user_id: &str,
+    except_id: &str,
+    shipping: bool,
+    billing: bool,
+) -> Result<(), diesel::result::Error> {
+    if shipping {
+        diesel::update(addresses::table
+            .filter(addresses::user_id.eq(user_id))
+            .filter(addresses::id.ne(except_id)))
+            .set(addresses::is_default_shipping.eq(false))
+            .execute(conn)?;
+    }
+
+    if billing {
+        diesel::update(addresses::table
+            .filter(addresses::user_id.eq(user_id))
+            .filter(addresses::id.ne(except_id)))
+            .set(addresses::is_default_billing.eq(false))
+            .execute(conn)?;
+    }
+
+    Ok(())
+}
+
+// 获取用户的默认收货地址
+pub fn find_default_shipping_address(conn: &mut MysqlConnection, user_id: &str) -> Option<Address> {
+    addresses::table
+        .filter(addresses::user_id.eq(user_id))
+        .filter(addresses::is_default_shipping.eq(true))
+        .select(Address::as_select())
+        .first(conn)
+        .ok()
+}
+
+// 获取地址列表
+pub async fn get_addresses(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    match addresses::table
+        .filter(addresses::user_id.eq(&user_id))
+        .order((addresses::is_default_shipping.desc(), addresses::created_at.desc()))
+        .select(Address::as_select())
+        .load(&mut conn) {
+        Ok(items) => HttpResponse::Ok().json(json!({
+            "addresses": items
+        })),
+        Err(e) => {
+            println!("读取地址列表失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "读取地址列表失败"
+            }))
+        }
+    }
+}
+
+// 新增地址
+pub async fn create_address(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    address_dto: web::Json<CreateAddressDto>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    if let Err(errors) = validate_shipping_address(
+        &address_dto.recipient_name,
+        &address_dto.phone,
+        &address_dto.street,
+        address_dto.postal_code.as_deref(),
+    ) {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "地址验证失败",
+            "errors": errors
+        }));
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let mut new_address = NewAddress::new(user_id.clone(), &address_dto);
+
+    // 第一个地址自动成为默认收货和账单地址
+    let has_addresses = addresses::table
+        .filter(addresses::user_id.eq(&user_id))
+        .select(addresses::id)
+        .first::<String>(&mut conn)
+        .is_ok();
+
+    if !has_addresses {
+        new_address.is_default_shipping = true;
+        new_address.is_default_billing = true;
+    }
+
+    let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        clear_default_flags(
+            conn,
+            &user_id,
+            &new_address.id,
+            new_address.is_default_shipping,
+            new_address.is_default_billing,
+        )?;
+
+        diesel::insert_into(addresses::table)
+            .values(&new_address)
+            .execute(conn)?;
+
+        addresses::table
+            .find(&new_address.id)
+            .select(Address::as_select())
+            .first(conn)
+    });
+
+    match result {
+        Ok(address) => HttpResponse::Created().json(json!({
+            "message": "地址已添加",
+            "address": address
+        })),
+        Err(e) => {
+            println!("添加地址失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "添加地址失败"
+            }))
+        }
+    }
+}
+
+// 修改地址
+pub async fn update_address(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    address_dto: web::Json<UpdateAddressDto>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let address_id = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let existing = match addresses::table
+        .find(&address_id)
+        .filter(addresses::user_id.eq(&user_id))
+        .select(Address::as_select())
+        .first(&mut conn) {
+        Ok(address) => address,
+        Err(_) => return HttpResponse::NotFound().json(json!({
+            "message": "地址不存在或不属于当前用户"
+        })),
+    };
+
+    // 以修改后的完整地址进行校验
+    let recipient_name = address_dto.recipient_name.clone().unwrap_or(existing.recipient_name);
+    let phone = address_dto.phone.clone().unwrap_or(existing.phone);
+    let street = address_dto.street.clone().unwrap_or(existing.street);
+    let postal_code_change = address_dto.postal_code.clone().map(normalize_postal_code);
+    let postal_code = postal_code_change.clone().unwrap_or(existing.postal_code);
+
+    if let Err(errors) = validate_shipping_address(&recipient_name, &phone, &street, postal_code.as_deref()) {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "地址验证失败",
+            "errors": errors
+        }));
+    }
+
+    let changes = UpdateAddress {
+        recipient_name: address_dto.recipient_name.as_ref().map(|name| name.trim().to_string()),
+        phone: address_dto.phone.as_ref().map(|phone| phone.trim().to_string()),
+        province: address_dto.province.clone(),
+        city: address_dto.city.clone(),
+        district: address_dto.district.clone(),
+        street: address_dto.street.as_ref().map(|street| street.trim().to_string()),
+        postal_code: postal_code_change,
+        is_default_shipping: address_dto.is_default_shipping,
+        is_default_billing: address_dto.is_default_billing,
+        updated_at: chrono::Utc::now().naive_utc(),
+    };
+
+    let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        clear_default_flags(
+            conn,
+            &user_id,
+            &address_id,
+            changes.is_default_shipping == Some(true),
+            changes.is_default_billing == Some(true),
+        )?;
+
+        diesel::update(addresses::table.find(&address_id))
+            .set(&changes)
+            .execute(conn)?;
+
+        addresses::table
+            .find(&address_id)
+            .select(Address::as_select())
+            .first(conn)
+    });
+
+    match result {
+        Ok(address) => HttpResponse::Ok().json(json!({
+            "message": "地址已更新",
+            "address": address
+        })),
+        Err(e) => {
+            println!("更新地址失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "更新地址失败"
+            }))
+        }
+    }
+}
+
+// 删除地址
+pub async fn delete_address(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let address_id = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let existing = match addresses::table
+        .find(&address_id)
+        .filter(addresses::user_id.eq(&user_id))
+        .select(Address::as_select())
+        .first(&mut conn) {
+        Ok(address) => address,
+        Err(_) => return HttpResponse::NotFound().json(json!({
+            "message": "地址不存在或不属于当前用户"
+        })),
+    };
+
+    let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::delete(addresses::table.find(&address_id))
+            .execute(conn)?;
+
+        // 删除的是默认地址时，由最近添加的地址接替
+        if existing.is_default_shipping || existing.is_default_billing {
+            let replacement = addresses::table
+                .filter(addresses::user_id.eq(&user_id))
+                .order(addresses::created_at.desc())
+                .select(addresses::id)
+                .first::<String>(conn)
+                .optional()?;
+
+            if let Some(replacement_id) = replacement {
+                if existing.is_default_shipping {
+                    diesel::update(addresses::table.find(&replacement_id))
+                        .set(addresses::is_default_shipping.eq(true))
+                        .execute(conn)?;
+                }
+                if existing.is_default_billing {
+                    diesel::update(addresses::table.find(&replacement_id))
+                        .set(addresses::is_default_billing.eq(true))
+                        .execute(conn)?;
+                }
+            }
+        }
+
+        Ok(())
+    });
+
+    match result {
+        Ok(_) => HttpResponse::Ok().json(json!({
+            "message": "地址已删除"
+        })),
+        Err(e) => {
+            println!("删除地址失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "删除地址失败"
+            }))
+        }
+    }
+}
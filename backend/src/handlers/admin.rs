@@ -92,6 +92,7 @@ pub async fn get_admin_settings(
                 tax_rate: profile.tax_rate,
                 payment_gateways: profile.payment_gateways.split(',').map(|s| s.to_string()).collect(),
                 log_level: profile.log_level,
+                prices_include_tax: profile.prices_include_tax,
             };
             
             println!("管理员设置获取成功");
@@ -123,6 +124,7 @@ pub async fn get_admin_settings(
                                 tax_rate: new_profile.tax_rate,
                                 payment_gateways: new_profile.payment_gateways.split(',').map(|s| s.to_string()).collect(),
                                 log_level: new_profile.log_level,
+                                prices_include_tax: new_profile.prices_include_tax,
                             };
                             
                             println!("已创建默认管理员设置");
@@ -232,6 +234,7 @@ pub async fn update_admin_settings(
         tax_rate: settings.tax_rate,
         payment_gateways,
         log_level: settings.log_level.clone(),
+        prices_include_tax: settings.prices_include_tax,
         updated_at: chrono::Utc::now().naive_utc(),
     };
     
@@ -268,6 +271,7 @@ pub async fn update_admin_settings(
                                 tax_rate: updated_profile.tax_rate,
                                 payment_gateways: updated_profile.payment_gateways.split(',').map(|s| s.to_string()).collect(),
                                 log_level: updated_profile.log_level,
+                                prices_include_tax: updated_profile.prices_include_tax,
                             };
                             
                             HttpResponse::Ok().json(response)
@@ -324,6 +328,7 @@ pub async fn update_admin_settings(
                                                 tax_rate: updated_profile.tax_rate,
                                                 payment_gateways: updated_profile.payment_gateways.split(',').map(|s| s.to_string()).collect(),
                                                 log_level: updated_profile.log_level,
+                                                prices_include_tax: updated_profile.prices_include_tax,
                                             };
                                             
                                             HttpResponse::Ok().json(response)
@@ -194,6 +194,14 @@ pub async fn login(login_dto: web::Json<LoginDto>, pool: web::Data<DbPool>) -> i
         },
     };
 
+    // 游客临时用户没有密码，不能登录
+    if user.is_guest {
+        println!("拒绝游客用户登录: {}", user.id);
+        return HttpResponse::Unauthorized().json(json!({
+            "message": "电子邮件或密码无效"
+        }));
+    }
+
     // 增强的测试账户处理逻辑
     // 检查是否是数据库初始化脚本中的账户，使用"邮箱前缀+123"密码格式
     let email_prefix = user.email.split('@').next().unwrap_or("");
@@ -3,12 +3,20 @@ use diesel::prelude::*;
 use diesel::r2d2::{ConnectionManager, Pool};
 use diesel::mysql::MysqlConnection;
 use serde_json::json;
+use std::collections::HashMap;
 use uuid::Uuid;
 
-use crate::models::cart::{CartItem, NewCartItem, AddToCartDto, UpdateCartItemDto, CartResponse, CartItemWithProductResponse};
+use crate::models::cart::{CartItem, NewCartItem, AddToCartDto, UpdateCartItemDto, CartResponse, CartItemWithProductResponse, CartCouponResponse, plan_reorder};
+use crate::models::coupon::ApplyCouponDto;
 use crate::models::product::Product;
+use crate::models::order::CheckoutDto;
+use crate::handlers::checkout::{place_order, OrderSource};
+use crate::handlers::order::find_accessible_order;
 use crate::middleware::get_user_id_from_request;
-use crate::schema::{cart_items, products};
+use crate::schema::{cart_items, cart_coupons, order_items, products};
+use crate::services::coupon::{cart_coupon, cart_discounts, find_by_code, CouponError};
+use crate::services::promotion::{apply_promotions, load_active};
+use crate::services::tax::round_currency;
 use crate::models::user::UserRole;
 use crate::config::jwt::Claims;
 
@@ -42,23 +50,33 @@ pub async fn get_cart(
         })),
     };
 
-    // 获取用户购物车物品
-    let cart_items_result = cart_items::table
-        .filter(cart_items::user_id.eq(&user_id))
-        .select(CartItem::as_select())
-        .load(&mut conn);
+    match build_cart_response(&mut conn, &user_id) {
+        Ok(cart) => HttpResponse::Ok().json(cart),
+        Err(e) => {
+            println!("读取购物车失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "读取购物车失败"
+            }))
+        }
+    }
+}
 
-    let cart_items = match cart_items_result {
-        Ok(items) => items,
-        Err(_) => return HttpResponse::InternalServerError().json(json!({
-            "message": "读取购物车失败"
-        })),
-    };
+// 计算购物车内容和金额：先计算自动促销，优惠券按促销后的金额计算；
+// 优惠券当前不可用时不计优惠，并在 coupon.error 中说明原因
+fn build_cart_response(conn: &mut MysqlConnection, user_id: &str) -> QueryResult<CartResponse> {
+    let cart_items = cart_items::table
+        .filter(cart_items::user_id.eq(user_id))
+        .select(CartItem::as_select())
+        .load(conn)?;
 
     if cart_items.is_empty() {
-        return HttpResponse::Ok().json(CartResponse {
+        return Ok(CartResponse {
             items: vec![],
+            subtotal: 0.0,
+            promotion_discount: 0.0,
+            discount: 0.0,
             total: 0.0,
+            coupon: None,
         });
     }
 
@@ -68,44 +86,224 @@ pub async fn get_cart(
         .collect();
 
     // 查询相关产品
-    let products_result = products::table
+    let products = products::table
         .filter(products::id.eq_any(&product_ids))
         .select(Product::as_select())
-        .load(&mut conn);
+        .load(conn)?;
 
-    let products = match products_result {
-        Ok(p) => p,
-        Err(_) => return HttpResponse::InternalServerError().json(json!({
-            "message": "读取产品信息失败"
-        })),
+    let lines: Vec<(CartItem, &Product)> = cart_items.into_iter()
+        .filter_map(|item| {
+            let product = products.iter().find(|p| p.id == item.product_id)?;
+            Some((item, product))
+        })
+        .collect();
+
+    // 计算优惠
+    let priced: Vec<(&Product, i32)> = lines.iter().map(|(item, product)| (*product, item.quantity)).collect();
+    let mut pricing = apply_promotions(&load_active(conn)?, &priced, chrono::Utc::now().naive_utc());
+    let remaining: Vec<(&Product, f64)> = priced.iter()
+        .zip(&pricing)
+        .map(|((product, _), line)| (*product, line.remaining()))
+        .collect();
+    let coupon = match cart_coupon(conn, user_id)? {
+        Some(coupon) => match cart_discounts(conn, &coupon, user_id, &remaining) {
+            Ok(discounts) => {
+                for (line, discount) in pricing.iter_mut().zip(discounts) {
+                    line.coupon_discount = discount;
+                }
+                let discount = round_currency(pricing.iter().map(|line| line.coupon_discount).sum());
+                Some(CartCouponResponse { code: coupon.code, discount, error: None })
+            }
+            Err(CouponError::Database(e)) => return Err(e),
+            Err(e) => Some(CartCouponResponse { code: coupon.code, discount: 0.0, error: Some(e.to_string()) }),
+        },
+        None => None,
     };
 
     // 创建购物车响应
     let mut cart_response_items: Vec<CartItemWithProductResponse> = Vec::new();
-    let mut total: f64 = 0.0;
-
-    for cart_item in cart_items {
-        if let Some(product) = products.iter().find(|p| p.id == cart_item.product_id) {
-            let subtotal = product.price * cart_item.quantity as f64;
-            total += subtotal;
-
-            cart_response_items.push(CartItemWithProductResponse {
-                id: cart_item.id,
-                product_id: product.id.clone(),
-                product_name: product.name.clone(),
-                product_price: product.price,
-                quantity: cart_item.quantity,
-                subtotal,
-            });
-        }
+    let mut subtotal: f64 = 0.0;
+    let mut promotion_discount: f64 = 0.0;
+    let mut discount: f64 = 0.0;
+
+    for ((cart_item, product), line) in lines.into_iter().zip(pricing) {
+        subtotal = round_currency(subtotal + line.amount);
+        promotion_discount = round_currency(promotion_discount + line.promotion_discount());
+        discount = round_currency(discount + line.discount());
+
+        cart_response_items.push(CartItemWithProductResponse {
+            id: cart_item.id,
+            product_id: product.id.clone(),
+            product_name: product.name.clone(),
+            product_price: product.price,
+            quantity: cart_item.quantity,
+            subtotal: line.amount,
+            discount: line.discount(),
+            promotions: line.promotions,
+        });
     }
 
-    HttpResponse::Ok().json(CartResponse {
+    Ok(CartResponse {
         items: cart_response_items,
-        total,
+        subtotal,
+        promotion_discount,
+        discount,
+        total: round_currency(subtotal - discount),
+        coupon,
     })
 }
 
+// 购物车使用优惠券，替换已使用的优惠券；优惠券需对当前购物车可用
+pub async fn apply_coupon(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    coupon_dto: web::Json<ApplyCouponDto>,
+) -> impl Responder {
+    // 检查用户角色，禁止管理员访问购物车功能
+    if let Some(claims) = req.extensions().get::<Claims>() {
+        if claims.role == UserRole::Admin {
+            return HttpResponse::Forbidden().json(json!({
+                "message": "管理员不能使用购物车功能"
+            }));
+        }
+    }
+
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let coupon = match find_by_code(&mut conn, &coupon_dto.code) {
+        Ok(coupon) => coupon,
+        Err(CouponError::NotFound) => return HttpResponse::NotFound().json(json!({
+            "message": "优惠券不存在"
+        })),
+        Err(e) => {
+            println!("读取优惠券失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "读取优惠券失败"
+            }));
+        }
+    };
+
+    let cart_lines = cart_items::table
+        .inner_join(products::table)
+        .filter(cart_items::user_id.eq(&user_id))
+        .select((Product::as_select(), cart_items::quantity))
+        .load::<(Product, i32)>(&mut conn);
+    let cart_lines = match cart_lines {
+        Ok(lines) if lines.is_empty() => return HttpResponse::BadRequest().json(json!({
+            "message": "购物车为空，无法使用优惠券"
+        })),
+        Ok(lines) => lines,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "读取购物车失败"
+        })),
+    };
+
+    // 优惠券按自动促销后的金额计算
+    let promotions = match load_active(&mut conn) {
+        Ok(promotions) => promotions,
+        Err(e) => {
+            println!("读取促销活动失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "读取促销活动失败"
+            }));
+        }
+    };
+    let priced: Vec<(&Product, i32)> = cart_lines.iter().map(|(product, quantity)| (product, *quantity)).collect();
+    let remaining: Vec<(&Product, f64)> = priced.iter()
+        .zip(apply_promotions(&promotions, &priced, chrono::Utc::now().naive_utc()))
+        .map(|((product, _), line)| (*product, line.remaining()))
+        .collect();
+    match cart_discounts(&mut conn, &coupon, &user_id, &remaining) {
+        Ok(_) => {}
+        Err(CouponError::Database(e)) => {
+            println!("检查优惠券失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "读取优惠券失败"
+            }));
+        }
+        Err(e) => return HttpResponse::BadRequest().json(json!({
+            "message": e.to_string()
+        })),
+    }
+
+    let saved = diesel::replace_into(cart_coupons::table)
+        .values((
+            cart_coupons::user_id.eq(&user_id),
+            cart_coupons::coupon_id.eq(&coupon.id),
+            cart_coupons::created_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .execute(&mut conn);
+    if let Err(e) = saved {
+        println!("保存购物车优惠券失败: {:?}", e);
+        return HttpResponse::InternalServerError().json(json!({
+            "message": "使用优惠券失败"
+        }));
+    }
+
+    match build_cart_response(&mut conn, &user_id) {
+        Ok(cart) => HttpResponse::Ok().json(json!({
+            "message": "优惠券已使用",
+            "cart": cart
+        })),
+        Err(_) => HttpResponse::InternalServerError().json(json!({
+            "message": "读取购物车失败"
+        })),
+    }
+}
+
+// 取消购物车中已使用的优惠券
+pub async fn remove_coupon(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+) -> impl Responder {
+    // 检查用户角色，禁止管理员访问购物车功能
+    if let Some(claims) = req.extensions().get::<Claims>() {
+        if claims.role == UserRole::Admin {
+            return HttpResponse::Forbidden().json(json!({
+                "message": "管理员不能使用购物车功能"
+            }));
+        }
+    }
+
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    match diesel::delete(cart_coupons::table.find(&user_id)).execute(&mut conn) {
+        Ok(0) => HttpResponse::NotFound().json(json!({
+            "message": "购物车未使用优惠券"
+        })),
+        Ok(_) => HttpResponse::Ok().json(json!({
+            "message": "已取消使用优惠券"
+        })),
+        Err(_) => HttpResponse::InternalServerError().json(json!({
+            "message": "取消优惠券失败"
+        })),
+    }
+}
+
 // 添加到购物车
 pub async fn add_to_cart(
     req: HttpRequest,
@@ -397,6 +595,7 @@ pub async fn remove_from_cart(
 pub async fn checkout(
     req: HttpRequest,
     pool: web::Data<DbPool>,
+    body: web::Bytes,
 ) -> impl Responder {
     // 检查用户角色，禁止管理员访问购物车功能
     if let Some(claims) = req.extensions().get::<Claims>() {
@@ -414,6 +613,15 @@ pub async fn checkout(
         })),
     };
 
+    // 未提交请求体时使用默认地址；请求体格式错误时不能下单
+    let checkout_dto = match CheckoutDto::from_body(&body) {
+        Ok(dto) => dto,
+        Err(e) => return HttpResponse::BadRequest().json(json!({
+            "message": format!("结账请求格式错误: {}", e)
+        })),
+    };
+    let request_body = serde_json::to_string(&checkout_dto).unwrap_or_default();
+
     let mut conn = match pool.get() {
         Ok(conn) => conn,
         Err(_) => return HttpResponse::InternalServerError().json(json!({
@@ -421,191 +629,138 @@ pub async fn checkout(
         })),
     };
 
-    // 1. 获取所有购物车项目
-    let cart_items_result = cart_items::table
-        .filter(cart_items::user_id.eq(&user_id))
-        .select(CartItem::as_select())
-        .load(&mut conn);
+    place_order(&req, &mut conn, &user_id, OrderSource::Cart, checkout_dto, &request_body).await
+}
+
+// 再次购买：把历史订单中的商品按当前价格加入购物车，与购物车中已有的相同商品合并数量
+pub async fn reorder(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+) -> impl Responder {
+    // 检查用户角色，禁止管理员访问购物车功能
+    if let Some(claims) = req.extensions().get::<Claims>() {
+        if claims.role == UserRole::Admin {
+            return HttpResponse::Forbidden().json(json!({
+                "message": "管理员不能使用购物车功能"
+            }));
+        }
+    }
+
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let order_ref = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    // 只能再次购买自己下的订单
+    let order = match find_accessible_order(&mut conn, &order_ref, &user_id, &UserRole::Customer) {
+        Ok(order) => order,
+        Err(response) => return response,
+    };
 
-    let cart_items = match cart_items_result {
+    // 原订单商品，同一商品合并数量并保持下单顺序
+    let items = match order_items::table
+        .filter(order_items::order_id.eq(&order.id))
+        .select((order_items::product_id, order_items::quantity))
+        .load::<(String, i32)>(&mut conn) {
         Ok(items) => items,
         Err(_) => return HttpResponse::InternalServerError().json(json!({
-            "message": "读取购物车失败"
+            "message": "读取订单项目失败"
         })),
     };
 
-    if cart_items.is_empty() {
-        return HttpResponse::BadRequest().json(json!({
-            "message": "购物车为空，无法结账"
-        }));
+    let mut lines: Vec<(String, i32)> = Vec::new();
+    for (product_id, quantity) in items {
+        match lines.iter_mut().find(|(id, _)| *id == product_id) {
+            Some((_, total)) => *total += quantity,
+            None => lines.push((product_id, quantity)),
+        }
     }
 
-    // 获取所有产品ID
-    let product_ids: Vec<String> = cart_items.iter()
-        .map(|item| item.product_id.clone())
-        .collect();
-
-    // 查询相关产品
-    let products_result = products::table
+    let product_ids: Vec<String> = lines.iter().map(|(id, _)| id.clone()).collect();
+    let current_products = match products::table
         .filter(products::id.eq_any(&product_ids))
         .select(Product::as_select())
-        .load(&mut conn);
-
-    let products = match products_result {
-        Ok(p) => p,
+        .load(&mut conn) {
+        Ok(products) => products,
         Err(_) => return HttpResponse::InternalServerError().json(json!({
             "message": "读取产品信息失败"
         })),
     };
 
-    // 2. 检查库存
-    let mut unavailable_products = Vec::new();
-    for cart_item in &cart_items {
-        if let Some(product) = products.iter().find(|p| p.id == cart_item.product_id) {
-            if product.stock < cart_item.quantity {
-                unavailable_products.push(json!({
-                    "product_id": product.id,
-                    "product_name": product.name,
-                    "requested_quantity": cart_item.quantity,
-                    "available_stock": product.stock
-                }));
-            }
-        } else {
-            unavailable_products.push(json!({
-                "product_id": cart_item.product_id,
-                "message": "产品不存在"
-            }));
-        }
-    }
+    let cart_quantities: HashMap<String, i32> = match cart_items::table
+        .filter(cart_items::user_id.eq(&user_id))
+        .filter(cart_items::product_id.eq_any(&product_ids))
+        .select((cart_items::product_id, cart_items::quantity))
+        .load::<(String, i32)>(&mut conn) {
+        Ok(items) => items.into_iter().collect(),
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "读取购物车失败"
+        })),
+    };
 
-    if !unavailable_products.is_empty() {
-        return HttpResponse::BadRequest().json(json!({
-            "message": "部分产品库存不足或不可用",
-            "unavailable_products": unavailable_products
-        }));
-    }
+    let (added, skipped) = plan_reorder(&lines, &current_products, &cart_quantities);
 
-    // 开始事务
-    let transaction_result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
-        // 3. 创建订单
+    let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
         let now = chrono::Utc::now().naive_utc();
-        let order_id = Uuid::new_v4().to_string();
-        
-        // 计算订单总价
-        let mut total: f64 = 0.0;
-        let mut order_items_to_insert = Vec::new();
-        
-        for cart_item in &cart_items {
-            if let Some(product) = products.iter().find(|p| p.id == cart_item.product_id) {
-                let item_price = product.price;
-                let subtotal = item_price * cart_item.quantity as f64;
-                total += subtotal;
-                
-                // 创建订单项
-                let order_item = crate::models::order::NewOrderItem {
-                    id: Uuid::new_v4().to_string(),
-                    order_id: order_id.clone(),
-                    product_id: product.id.clone(),
-                    quantity: cart_item.quantity,
-                    price: item_price,
-                };
-                
-                order_items_to_insert.push(order_item);
-            }
-        }
-        
-        // 创建订单记录
-        let new_order = crate::models::order::NewOrder {
-            id: order_id.clone(),
-            user_id: user_id.clone(),
-            total,
-            status: crate::models::order::OrderStatus::Pending.to_string(),
-            created_at: now,
-            updated_at: now,
-        };
-        
-        // 插入订单
-        diesel::insert_into(crate::schema::orders::table)
-            .values(&new_order)
+        for item in &added {
+            // 已在购物车中的商品累加数量，否则新增购物车项目
+            let updated = diesel::update(
+                cart_items::table
+                    .filter(cart_items::user_id.eq(&user_id))
+                    .filter(cart_items::product_id.eq(&item.product_id)),
+            )
+            .set((
+                cart_items::quantity.eq(cart_items::quantity + item.quantity),
+                cart_items::updated_at.eq(now),
+            ))
             .execute(conn)?;
-        
-        // 插入订单项
-        for order_item in &order_items_to_insert {
-            diesel::insert_into(crate::schema::order_items::table)
-                .values(order_item)
-                .execute(conn)?;
-        }
-        
-        // 4. 更新产品库存
-        for cart_item in &cart_items {
-            diesel::update(products::table.find(&cart_item.product_id))
-                .set(products::stock.eq(products::stock - cart_item.quantity))
-                .execute(conn)?;
+
+            if updated == 0 {
+                diesel::insert_into(cart_items::table)
+                    .values(&NewCartItem {
+                        id: Uuid::new_v4().to_string(),
+                        user_id: user_id.clone(),
+                        product_id: item.product_id.clone(),
+                        quantity: item.quantity,
+                        created_at: now,
+                        updated_at: now,
+                    })
+                    .execute(conn)?;
+            }
         }
-        
-        // 5. 清空购物车
-        diesel::delete(cart_items::table.filter(cart_items::user_id.eq(&user_id)))
-            .execute(conn)?;
-        
-        Ok(order_id)
+        Ok(())
     });
 
-    // 处理事务结果
-    match transaction_result {
-        Ok(order_id) => {
-            // 6. 返回新订单信息
-            // 获取创建的订单详情
-            match crate::schema::orders::table
-                .find(&order_id)
-                .first::<crate::models::order::Order>(&mut conn) {
-                Ok(order) => {
-                    // 获取订单项
-                    match crate::schema::order_items::table
-                        .filter(crate::schema::order_items::order_id.eq(&order_id))
-                        .load::<crate::models::order::OrderItem>(&mut conn) {
-                        Ok(items) => {
-                            let order_status = order.get_status().unwrap_or(crate::models::order::OrderStatus::Pending);
-                            
-                            // 构建订单响应
-                            let item_responses: Vec<crate::models::order::OrderItemResponse> = items
-                                .into_iter()
-                                .map(|item| crate::models::order::OrderItemResponse {
-                                    id: item.id,
-                                    product_id: item.product_id,
-                                    quantity: item.quantity,
-                                    price: item.price,
-                                })
-                                .collect();
-                            
-                            let order_response = crate::models::order::OrderResponse {
-                                id: order.id,
-                                user_id: order.user_id,
-                                total: order.total,
-                                status: order_status,
-                                items: item_responses,
-                                created_at: order.created_at,
-                                updated_at: order.updated_at,
-                            };
-                            
-                            HttpResponse::Created().json(json!({
-                                "message": "订单创建成功",
-                                "order": order_response
-                            }))
-                        },
-                        Err(_) => HttpResponse::InternalServerError().json(json!({
-                            "message": "订单创建成功但无法获取订单详情",
-                            "order_id": order_id
-                        })),
-                    }
-                },
-                Err(_) => HttpResponse::InternalServerError().json(json!({
-                    "message": "订单创建成功但无法获取订单详情",
-                    "order_id": order_id
-                })),
-            }
-        },
-        Err(_) => HttpResponse::InternalServerError().json(json!({
-            "message": "订单创建失败"
-        })),
+    if let Err(e) = result {
+        println!("再次购买加入购物车失败: {:?}", e);
+        return HttpResponse::InternalServerError().json(json!({
+            "message": "加入购物车失败"
+        }));
     }
-} 
\ No newline at end of file
+
+    let message = if added.is_empty() {
+        "订单中的商品均已下架或库存不足"
+    } else if skipped.is_empty() && added.iter().all(|item| item.quantity == item.requested_quantity) {
+        "已将订单商品加入购物车"
+    } else {
+        "部分商品已加入购物车"
+    };
+
+    HttpResponse::Ok().json(json!({
+        "message": message,
+        "order_id": order.id,
+        "added": added,
+        "skipped": skipped
+    }))
+}
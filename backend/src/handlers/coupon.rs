@@ -0,0 +1,243 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::mysql::MysqlConnection;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::middleware::{get_user_id_from_request, is_admin};
+use crate::models::coupon::{
+    join_scope, normalize_code, Coupon, CouponChangeset, CreateCouponDto, DiscountType, NewCoupon, UpdateCouponDto,
+};
+use crate::schema::coupons;
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+// 校验有效期和使用次数上限
+fn validate_limits(
+    min_spend: Option<f64>,
+    starts_at: Option<chrono::NaiveDateTime>,
+    ends_at: Option<chrono::NaiveDateTime>,
+    usage_limit: Option<i32>,
+    per_user_limit: Option<i32>,
+) -> Result<(), &'static str> {
+    if min_spend.map(|min_spend| !min_spend.is_finite() || min_spend < 0.0).unwrap_or(false) {
+        return Err("最低消费不能小于0");
+    }
+    if let (Some(starts_at), Some(ends_at)) = (starts_at, ends_at) {
+        if ends_at <= starts_at {
+            return Err("结束时间必须晚于开始时间");
+        }
+    }
+    if usage_limit.map(|limit| limit <= 0).unwrap_or(false) || per_user_limit.map(|limit| limit <= 0).unwrap_or(false) {
+        return Err("使用次数上限必须大于0");
+    }
+    Ok(())
+}
+
+// 获取优惠券列表，最新创建的在前
+pub async fn get_coupons(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+) -> impl Responder {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    match coupons::table
+        .order(coupons::created_at.desc())
+        .select(Coupon::as_select())
+        .load(&mut conn) {
+        Ok(list) => HttpResponse::Ok().json(list),
+        Err(e) => {
+            println!("读取优惠券失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "读取优惠券失败"
+            }))
+        }
+    }
+}
+
+// 创建优惠券
+pub async fn create_coupon(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    coupon_dto: web::Json<CreateCouponDto>,
+) -> impl Responder {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    let admin_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let dto = coupon_dto.into_inner();
+    let code = normalize_code(&dto.code);
+    if code.is_empty() || code.chars().count() > 50 {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "优惠码不能为空且不能超过50个字符"
+        }));
+    }
+    if !dto.discount_value.is_finite() || dto.discount_value <= 0.0 {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "优惠金额必须大于0"
+        }));
+    }
+    if dto.discount_type == DiscountType::Percentage && dto.discount_value > 100.0 {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "优惠百分比不能超过100"
+        }));
+    }
+    if let Err(message) = validate_limits(dto.min_spend, dto.starts_at, dto.ends_at, dto.usage_limit, dto.per_user_limit) {
+        return HttpResponse::BadRequest().json(json!({
+            "message": message
+        }));
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let now = chrono::Utc::now().naive_utc();
+    let new_coupon = NewCoupon {
+        id: Uuid::new_v4().to_string(),
+        code,
+        discount_type: dto.discount_type.to_string(),
+        discount_value: dto.discount_value,
+        min_spend: dto.min_spend.unwrap_or(0.0),
+        starts_at: dto.starts_at,
+        ends_at: dto.ends_at,
+        usage_limit: dto.usage_limit,
+        per_user_limit: dto.per_user_limit,
+        used_count: 0,
+        product_ids: join_scope(dto.product_ids),
+        categories: join_scope(dto.categories),
+        vendor_ids: join_scope(dto.vendor_ids),
+        active: true,
+        created_by: Some(admin_id),
+        created_at: now,
+        updated_at: now,
+    };
+
+    let result = diesel::insert_into(coupons::table)
+        .values(&new_coupon)
+        .execute(&mut conn)
+        .and_then(|_| coupons::table
+            .find(&new_coupon.id)
+            .select(Coupon::as_select())
+            .first(&mut conn));
+
+    match result {
+        Ok(coupon) => HttpResponse::Created().json(coupon),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => HttpResponse::Conflict().json(json!({
+            "message": "优惠码已存在"
+        })),
+        Err(e) => {
+            println!("创建优惠券失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "创建优惠券失败"
+            }))
+        }
+    }
+}
+
+// 修改优惠券的启用状态、最低消费、有效期和使用次数上限
+pub async fn update_coupon(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    coupon_dto: web::Json<UpdateCouponDto>,
+) -> impl Responder {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    let coupon_id = path.into_inner();
+    let dto = coupon_dto.into_inner();
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let existing = match coupons::table
+        .find(&coupon_id)
+        .select(Coupon::as_select())
+        .first(&mut conn)
+        .optional() {
+        Ok(Some(coupon)) => coupon,
+        Ok(None) => return HttpResponse::NotFound().json(json!({
+            "message": "优惠券不存在"
+        })),
+        Err(e) => {
+            println!("读取优惠券失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "读取优惠券失败"
+            }));
+        }
+    };
+
+    // 与未修改的字段一起校验
+    if let Err(message) = validate_limits(
+        dto.min_spend,
+        dto.starts_at.or(existing.starts_at),
+        dto.ends_at.or(existing.ends_at),
+        dto.usage_limit,
+        dto.per_user_limit,
+    ) {
+        return HttpResponse::BadRequest().json(json!({
+            "message": message
+        }));
+    }
+
+    let changeset = CouponChangeset {
+        active: dto.active,
+        min_spend: dto.min_spend,
+        starts_at: dto.starts_at,
+        ends_at: dto.ends_at,
+        usage_limit: dto.usage_limit,
+        per_user_limit: dto.per_user_limit,
+        updated_at: chrono::Utc::now().naive_utc(),
+    };
+
+    let result = diesel::update(coupons::table.find(&coupon_id))
+        .set(&changeset)
+        .execute(&mut conn)
+        .and_then(|_| coupons::table
+            .find(&coupon_id)
+            .select(Coupon::as_select())
+            .first(&mut conn));
+
+    match result {
+        Ok(coupon) => HttpResponse::Ok().json(coupon),
+        Err(e) => {
+            println!("修改优惠券失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "修改优惠券失败"
+            }))
+        }
+    }
+}
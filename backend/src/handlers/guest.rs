@@ -0,0 +1,236 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::mysql::MysqlConnection;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde_json::json;
+use bcrypt::{hash, DEFAULT_COST};
+
+use crate::middleware::get_user_id_from_request;
+use crate::models::guest::{GuestCheckoutDto, GuestOrderLookupDto, ConvertGuestDto};
+use crate::models::order::{CheckoutDto, Order};
+use crate::models::user::{User, NewGuestUser, AuthResponse};
+use crate::handlers::checkout::{place_order, OrderSource};
+use crate::handlers::order::order_detail_response;
+use crate::services::cart_token;
+use crate::schema::{orders, users};
+use crate::config::jwt::generate_token;
+use crate::utils::validators::{is_valid_email, validate_user_input};
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+// 创建游客购物车：新建游客用户并签发购物车令牌，之后的游客接口通过 X-Cart-Token 请求头携带
+pub async fn create_guest_cart(pool: web::Data<DbPool>) -> impl Responder {
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let guest = NewGuestUser::new();
+    match diesel::insert_into(users::table)
+        .values(&guest)
+        .execute(&mut conn) {
+        Ok(_) => {
+            println!("创建游客购物车: {}", guest.id);
+            HttpResponse::Created().json(json!({
+                "message": "游客购物车已创建",
+                "cart_token": cart_token::issue(&guest.id),
+                "expires_in_days": cart_token::ttl_days()
+            }))
+        }
+        Err(e) => {
+            println!("创建游客失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "创建游客购物车失败"
+            }))
+        }
+    }
+}
+
+// 游客结账：使用游客购物车中的商品下单，联系邮箱和收货地址必填
+pub async fn guest_checkout(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    checkout_dto: web::Json<GuestCheckoutDto>,
+) -> impl Responder {
+    let guest_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取游客身份"
+        })),
+    };
+
+    let checkout_dto = checkout_dto.into_inner();
+    if !is_valid_email(checkout_dto.email.trim()) {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "请填写有效的联系邮箱"
+        }));
+    }
+
+    let request_body = serde_json::to_string(&checkout_dto).unwrap_or_default();
+    let checkout_dto: CheckoutDto = checkout_dto.into();
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    place_order(&req, &mut conn, &guest_id, OrderSource::Cart, Some(checkout_dto), &request_body).await
+}
+
+// 按订单号和联系邮箱查询订单，无需登录；订单号和邮箱不匹配时与订单不存在返回相同结果
+pub async fn lookup_guest_order(
+    pool: web::Data<DbPool>,
+    lookup_dto: web::Json<GuestOrderLookupDto>,
+) -> impl Responder {
+    let order_number = lookup_dto.order_number.trim();
+    let email = lookup_dto.email.trim().to_lowercase();
+    if order_number.is_empty() || email.is_empty() {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "请提供订单号和联系邮箱"
+        }));
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let order = match orders::table
+        .filter(orders::order_number.eq(order_number))
+        .filter(orders::contact_email.eq(&email))
+        .select(Order::as_select())
+        .first(&mut conn) {
+        Ok(order) => order,
+        Err(_) => return HttpResponse::NotFound().json(json!({
+            "message": "未找到匹配的订单，请检查订单号和邮箱"
+        })),
+    };
+
+    match order_detail_response(&mut conn, order) {
+        Ok(order) => HttpResponse::Ok().json(order),
+        Err(e) => {
+            println!("读取订单详情失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "读取订单详情失败"
+            }))
+        }
+    }
+}
+
+// 游客注册为正式用户：保留游客期间的购物车和订单，原购物车令牌随之失效
+pub async fn convert_guest_account(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    convert_dto: web::Json<ConvertGuestDto>,
+) -> impl Responder {
+    let guest_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取游客身份"
+        })),
+    };
+
+    let email = convert_dto.email.trim().to_string();
+    if let Err(errors) = validate_user_input(&email, &convert_dto.password) {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "输入验证失败",
+            "errors": errors
+        }));
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let email_exists = users::table
+        .filter(users::email.eq(&email))
+        .filter(users::id.ne(&guest_id))
+        .select(users::id)
+        .first::<String>(&mut conn)
+        .is_ok();
+    if email_exists {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "该邮箱已被注册，请直接登录"
+        }));
+    }
+
+    let hashed_password = match hash(&convert_dto.password, DEFAULT_COST) {
+        Ok(h) => h,
+        Err(e) => {
+            println!("密码哈希失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "密码处理失败"
+            }));
+        }
+    };
+
+    // 只转换仍是游客的用户，防止同一令牌重复注册
+    let updated = diesel::update(users::table
+        .find(&guest_id)
+        .filter(users::is_guest.eq(true)))
+        .set((
+            users::email.eq(&email),
+            users::password_hash.eq(&hashed_password),
+            users::is_guest.eq(false),
+            users::updated_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .execute(&mut conn);
+
+    match updated {
+        Ok(1) => {}
+        Ok(_) => return HttpResponse::Conflict().json(json!({
+            "message": "该游客已注册为正式用户"
+        })),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+            return HttpResponse::BadRequest().json(json!({
+                "message": "该邮箱已被注册，请直接登录"
+            }));
+        }
+        Err(e) => {
+            println!("游客注册失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "注册失败"
+            }));
+        }
+    }
+
+    let user = match users::table
+        .find(&guest_id)
+        .select(User::as_select())
+        .first(&mut conn) {
+        Ok(user) => user,
+        Err(e) => {
+            println!("查询新用户失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "注册成功但无法检索用户信息"
+            }));
+        }
+    };
+
+    let token = match generate_token(&user) {
+        Ok(token) => token,
+        Err(e) => {
+            println!("生成令牌失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "生成令牌失败"
+            }));
+        }
+    };
+
+    println!("游客 {} 已注册为正式用户: {}", user.id, user.email);
+    HttpResponse::Created().json(AuthResponse {
+        user: user.into(),
+        token,
+    })
+}
+
@@ -0,0 +1,117 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest, HttpMessage};
+use actix_web::http::header;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::mysql::MysqlConnection;
+use serde_json::json;
+
+use crate::config::jwt::Claims;
+use crate::handlers::order::find_accessible_order;
+use crate::middleware::get_user_id_from_request;
+use crate::models::invoice::InvoiceQuery;
+use crate::models::order::OrderStatus;
+use crate::services::invoice::{build_invoice_document, issue_invoice, load_pdf_font, render_html, render_pdf};
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+// 获取订单发票，首次请求时开具发票并分配发票号
+pub async fn get_order_invoice(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    query: web::Query<InvoiceQuery>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let user_role = match req.extensions().get::<Claims>() {
+        Some(claims) => claims.role.clone(),
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户角色"
+        })),
+    };
+
+    let format = query.format.clone().unwrap_or_else(|| "html".to_string()).to_lowercase();
+    if !["html", "pdf", "json"].contains(&format.as_str()) {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "不支持的发票格式，可选 html、pdf、json"
+        }));
+    }
+
+    let order_ref = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let order = match find_accessible_order(&mut conn, &order_ref, &user_id, &user_role) {
+        Ok(order) => order,
+        Err(response) => return response,
+    };
+
+    if order.get_status() == Ok(OrderStatus::Cancelled) {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "已取消的订单无法开具发票"
+        }));
+    }
+
+    let invoice = match issue_invoice(&mut conn, &order.id) {
+        Ok(invoice) => invoice,
+        Err(e) => {
+            println!("开具发票失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "开具发票失败"
+            }));
+        }
+    };
+
+    let document = match build_invoice_document(&mut conn, &order, &invoice) {
+        Ok(document) => document,
+        Err(e) => {
+            println!("生成发票数据失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "生成发票数据失败"
+            }));
+        }
+    };
+
+    match format.as_str() {
+        "pdf" => {
+            let font = match load_pdf_font() {
+                Ok(font) => font,
+                Err(e) => {
+                    println!("读取PDF发票字体失败: {:?}", e);
+                    return HttpResponse::InternalServerError().json(json!({
+                        "message": "生成PDF发票失败"
+                    }));
+                }
+            };
+            match render_pdf(&document, font) {
+                Ok(bytes) => HttpResponse::Ok()
+                    .content_type("application/pdf")
+                    .insert_header((
+                        header::CONTENT_DISPOSITION,
+                        format!("inline; filename=\"{}.pdf\"", document.invoice_number),
+                    ))
+                    .body(bytes),
+                Err(e) => {
+                    println!("生成PDF发票失败: {:?}", e);
+                    HttpResponse::InternalServerError().json(json!({
+                        "message": "生成PDF发票失败"
+                    }))
+                }
+            }
+        }
+        "json" => HttpResponse::Ok().json(json!({
+            "invoice": document
+        })),
+        _ => HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body(render_html(&document)),
+    }
+}
@@ -1,12 +1,26 @@
 pub mod auth;
 pub mod product;
 pub mod cart;
+pub mod checkout;
+pub mod guest;
 pub mod order;
+pub mod shipment;
+pub mod invoice;
+pub mod order_message;
 // pub mod test;
 // pub mod test_data;
 pub mod favorite;
 pub mod user_profile;
+pub mod address;
 pub mod vendor_profile;
 pub mod user;
 pub mod analytics;
-pub mod admin;
\ No newline at end of file
+pub mod admin;
+pub mod tax_rate;
+pub mod risk;
+pub mod subscription;
+pub mod notification;
+pub mod payment;
+pub mod wallet;
+pub mod coupon;
+pub mod promotion;
\ No newline at end of file
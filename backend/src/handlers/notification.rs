@@ -0,0 +1,126 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::mysql::MysqlConnection;
+use serde_json::json;
+
+use crate::middleware::get_user_id_from_request;
+use crate::models::notification::{Notification, NotificationQuery};
+use crate::schema::notifications;
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+const DEFAULT_NOTIFICATION_PAGE_SIZE: i64 = 20;
+const MAX_NOTIFICATION_PAGE_SIZE: i64 = 100;
+
+// 获取当前用户的站内通知，最新的在前
+pub async fn get_notifications(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    query: web::Query<NotificationQuery>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let unread_only = query.unread.unwrap_or(false);
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(DEFAULT_NOTIFICATION_PAGE_SIZE).clamp(1, MAX_NOTIFICATION_PAGE_SIZE);
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let unread_count = match notifications::table
+        .filter(notifications::user_id.eq(&user_id))
+        .filter(notifications::read_at.is_null())
+        .count()
+        .get_result::<i64>(&mut conn) {
+        Ok(count) => count,
+        Err(e) => {
+            println!("统计未读通知失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "读取通知失败"
+            }));
+        }
+    };
+
+    let mut notifications_query = notifications::table
+        .filter(notifications::user_id.eq(&user_id))
+        .into_boxed();
+    if unread_only {
+        notifications_query = notifications_query.filter(notifications::read_at.is_null());
+    }
+
+    match notifications_query
+        .order((notifications::created_at.desc(), notifications::id.desc()))
+        .offset((page - 1) * limit)
+        .limit(limit)
+        .select(Notification::as_select())
+        .load(&mut conn) {
+        Ok(list) => HttpResponse::Ok().json(json!({
+            "notifications": list,
+            "unread_count": unread_count,
+            "pagination": {
+                "page": page,
+                "limit": limit
+            }
+        })),
+        Err(e) => {
+            println!("读取通知失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "读取通知失败"
+            }))
+        }
+    }
+}
+
+// 将通知标记为已读
+pub async fn mark_notification_read(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let notification_id = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let target = notifications::table
+        .find(&notification_id)
+        .filter(notifications::user_id.eq(&user_id));
+
+    let result = diesel::update(target.filter(notifications::read_at.is_null()))
+        .set(notifications::read_at.eq(chrono::Utc::now().naive_utc()))
+        .execute(&mut conn)
+        .and_then(|_| target.select(Notification::as_select()).first(&mut conn).optional());
+
+    match result {
+        Ok(Some(notification)) => HttpResponse::Ok().json(notification),
+        Ok(None) => HttpResponse::NotFound().json(json!({
+            "message": "通知不存在"
+        })),
+        Err(e) => {
+            println!("标记通知已读失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "标记通知已读失败"
+            }))
+        }
+    }
+}
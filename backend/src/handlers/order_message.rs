@@ -0,0 +1,330 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest, HttpMessage};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::mysql::MysqlConnection;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use diesel::sql_types::VarChar;
+use serde_json::json;
+
+use crate::middleware::get_user_id_from_request;
+use crate::schema::{order_messages, order_message_attachments, order_message_reads};
+use crate::models::order_message::{
+    OrderMessage, NewOrderMessage, OrderMessageAttachment, NewOrderMessageAttachment,
+    OrderMessageRead, NewOrderMessageRead, OrderMessageResponse, SendOrderMessageDto, UnreadMessageCount,
+};
+use crate::handlers::order::find_accessible_order;
+use crate::models::user::UserRole;
+use crate::config::jwt::Claims;
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+// 从请求中取出用户ID和角色
+fn current_user(req: &HttpRequest) -> Result<(String, UserRole), HttpResponse> {
+    let user_id = get_user_id_from_request(req).ok_or_else(|| HttpResponse::Unauthorized().json(json!({
+        "message": "无法获取用户ID"
+    })))?;
+    let user_role = req.extensions().get::<Claims>()
+        .map(|claims| claims.role.clone())
+        .ok_or_else(|| HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户角色"
+        })))?;
+    Ok((user_id, user_role))
+}
+
+// 将用户在订单留言中的已读位置前移到指定时间，已读位置只会前进不会后退
+fn advance_read_position(
+    conn: &mut MysqlConnection,
+    order_id: &str,
+    user_id: &str,
+    read_at: chrono::NaiveDateTime,
+) -> QueryResult<()> {
+    let updated = diesel::update(order_message_reads::table
+        .filter(order_message_reads::order_id.eq(order_id))
+        .filter(order_message_reads::user_id.eq(user_id))
+        .filter(order_message_reads::last_read_at.lt(read_at)))
+        .set(order_message_reads::last_read_at.eq(read_at))
+        .execute(conn)?;
+
+    if updated > 0 {
+        return Ok(());
+    }
+
+    // 没有已读记录时新建；已有记录且位置更新时插入会因唯一键冲突失败，忽略即可
+    match diesel::insert_into(order_message_reads::table)
+        .values(&NewOrderMessageRead::new(order_id.to_string(), user_id.to_string(), read_at))
+        .execute(conn) {
+        Ok(_) | Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+// 获取订单留言列表，按时间先后排列
+pub async fn get_order_messages(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let (user_id, user_role) = match current_user(&req) {
+        Ok(user) => user,
+        Err(response) => return response,
+    };
+
+    let order_ref = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let order = match find_accessible_order(&mut conn, &order_ref, &user_id, &user_role) {
+        Ok(order) => order,
+        Err(response) => return response,
+    };
+
+    let messages = match OrderMessage::belonging_to(&order)
+        .order(order_messages::created_at.asc())
+        .select(OrderMessage::as_select())
+        .load(&mut conn) {
+        Ok(messages) => messages,
+        Err(e) => {
+            println!("读取订单留言失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "读取订单留言失败"
+            }));
+        }
+    };
+
+    let attachments = OrderMessageAttachment::belonging_to(&messages)
+        .order(order_message_attachments::created_at.asc())
+        .select(OrderMessageAttachment::as_select())
+        .load(&mut conn)
+        .unwrap_or_else(|e| {
+            println!("读取留言附件失败: {:?}", e);
+            Vec::new()
+        });
+
+    let reads = order_message_reads::table
+        .filter(order_message_reads::order_id.eq(&order.id))
+        .select(OrderMessageRead::as_select())
+        .load(&mut conn)
+        .unwrap_or_else(|e| {
+            println!("读取留言已读记录失败: {:?}", e);
+            Vec::new()
+        });
+
+    let last_read_at = reads.iter()
+        .find(|read| read.user_id == user_id)
+        .map(|read| read.last_read_at);
+    let unread = messages.iter()
+        .filter(|message| message.sender_id != user_id)
+        .filter(|message| last_read_at.is_none_or(|read_at| message.created_at > read_at))
+        .count();
+
+    let grouped = attachments.grouped_by(&messages);
+    let messages: Vec<OrderMessageResponse> = messages
+        .into_iter()
+        .zip(grouped)
+        .map(|(message, attachments)| OrderMessageResponse::new(message, attachments, &reads))
+        .collect();
+
+    HttpResponse::Ok().json(json!({
+        "order_id": order.id,
+        "messages": messages,
+        "last_read_at": last_read_at,
+        "unread": unread
+    }))
+}
+
+// 在订单下发送留言，可附带附件地址
+pub async fn send_order_message(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    message_dto: web::Json<SendOrderMessageDto>,
+) -> impl Responder {
+    let (user_id, user_role) = match current_user(&req) {
+        Ok(user) => user,
+        Err(response) => return response,
+    };
+
+    let body = match message_dto.validate() {
+        Ok(body) => body,
+        Err(message) => return HttpResponse::BadRequest().json(json!({
+            "message": message
+        })),
+    };
+
+    let order_ref = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let order = match find_accessible_order(&mut conn, &order_ref, &user_id, &user_role) {
+        Ok(order) => order,
+        Err(response) => return response,
+    };
+
+    let new_message = NewOrderMessage::new(order.id.clone(), user_id.clone(), user_role.to_string(), body);
+    let new_attachments: Vec<NewOrderMessageAttachment> = message_dto.attachments
+        .as_deref()
+        .unwrap_or(&[])
+        .iter()
+        .map(|attachment| NewOrderMessageAttachment::new(new_message.id.clone(), attachment))
+        .collect();
+
+    let result = conn.transaction::<_, DieselError, _>(|conn| {
+        diesel::insert_into(order_messages::table)
+            .values(&new_message)
+            .execute(conn)?;
+
+        if !new_attachments.is_empty() {
+            diesel::insert_into(order_message_attachments::table)
+                .values(&new_attachments)
+                .execute(conn)?;
+        }
+
+        // 发言即表示已读此前的全部留言
+        advance_read_position(conn, &order.id, &user_id, new_message.created_at)?;
+
+        let message = order_messages::table
+            .find(&new_message.id)
+            .select(OrderMessage::as_select())
+            .first(conn)?;
+        let attachments = OrderMessageAttachment::belonging_to(&message)
+            .select(OrderMessageAttachment::as_select())
+            .load(conn)?;
+        let reads = order_message_reads::table
+            .filter(order_message_reads::order_id.eq(&order.id))
+            .select(OrderMessageRead::as_select())
+            .load(conn)?;
+
+        Ok(OrderMessageResponse::new(message, attachments, &reads))
+    });
+
+    match result {
+        Ok(message) => {
+            println!("用户 {} 在订单 {} 下发送留言", user_id, order.id);
+            HttpResponse::Created().json(json!({
+                "message": "留言已发送",
+                "order_message": message
+            }))
+        }
+        Err(e) => {
+            println!("发送订单留言失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "发送留言失败"
+            }))
+        }
+    }
+}
+
+// 将订单留言全部标记为已读
+pub async fn mark_order_messages_read(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let (user_id, user_role) = match current_user(&req) {
+        Ok(user) => user,
+        Err(response) => return response,
+    };
+
+    let order_ref = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let order = match find_accessible_order(&mut conn, &order_ref, &user_id, &user_role) {
+        Ok(order) => order,
+        Err(response) => return response,
+    };
+
+    let read_at = chrono::Utc::now().naive_utc();
+    match advance_read_position(&mut conn, &order.id, &user_id, read_at) {
+        Ok(_) => HttpResponse::Ok().json(json!({
+            "message": "留言已标记为已读",
+            "order_id": order.id,
+            "last_read_at": read_at
+        })),
+        Err(e) => {
+            println!("标记留言已读失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "标记留言已读失败"
+            }))
+        }
+    }
+}
+
+// 获取当前用户可访问订单中的未读留言数（不含自己发送的留言），按订单分别统计
+pub async fn get_unread_message_count(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+) -> impl Responder {
+    let (user_id, user_role) = match current_user(&req) {
+        Ok(user) => user,
+        Err(response) => return response,
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    // 可访问范围与查看订单详情一致：顾客为自己的订单，商家为包含其商品或自己下的订单，管理员为全部订单
+    let scope = match user_role {
+        UserRole::Admin => "",
+        UserRole::Vendor => "AND (m.order_id IN (SELECT order_id FROM order_fulfillments WHERE vendor_id = ?) \
+                             OR m.order_id IN (SELECT id FROM orders WHERE user_id = ?))",
+        UserRole::Customer => "AND m.order_id IN (SELECT id FROM orders WHERE user_id = ?)",
+    };
+    let sql = format!(
+        "SELECT m.order_id AS order_id, CAST(COUNT(*) AS SIGNED) AS unread \
+         FROM order_messages m \
+         LEFT JOIN order_message_reads r ON r.order_id = m.order_id AND r.user_id = ? \
+         WHERE m.sender_id <> ? AND (r.last_read_at IS NULL OR m.created_at > r.last_read_at) {} \
+         GROUP BY m.order_id \
+         ORDER BY MAX(m.created_at) DESC",
+        scope
+    );
+
+    let mut query = diesel::sql_query(sql)
+        .into_boxed()
+        .bind::<VarChar, _>(user_id.clone())
+        .bind::<VarChar, _>(user_id.clone());
+    match user_role {
+        UserRole::Admin => {}
+        UserRole::Vendor => {
+            query = query
+                .bind::<VarChar, _>(user_id.clone())
+                .bind::<VarChar, _>(user_id.clone());
+        }
+        UserRole::Customer => {
+            query = query.bind::<VarChar, _>(user_id.clone());
+        }
+    }
+
+    match query.load::<UnreadMessageCount>(&mut conn) {
+        Ok(counts) => {
+            let total: i64 = counts.iter().map(|count| count.unread).sum();
+            HttpResponse::Ok().json(json!({
+                "total": total,
+                "orders": counts
+            }))
+        }
+        Err(e) => {
+            println!("统计未读留言失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "统计未读留言失败"
+            }))
+        }
+    }
+}
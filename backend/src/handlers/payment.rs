@@ -0,0 +1,667 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::mysql::MysqlConnection;
+use diesel::result::Error as DieselError;
+use serde_json::json;
+use std::str::FromStr;
+
+use crate::middleware::{get_user_id_from_request, is_admin};
+use crate::models::order::{amount_due, Order, OrderStatus};
+use crate::models::payment::{
+    CallbackOutcome, CreatePaymentDto, NewPayment, NewPaymentCallback, Payment, PaymentStatus,
+    RefundPaymentDto,
+};
+use crate::models::wallet::WalletTransactionKind;
+use crate::schema::{admin_profiles, orders, payment_callbacks, payments};
+use crate::services::fulfillment::mark_order_paid;
+use crate::services::payment_gateway::{
+    callback_tolerance_seconds, check_freshness, gateway_for, CallbackRequest, GatewayError, PaymentGateway,
+    PaymentNotification,
+};
+use crate::services::tax::round_currency;
+use crate::services::wallet::credit;
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+// 支付成功后写入订单状态记录的原因
+const PAYMENT_SUCCEEDED_REASON: &str = "支付成功";
+// 订单已取消或已由其他支付完成时自动退款的原因
+const DUPLICATE_PAYMENT_REFUND_REASON: &str = "订单已取消或已支付，自动退款";
+
+// 支付渠道是否已在后台启用；尚未保存后台设置时所有已接入的渠道均可用
+fn gateway_enabled(conn: &mut MysqlConnection, code: &str) -> QueryResult<bool> {
+    let configured = admin_profiles::table
+        .order(admin_profiles::created_at.asc())
+        .select(admin_profiles::payment_gateways)
+        .first::<String>(conn)
+        .optional()?;
+
+    Ok(match configured {
+        Some(gateways) => gateways.split(',').any(|gateway| gateway.trim().eq_ignore_ascii_case(code)),
+        None => true,
+    })
+}
+
+#[derive(Debug)]
+pub enum PaymentError {
+    Database(DieselError),
+    NotFound,
+    // 当前状态不允许该操作
+    InvalidState(&'static str),
+    Gateway(GatewayError),
+}
+
+impl From<DieselError> for PaymentError {
+    fn from(error: DieselError) -> Self {
+        PaymentError::Database(error)
+    }
+}
+
+impl PaymentError {
+    pub fn into_response(self, action: &str) -> HttpResponse {
+        match self {
+            PaymentError::NotFound => HttpResponse::NotFound().json(json!({
+                "message": "支付记录不存在"
+            })),
+            PaymentError::InvalidState(message) => HttpResponse::Conflict().json(json!({
+                "message": message
+            })),
+            PaymentError::Gateway(e) => {
+                println!("{}失败: {}", action, e);
+                HttpResponse::BadGateway().json(json!({
+                    "message": format!("{}失败: {}", action, e)
+                }))
+            }
+            PaymentError::Database(e) => {
+                println!("{}失败: {:?}", action, e);
+                HttpResponse::InternalServerError().json(json!({
+                    "message": format!("{}失败", action)
+                }))
+            }
+        }
+    }
+}
+
+// 发起支付：只有本人的待支付且未在风控审核中的订单可以支付
+pub async fn create_payment(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    payment_dto: web::Json<CreatePaymentDto>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let order_id = path.into_inner();
+    let gateway = match gateway_for(&payment_dto.gateway) {
+        Some(gateway) => gateway,
+        None => return HttpResponse::BadRequest().json(json!({
+            "message": format!("不支持的支付方式: {}", payment_dto.gateway)
+        })),
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    match gateway_enabled(&mut conn, gateway.code()) {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::BadRequest().json(json!({
+            "message": format!("支付方式 {} 未启用", gateway.code())
+        })),
+        Err(e) => {
+            println!("读取支付设置失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "读取支付设置失败"
+            }));
+        }
+    }
+
+    let order = match orders::table
+        .find(&order_id)
+        .filter(orders::user_id.eq(&user_id))
+        .select(Order::as_select())
+        .first(&mut conn) {
+        Ok(order) => order,
+        Err(_) => return HttpResponse::NotFound().json(json!({
+            "message": "订单不存在"
+        })),
+    };
+
+    if order.get_status() != Ok(OrderStatus::Pending) {
+        return HttpResponse::Conflict().json(json!({
+            "message": format!("订单状态为 {}，无需支付", order.status)
+        }));
+    }
+    if order.on_hold {
+        return HttpResponse::Conflict().json(json!({
+            "message": "订单正在风控审核中，审核通过后才能支付"
+        }));
+    }
+    let due = order.amount_due();
+    if due <= 0.0 {
+        return HttpResponse::Conflict().json(json!({
+            "message": "订单已由钱包余额全额支付"
+        }));
+    }
+
+    let mut new_payment = NewPayment::new(order.id.clone(), user_id, gateway.code(), due);
+    let intent = match gateway.create_intent(&new_payment.id, new_payment.amount, &new_payment.currency) {
+        Ok(intent) => intent,
+        Err(e) => {
+            println!("发起支付失败: {}", e);
+            return HttpResponse::BadGateway().json(json!({
+                "message": format!("发起支付失败: {}", e)
+            }));
+        }
+    };
+    new_payment.gateway_payment_id = intent.gateway_payment_id;
+    new_payment.payment_url = intent.payment_url;
+
+    let result = diesel::insert_into(payments::table)
+        .values(&new_payment)
+        .execute(&mut conn)
+        .and_then(|_| payments::table
+            .find(&new_payment.id)
+            .select(Payment::as_select())
+            .first(&mut conn));
+
+    match result {
+        Ok(payment) => {
+            println!("订单 {} 发起支付 {}，渠道 {}", order.order_number, payment.id, payment.gateway);
+            HttpResponse::Created().json(payment)
+        }
+        Err(e) => {
+            println!("保存支付记录失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "发起支付失败"
+            }))
+        }
+    }
+}
+
+// 获取订单的支付记录，订单所有者和管理员可以查看
+pub async fn get_order_payments(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let order_id = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let owner_id = match orders::table
+        .find(&order_id)
+        .select(orders::user_id)
+        .first::<String>(&mut conn) {
+        Ok(owner_id) => owner_id,
+        Err(_) => return HttpResponse::NotFound().json(json!({
+            "message": "订单不存在"
+        })),
+    };
+    if owner_id != user_id && !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "无权访问此订单"
+        }));
+    }
+
+    match payments::table
+        .filter(payments::order_id.eq(&order_id))
+        .order(payments::created_at.desc())
+        .select(Payment::as_select())
+        .load(&mut conn) {
+        Ok(list) => HttpResponse::Ok().json(json!({
+            "payments": list
+        })),
+        Err(e) => {
+            println!("读取支付记录失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "读取支付记录失败"
+            }))
+        }
+    }
+}
+
+// 处理验签通过的通知，返回 None 表示该通知已处理过（重放或渠道重复推送）：
+// 支付状态只前进不后退；支付成功时核对通知金额、支付金额与订单应付金额（扣除钱包抵扣）一致，
+// 订单仍待支付才将待处理的商家子订单改为处理中；处理结果与通知编号一起记录在同一事务中
+fn settle_payment(
+    conn: &mut MysqlConnection,
+    gateway: &str,
+    notification: &PaymentNotification,
+) -> Result<Option<(Payment, CallbackOutcome)>, PaymentError> {
+    conn.transaction::<_, PaymentError, _>(|conn| {
+        // 锁定支付记录，同一支付的通知串行处理
+        let payment = payments::table
+            .filter(payments::gateway.eq(gateway))
+            .filter(payments::gateway_payment_id.eq(&notification.gateway_payment_id))
+            .select(Payment::as_select())
+            .for_update()
+            .first(conn)
+            .optional()?
+            .ok_or(PaymentError::NotFound)?;
+
+        let processed = payment_callbacks::table
+            .filter(payment_callbacks::gateway.eq(gateway))
+            .filter(payment_callbacks::notification_id.eq(&notification.notification_id))
+            .count()
+            .get_result::<i64>(conn)?;
+        if processed > 0 {
+            return Ok(None);
+        }
+
+        let now = chrono::Utc::now().naive_utc();
+        let outcome = match payment.get_status().apply(notification.status) {
+            None => CallbackOutcome::Unchanged,
+            Some(PaymentStatus::Succeeded) => {
+                // 锁定订单，避免与超时取消任务同时修改
+                let (order_status, order_total, wallet_amount) = orders::table
+                    .find(&payment.order_id)
+                    .select((orders::status, orders::total, orders::wallet_amount))
+                    .for_update()
+                    .first::<(String, f64, f64)>(conn)?;
+                // 钱包余额抵扣的部分不经过支付渠道
+                let order_due = amount_due(order_total, wallet_amount);
+
+                if PaymentStatus::settled(notification.amount, payment.amount, order_due) == PaymentStatus::Mismatched {
+                    diesel::update(payments::table.find(&payment.id))
+                        .set((
+                            payments::status.eq(PaymentStatus::Mismatched.to_string()),
+                            payments::paid_amount.eq(notification.amount),
+                            payments::paid_at.eq(now),
+                            payments::failure_reason.eq(format!(
+                                "支付金额 {:.2} 与订单应付金额 {:.2} 不一致",
+                                notification.amount, order_due
+                            )),
+                            payments::updated_at.eq(now),
+                        ))
+                        .execute(conn)?;
+                    CallbackOutcome::Mismatched
+                } else {
+                    diesel::update(payments::table.find(&payment.id))
+                        .set((
+                            payments::status.eq(PaymentStatus::Succeeded.to_string()),
+                            payments::paid_amount.eq(notification.amount),
+                            payments::paid_at.eq(now),
+                            payments::failure_reason.eq(None::<String>),
+                            payments::updated_at.eq(now),
+                        ))
+                        .execute(conn)?;
+
+                    if OrderStatus::from_str(&order_status) == Ok(OrderStatus::Pending) {
+                        mark_order_paid(conn, &payment.order_id, PAYMENT_SUCCEEDED_REASON)?;
+                        CallbackOutcome::Paid
+                    } else {
+                        CallbackOutcome::RefundRequired
+                    }
+                }
+            }
+            Some(status) => {
+                diesel::update(payments::table.find(&payment.id))
+                    .set((
+                        payments::status.eq(status.to_string()),
+                        payments::failure_reason.eq(&notification.failure_reason),
+                        payments::updated_at.eq(now),
+                    ))
+                    .execute(conn)?;
+                CallbackOutcome::Failed
+            }
+        };
+
+        diesel::insert_into(payment_callbacks::table)
+            .values(&NewPaymentCallback::new(payment.id.clone(), gateway, notification, outcome))
+            .execute(conn)?;
+
+        let payment = payments::table
+            .find(&payment.id)
+            .select(Payment::as_select())
+            .first(conn)?;
+        Ok(Some((payment, outcome)))
+    })
+}
+
+// 退款去向
+enum RefundDestination<'a> {
+    // 原路退回支付渠道
+    Gateway(&'a dyn PaymentGateway),
+    // 退入用户钱包，记录操作的管理员
+    Wallet(&'a str),
+}
+
+// 退款并记录退款金额，全额退款后支付状态变为已退款；
+// 持有支付记录的行锁调用渠道接口，避免重复退款
+fn refund_payment_through(
+    conn: &mut MysqlConnection,
+    destination: RefundDestination,
+    payment_id: &str,
+    amount: Option<f64>,
+    reason: Option<&str>,
+) -> Result<Payment, PaymentError> {
+    conn.transaction::<_, PaymentError, _>(|conn| {
+        let payment = payments::table
+            .find(payment_id)
+            .select(Payment::as_select())
+            .for_update()
+            .first(conn)
+            .optional()?
+            .ok_or(PaymentError::NotFound)?;
+
+        let refundable = round_currency(payment.refundable_amount());
+        if refundable <= 0.0 {
+            return Err(PaymentError::InvalidState("该支付没有可退款的金额"));
+        }
+        let amount = round_currency(amount.unwrap_or(refundable));
+        if amount <= 0.0 || amount > refundable {
+            return Err(PaymentError::InvalidState("退款金额必须大于0且不超过可退款金额"));
+        }
+
+        let refund_id = match destination {
+            RefundDestination::Gateway(gateway) => gateway
+                .refund(&payment.gateway_payment_id, amount)
+                .map_err(PaymentError::Gateway)?,
+            RefundDestination::Wallet(admin_id) => credit(
+                conn,
+                &payment.user_id,
+                WalletTransactionKind::OrderRefund,
+                amount,
+                Some(reason.unwrap_or("支付退款")),
+                Some(&payment.order_id),
+                Some(admin_id),
+            )?.id,
+        };
+        println!(
+            "支付 {} 退款 {:.2}，退款单号 {}，原因: {}",
+            payment.id, amount, refund_id, reason.unwrap_or("-")
+        );
+
+        let now = chrono::Utc::now().naive_utc();
+        let refunded_amount = round_currency(payment.refunded_amount + amount);
+        let status = if refunded_amount >= round_currency(payment.paid_amount.unwrap_or(payment.amount)) {
+            PaymentStatus::Refunded
+        } else {
+            PaymentStatus::Succeeded
+        };
+        diesel::update(payments::table.find(&payment.id))
+            .set((
+                payments::status.eq(status.to_string()),
+                payments::refunded_amount.eq(refunded_amount),
+                payments::refunded_at.eq(now),
+                payments::updated_at.eq(now),
+            ))
+            .execute(conn)?;
+
+        Ok(payments::table
+            .find(&payment.id)
+            .select(Payment::as_select())
+            .first(conn)?)
+    })
+}
+
+// 退回订单所有成功支付的款项：已接入的渠道原路退回，渠道未接入时退入用户钱包并记录操作的管理员；
+// 用于管理员取消已支付的订单，必须在事务中调用
+pub fn refund_order_payments(
+    conn: &mut MysqlConnection,
+    order_id: &str,
+    admin_id: &str,
+    reason: &str,
+) -> Result<(), PaymentError> {
+    let order_payments = payments::table
+        .filter(payments::order_id.eq(order_id))
+        .select(Payment::as_select())
+        .load(conn)?;
+
+    for payment in order_payments.iter().filter(|payment| payment.refundable_amount() > 0.0) {
+        let gateway = gateway_for(&payment.gateway);
+        let destination = match &gateway {
+            Some(gateway) => RefundDestination::Gateway(gateway.as_ref()),
+            None => RefundDestination::Wallet(admin_id),
+        };
+        refund_payment_through(conn, destination, &payment.id, None, Some(reason))?;
+    }
+    Ok(())
+}
+
+// 支付渠道回调（无需登录）：由渠道校验签名，拒绝过期的通知，已处理过的通知直接确认不再重复处理；
+// 订单只有在此确认支付成功且金额一致后才进入处理中
+pub async fn payment_callback(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    body: web::Bytes,
+) -> impl Responder {
+    let gateway = match gateway_for(&path.into_inner()) {
+        Some(gateway) => gateway,
+        None => return HttpResponse::NotFound().json(json!({
+            "message": "不支持的支付渠道"
+        })),
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    // 后台未启用的渠道不接受回调
+    match gateway_enabled(&mut conn, gateway.code()) {
+        Ok(true) => {}
+        Ok(false) => {
+            println!("拒绝未启用的支付渠道 {} 的回调", gateway.code());
+            return HttpResponse::Forbidden().json(json!({
+                "message": format!("支付方式 {} 未启用", gateway.code())
+            }));
+        }
+        Err(e) => {
+            println!("读取支付设置失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "读取支付设置失败"
+            }));
+        }
+    }
+
+    let signature = req.headers()
+        .get(gateway.signature_header())
+        .and_then(|value| value.to_str().ok());
+    let notification = match gateway.verify_callback(&CallbackRequest { signature, body: &body }) {
+        Ok(notification) => check_freshness(
+            notification.sent_at,
+            chrono::Utc::now().naive_utc(),
+            callback_tolerance_seconds(),
+        ).map(|_| notification),
+        Err(e) => Err(e),
+    };
+    let notification = match notification {
+        Ok(notification) => notification,
+        Err(e) => {
+            println!("支付回调校验失败（{}）: {}", gateway.code(), e);
+            return match e {
+                GatewayError::InvalidSignature => HttpResponse::Unauthorized().json(json!({
+                    "message": e.to_string()
+                })),
+                _ => HttpResponse::BadRequest().json(json!({
+                    "message": e.to_string()
+                })),
+            };
+        }
+    };
+
+    let (payment, outcome) = match settle_payment(&mut conn, gateway.code(), &notification) {
+        Ok(Some(result)) => result,
+        Ok(None) => {
+            println!("支付通知 {}（{}）已处理过，忽略", notification.notification_id, gateway.code());
+            return HttpResponse::Ok().json(json!({
+                "message": "通知已处理"
+            }));
+        }
+        Err(e) => return e.into_response("处理支付回调"),
+    };
+
+    match outcome {
+        CallbackOutcome::Paid => println!("订单 {} 支付成功，支付记录 {}", payment.order_id, payment.id),
+        CallbackOutcome::Failed => println!("支付 {} 失败: {}", payment.id, payment.failure_reason.as_deref().unwrap_or("-")),
+        CallbackOutcome::Mismatched => println!("支付 {} 金额不符，需人工核对: {}", payment.id, payment.failure_reason.as_deref().unwrap_or("-")),
+        CallbackOutcome::Unchanged => println!("支付 {} 状态为 {}，忽略回调", payment.id, payment.status),
+        CallbackOutcome::RefundRequired => {
+            // 回调本身已处理完成，退款失败时保留已支付状态，由管理员手动退款
+            return match refund_payment_through(&mut conn, RefundDestination::Gateway(gateway.as_ref()), &payment.id, None, Some(DUPLICATE_PAYMENT_REFUND_REASON)) {
+                Ok(payment) => HttpResponse::Ok().json(json!({
+                    "message": "订单已取消或已支付，款项已退回",
+                    "payment": payment
+                })),
+                Err(e) => {
+                    println!("支付 {} 自动退款失败，需人工处理: {:?}", payment.id, e);
+                    HttpResponse::Ok().json(json!({
+                        "message": "订单已取消或已支付，款项待人工退款",
+                        "payment": payment
+                    }))
+                }
+            };
+        }
+    }
+
+    HttpResponse::Ok().json(json!({
+        "message": "回调已处理",
+        "payment": payment
+    }))
+}
+
+// 管理员主动向支付渠道查询支付结果，用于回调丢失时补单；结果按回调同样的流程处理
+pub async fn sync_payment(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+) -> impl Responder {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    let payment_id = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let payment = match payments::table
+        .find(&payment_id)
+        .select(Payment::as_select())
+        .first(&mut conn) {
+        Ok(payment) => payment,
+        Err(_) => return PaymentError::NotFound.into_response("查询支付结果"),
+    };
+    let gateway = match gateway_for(&payment.gateway) {
+        Some(gateway) => gateway,
+        None => return HttpResponse::Conflict().json(json!({
+            "message": format!("支付渠道 {} 未接入，无法查询", payment.gateway)
+        })),
+    };
+
+    // 按渠道实际收到的金额对账，与回调通知相同
+    let result = match gateway.query(&payment.gateway_payment_id) {
+        Ok(result) => result,
+        Err(e) => return PaymentError::Gateway(e).into_response("查询支付结果"),
+    };
+    let status = result.status;
+    let notification = PaymentNotification::from_query(&payment.gateway_payment_id, result);
+
+    match settle_payment(&mut conn, gateway.code(), &notification) {
+        Ok(Some((payment, CallbackOutcome::RefundRequired))) => {
+            match refund_payment_through(&mut conn, RefundDestination::Gateway(gateway.as_ref()), &payment.id, None, Some(DUPLICATE_PAYMENT_REFUND_REASON)) {
+                Ok(payment) => HttpResponse::Ok().json(json!({
+                    "gateway_status": status,
+                    "payment": payment
+                })),
+                Err(e) => e.into_response("自动退款"),
+            }
+        }
+        Ok(Some((payment, _))) => HttpResponse::Ok().json(json!({
+            "gateway_status": status,
+            "payment": payment
+        })),
+        Ok(None) => PaymentError::InvalidState("查询结果已处理").into_response("查询支付结果"),
+        Err(e) => e.into_response("查询支付结果"),
+    }
+}
+
+// 管理员退款：原路退回或退入用户钱包，只退还款项，订单状态由管理员另行处理
+pub async fn refund_payment(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    refund_dto: web::Json<RefundPaymentDto>,
+) -> impl Responder {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    let admin_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let payment_id = path.into_inner();
+    let refund_dto = refund_dto.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let gateway_code = match payments::table
+        .find(&payment_id)
+        .select(payments::gateway)
+        .first::<String>(&mut conn) {
+        Ok(code) => code,
+        Err(_) => return PaymentError::NotFound.into_response("退款"),
+    };
+    let gateway = gateway_for(&gateway_code);
+    let destination = if refund_dto.to_wallet.unwrap_or(false) {
+        RefundDestination::Wallet(&admin_id)
+    } else {
+        match &gateway {
+            Some(gateway) => RefundDestination::Gateway(gateway.as_ref()),
+            None => return HttpResponse::Conflict().json(json!({
+                "message": format!("支付渠道 {} 未接入，只能退入钱包", gateway_code)
+            })),
+        }
+    };
+
+    let reason = refund_dto.reason
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty());
+    match refund_payment_through(&mut conn, destination, &payment_id, refund_dto.amount, reason.as_deref()) {
+        Ok(payment) => HttpResponse::Ok().json(json!({
+            "message": "退款成功",
+            "payment": payment
+        })),
+        Err(e) => e.into_response("退款"),
+    }
+}
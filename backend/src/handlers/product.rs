@@ -3,10 +3,12 @@ use serde_json::json;
 use diesel::prelude::*;
 use url;
 
-use crate::models::product::{Product, CreateProductDto, UpdateProductDto, ProductResponse};
+use crate::models::order::OrderStatus;
+use crate::models::product::{Product, CreateProductDto, UpdateProductDto, ProductResponse, BackorderSettingsDto, BackorderLineResponse};
 use crate::models::user::UserRole;
 use crate::config::jwt::Claims;
-use crate::schema::products;
+use crate::schema::{order_items, orders, products};
+use crate::services::inventory::allocate_backorders;
 
 // 获取所有产品
 pub async fn get_all_products(
@@ -194,8 +196,16 @@ pub async fn create_product(
     // 验证用户角色
     match claims {
         Some(claims) if claims.role == UserRole::Vendor || claims.role == UserRole::Admin => {
+            // 校验缺货预订设置
+            let backorder_mode = match product_dto.backorder_settings().validate() {
+                Ok(mode) => mode,
+                Err(message) => return HttpResponse::BadRequest().json(json!({
+                    "message": message
+                })),
+            };
+            
             // 创建新产品
-            let new_product = Product::new(
+            let mut new_product = Product::new(
                 product_dto.name.clone(),
                 product_dto.description.clone(),
                 product_dto.price,
@@ -203,6 +213,9 @@ pub async fn create_product(
                 product_dto.stock,
                 product_dto.category.clone(),
             );
+            new_product.backorder_mode = backorder_mode.to_string();
+            new_product.backorder_limit = product_dto.backorder_limit;
+            new_product.expected_ship_date = product_dto.expected_ship_date;
             
             // 获取数据库连接
             let mut conn = match pool.get() {
@@ -323,9 +336,14 @@ pub async fn update_product(
                     }
                     
                     if let Some(stock) = product_dto.stock {
-                        match diesel::update(products::table.find(&product_id))
-                            .set(products::stock.eq(stock))
-                            .execute(&mut conn) {
+                        // 到货的库存优先分配给等待到货的订单项
+                        let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                            diesel::update(products::table.find(&product_id))
+                                .set(products::stock.eq(stock))
+                                .execute(conn)?;
+                            allocate_backorders(conn, &product_id)
+                        });
+                        match result {
                             Ok(_) => updates.push("stock"),
                             Err(_) => return HttpResponse::InternalServerError().json(json!({
                                 "message": "更新产品库存失败"
@@ -481,4 +499,147 @@ pub async fn get_vendor_products(
             "message": "无权访问"
         })),
     }
+}
+
+// 修改产品的缺货预订设置（供应商）
+pub async fn update_backorder_settings(
+    req: HttpRequest,
+    pool: web::Data<crate::config::database::DbPool>,
+    path: web::Path<String>,
+    settings_dto: web::Json<BackorderSettingsDto>
+) -> impl Responder {
+    let product_id = path.into_inner();
+    
+    let claims = match req.extensions().get::<Claims>() {
+        Some(claims) if claims.role == UserRole::Vendor || claims.role == UserRole::Admin => claims.clone(),
+        _ => return HttpResponse::Forbidden().json(json!({
+            "message": "无权更新产品"
+        })),
+    };
+    
+    let backorder_mode = match settings_dto.validate() {
+        Ok(mode) => mode,
+        Err(message) => return HttpResponse::BadRequest().json(json!({
+            "message": message
+        })),
+    };
+    
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "无法获取数据库连接"
+        })),
+    };
+    
+    let product = match products::table
+        .find(&product_id)
+        .first::<Product>(&mut conn)
+        .optional() {
+        Ok(Some(product)) => product,
+        Ok(None) => return HttpResponse::NotFound().json(json!({
+            "message": "产品不存在"
+        })),
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "获取产品信息失败"
+        })),
+    };
+    
+    if product.vendor_id != claims.sub && claims.role != UserRole::Admin {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "无权更新此产品"
+        }));
+    }
+    
+    let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::update(products::table.find(&product_id))
+            .set((
+                products::backorder_mode.eq(backorder_mode.to_string()),
+                products::backorder_limit.eq(settings_dto.backorder_limit),
+                products::expected_ship_date.eq(settings_dto.expected_ship_date),
+                products::updated_at.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .execute(conn)?;
+        
+        // 等待到货的订单项同步新的预计发货日期
+        diesel::update(order_items::table
+            .filter(order_items::product_id.eq(&product_id))
+            .filter(order_items::backordered_quantity.gt(0)))
+            .set(order_items::expected_ship_date.eq(settings_dto.expected_ship_date))
+            .execute(conn)?;
+        
+        products::table.find(&product_id).first::<Product>(conn)
+    });
+    
+    match result {
+        Ok(product) => HttpResponse::Ok().json(ProductResponse::from(product)),
+        Err(e) => {
+            println!("更新缺货预订设置失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "更新缺货预订设置失败"
+            }))
+        }
+    }
+}
+
+// 获取等待到货的订单项（供应商），按下单先后排列，即到货后的分配顺序
+pub async fn get_vendor_backorders(
+    req: HttpRequest,
+    pool: web::Data<crate::config::database::DbPool>
+) -> impl Responder {
+    let claims = match req.extensions().get::<Claims>() {
+        Some(claims) if claims.role == UserRole::Vendor || claims.role == UserRole::Admin => claims.clone(),
+        _ => return HttpResponse::Forbidden().json(json!({
+            "message": "无权访问"
+        })),
+    };
+    
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "无法获取数据库连接"
+        })),
+    };
+    
+    let mut query = order_items::table
+        .inner_join(orders::table)
+        .inner_join(products::table)
+        .filter(order_items::backordered_quantity.gt(0))
+        .filter(orders::status.ne(OrderStatus::Cancelled.to_string()))
+        .into_boxed();
+    
+    // 管理员可以看到所有商家的预订
+    if claims.role != UserRole::Admin {
+        query = query.filter(products::vendor_id.eq(claims.sub.clone()));
+    }
+    
+    let rows = query
+        .order((orders::created_at.asc(), order_items::id.asc()))
+        .select((
+            order_items::id,
+            orders::id,
+            orders::order_number,
+            products::id,
+            products::name,
+            products::backorder_mode,
+            order_items::quantity,
+            order_items::backordered_quantity,
+            order_items::expected_ship_date,
+            orders::created_at,
+        ))
+        .load::<(String, String, String, String, String, String, i32, i32, Option<chrono::NaiveDate>, chrono::NaiveDateTime)>(&mut conn);
+    
+    match rows {
+        Ok(rows) => {
+            let response: Vec<BackorderLineResponse> = rows.into_iter()
+                .map(BackorderLineResponse::from)
+                .collect();
+            HttpResponse::Ok().json(response)
+        },
+        Err(e) => {
+            println!("获取等待到货订单失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "获取等待到货订单失败"
+            }))
+        }
+    }
 }
\ No newline at end of file
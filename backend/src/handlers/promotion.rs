@@ -0,0 +1,271 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::mysql::MysqlConnection;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::middleware::{get_user_id_from_request, is_admin};
+use crate::models::coupon::join_scope;
+use crate::models::promotion::{
+    format_tiers, CreatePromotionDto, NewPromotion, Promotion, PromotionKind, UpdatePromotionDto,
+};
+use crate::models::user::UserRole;
+use crate::schema::{promotions, users};
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+// 按促销类型校验优惠内容
+fn validate_rules(dto: &CreatePromotionDto) -> Result<(), &'static str> {
+    match dto.kind {
+        PromotionKind::Tiered => {
+            let tiers = dto.tiers.as_deref().unwrap_or(&[]);
+            if tiers.is_empty() {
+                return Err("满减促销至少需要一个档位");
+            }
+            let valid = tiers.iter().all(|tier| {
+                tier.threshold.is_finite()
+                    && tier.discount.is_finite()
+                    && tier.discount > 0.0
+                    && tier.discount <= tier.threshold
+            });
+            if !valid {
+                return Err("满减金额必须大于0且不能超过门槛金额");
+            }
+        }
+        PromotionKind::BuyXGetY => {
+            if dto.buy_quantity.unwrap_or(0) <= 0 || dto.free_quantity.unwrap_or(0) <= 0 {
+                return Err("购买件数和赠送件数必须大于0");
+            }
+        }
+        PromotionKind::PercentageOff => {
+            let percentage = dto.percentage.unwrap_or(0.0);
+            if !percentage.is_finite() || percentage <= 0.0 || percentage > 100.0 {
+                return Err("折扣百分比必须大于0且不能超过100");
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_period(
+    starts_at: Option<chrono::NaiveDateTime>,
+    ends_at: Option<chrono::NaiveDateTime>,
+) -> Result<(), &'static str> {
+    if let (Some(starts_at), Some(ends_at)) = (starts_at, ends_at) {
+        if ends_at <= starts_at {
+            return Err("结束时间必须晚于开始时间");
+        }
+    }
+    Ok(())
+}
+
+// 获取促销列表，按计算顺序排列
+pub async fn get_promotions(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+) -> impl Responder {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    match promotions::table
+        .order((promotions::priority.desc(), promotions::created_at.asc()))
+        .select(Promotion::as_select())
+        .load(&mut conn) {
+        Ok(list) => HttpResponse::Ok().json(list),
+        Err(e) => {
+            println!("读取促销活动失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "读取促销活动失败"
+            }))
+        }
+    }
+}
+
+// 创建促销
+pub async fn create_promotion(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    promotion_dto: web::Json<CreatePromotionDto>,
+) -> impl Responder {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    let admin_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let dto = promotion_dto.into_inner();
+    let name = dto.name.trim().to_string();
+    if name.is_empty() || name.chars().count() > 100 {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "促销名称不能为空且不能超过100个字符"
+        }));
+    }
+    if let Err(message) = validate_rules(&dto).and_then(|_| validate_period(dto.starts_at, dto.ends_at)) {
+        return HttpResponse::BadRequest().json(json!({
+            "message": message
+        }));
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    // 出资方必须是商家
+    if let Some(funded_by) = &dto.funded_by {
+        let role = users::table
+            .find(funded_by)
+            .select(users::role)
+            .first::<String>(&mut conn)
+            .optional();
+        match role {
+            Ok(Some(role)) if role.parse::<UserRole>() == Ok(UserRole::Vendor) => {}
+            Ok(_) => return HttpResponse::BadRequest().json(json!({
+                "message": "出资商家不存在"
+            })),
+            Err(e) => {
+                println!("读取出资商家失败: {:?}", e);
+                return HttpResponse::InternalServerError().json(json!({
+                    "message": "创建促销活动失败"
+                }));
+            }
+        }
+    }
+
+    // 只保存该类型用到的优惠内容
+    let now = chrono::Utc::now().naive_utc();
+    let new_promotion = NewPromotion {
+        id: Uuid::new_v4().to_string(),
+        name,
+        kind: dto.kind.to_string(),
+        priority: dto.priority.unwrap_or(0),
+        stackable: dto.stackable.unwrap_or(true),
+        tiers: dto.tiers.filter(|_| dto.kind == PromotionKind::Tiered).map(|tiers| format_tiers(&tiers)),
+        buy_quantity: dto.buy_quantity.filter(|_| dto.kind == PromotionKind::BuyXGetY),
+        free_quantity: dto.free_quantity.filter(|_| dto.kind == PromotionKind::BuyXGetY),
+        percentage: dto.percentage.filter(|_| dto.kind == PromotionKind::PercentageOff),
+        product_ids: join_scope(dto.product_ids),
+        categories: join_scope(dto.categories),
+        vendor_ids: join_scope(dto.vendor_ids),
+        funded_by: dto.funded_by,
+        starts_at: dto.starts_at,
+        ends_at: dto.ends_at,
+        active: true,
+        created_by: Some(admin_id),
+        created_at: now,
+        updated_at: now,
+    };
+
+    let result = diesel::insert_into(promotions::table)
+        .values(&new_promotion)
+        .execute(&mut conn)
+        .and_then(|_| promotions::table
+            .find(&new_promotion.id)
+            .select(Promotion::as_select())
+            .first(&mut conn));
+
+    match result {
+        Ok(promotion) => HttpResponse::Created().json(promotion),
+        Err(e) => {
+            println!("创建促销活动失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "创建促销活动失败"
+            }))
+        }
+    }
+}
+
+// 修改促销的名称、优先级、叠加规则、启用状态和有效期
+pub async fn update_promotion(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    promotion_dto: web::Json<UpdatePromotionDto>,
+) -> impl Responder {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    let promotion_id = path.into_inner();
+    let mut dto = promotion_dto.into_inner();
+    if let Some(name) = &dto.name {
+        let name = name.trim().to_string();
+        if name.is_empty() || name.chars().count() > 100 {
+            return HttpResponse::BadRequest().json(json!({
+                "message": "促销名称不能为空且不能超过100个字符"
+            }));
+        }
+        dto.name = Some(name);
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let existing = match promotions::table
+        .find(&promotion_id)
+        .select(Promotion::as_select())
+        .first(&mut conn)
+        .optional() {
+        Ok(Some(promotion)) => promotion,
+        Ok(None) => return HttpResponse::NotFound().json(json!({
+            "message": "促销活动不存在"
+        })),
+        Err(e) => {
+            println!("读取促销活动失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "读取促销活动失败"
+            }));
+        }
+    };
+
+    // 与未修改的字段一起校验
+    if let Err(message) = validate_period(dto.starts_at.or(existing.starts_at), dto.ends_at.or(existing.ends_at)) {
+        return HttpResponse::BadRequest().json(json!({
+            "message": message
+        }));
+    }
+
+    let result = diesel::update(promotions::table.find(&promotion_id))
+        .set((&dto, promotions::updated_at.eq(chrono::Utc::now().naive_utc())))
+        .execute(&mut conn)
+        .and_then(|_| promotions::table
+            .find(&promotion_id)
+            .select(Promotion::as_select())
+            .first(&mut conn));
+
+    match result {
+        Ok(promotion) => HttpResponse::Ok().json(promotion),
+        Err(e) => {
+            println!("修改促销活动失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "修改促销活动失败"
+            }))
+        }
+    }
+}
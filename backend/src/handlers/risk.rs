@@ -0,0 +1,367 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::mysql::MysqlConnection;
+use diesel::result::Error as DieselError;
+use serde_json::json;
+use std::str::FromStr;
+
+use crate::middleware::{get_user_id_from_request, is_admin};
+use crate::models::order::{amount_due, OrderStatus};
+use crate::models::risk::{
+    OrderRiskAssessment, RiskReviewDto, RiskReviewQuery, RiskReviewResponse, RiskReviewStatus, RiskRule,
+    UpdateRiskRuleDto,
+};
+use crate::schema::{orders, order_risk_assessments, risk_rules, users};
+use crate::services::fulfillment::{cancel_pending_orders, mark_order_paid};
+use crate::services::wallet::WALLET_PAID_REASON;
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+const DEFAULT_REVIEW_PAGE_SIZE: i64 = 20;
+const MAX_REVIEW_PAGE_SIZE: i64 = 100;
+// 风控审核拒绝时写入订单状态记录的原因
+const REVIEW_REJECTED_REASON: &str = "风控审核未通过";
+
+// 获取风控规则列表
+pub async fn get_risk_rules(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+) -> impl Responder {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    match risk_rules::table
+        .order(risk_rules::rule.asc())
+        .select(RiskRule::as_select())
+        .load(&mut conn) {
+        Ok(rules) => HttpResponse::Ok().json(json!({
+            "risk_rules": rules
+        })),
+        Err(e) => {
+            println!("读取风控规则失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "读取风控规则失败"
+            }))
+        }
+    }
+}
+
+// 修改风控规则：启用/停用、阈值、命中分值
+pub async fn update_risk_rule(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    rule_dto: web::Json<UpdateRiskRuleDto>,
+) -> impl Responder {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    if let Err(message) = rule_dto.validate() {
+        return HttpResponse::BadRequest().json(json!({
+            "message": message
+        }));
+    }
+
+    let rule = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let result = conn.transaction::<_, DieselError, _>(|conn| {
+        let updated = diesel::update(risk_rules::table.filter(risk_rules::rule.eq(rule.trim())))
+            .set((&rule_dto.into_inner(), risk_rules::updated_at.eq(chrono::Utc::now().naive_utc())))
+            .execute(conn)?;
+        if updated == 0 {
+            return Err(DieselError::NotFound);
+        }
+
+        risk_rules::table
+            .filter(risk_rules::rule.eq(rule.trim()))
+            .select(RiskRule::as_select())
+            .first(conn)
+    });
+
+    match result {
+        Ok(rule) => HttpResponse::Ok().json(json!({
+            "message": "风控规则已更新",
+            "risk_rule": rule
+        })),
+        Err(DieselError::NotFound) => HttpResponse::NotFound().json(json!({
+            "message": "风控规则不存在"
+        })),
+        Err(e) => {
+            println!("更新风控规则失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "更新风控规则失败"
+            }))
+        }
+    }
+}
+
+// 风控审核队列：默认列出待审核订单，最早的排在前面
+pub async fn get_risk_reviews(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    query: web::Query<RiskReviewQuery>,
+) -> impl Responder {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    let status = match query.status.as_deref().map(str::trim).filter(|status| !status.is_empty()) {
+        Some(status) => match RiskReviewStatus::from_str(status) {
+            Ok(status) => status,
+            Err(_) => return HttpResponse::BadRequest().json(json!({
+                "message": "审核状态只能是 pending、approved 或 rejected"
+            })),
+        },
+        None => RiskReviewStatus::Pending,
+    };
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(DEFAULT_REVIEW_PAGE_SIZE).clamp(1, MAX_REVIEW_PAGE_SIZE);
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let total = match order_risk_assessments::table
+        .filter(order_risk_assessments::review_status.eq(status.to_string()))
+        .count()
+        .get_result::<i64>(&mut conn) {
+        Ok(total) => total,
+        Err(e) => {
+            println!("统计风控审核记录失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "读取风控审核队列失败"
+            }));
+        }
+    };
+
+    let mut reviews_query = order_risk_assessments::table
+        .inner_join(orders::table)
+        .inner_join(users::table)
+        .filter(order_risk_assessments::review_status.eq(status.to_string()))
+        .select((OrderRiskAssessment::as_select(), orders::order_number, orders::status, users::email))
+        .into_boxed();
+    reviews_query = match status {
+        RiskReviewStatus::Pending => reviews_query.order(order_risk_assessments::created_at.asc()),
+        _ => reviews_query.order(order_risk_assessments::reviewed_at.desc()),
+    };
+
+    match reviews_query
+        .offset((page - 1) * limit)
+        .limit(limit)
+        .load::<(OrderRiskAssessment, String, String, String)>(&mut conn) {
+        Ok(rows) => {
+            let reviews: Vec<RiskReviewResponse> = rows
+                .into_iter()
+                .map(|(assessment, order_number, order_status, email)| {
+                    RiskReviewResponse::new(assessment, order_number, order_status, email)
+                })
+                .collect();
+            HttpResponse::Ok().json(json!({
+                "reviews": reviews,
+                "pagination": {
+                    "page": page,
+                    "limit": limit,
+                    "total": total
+                }
+            }))
+        }
+        Err(e) => {
+            println!("读取风控审核队列失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "读取风控审核队列失败"
+            }))
+        }
+    }
+}
+
+// 审核处理失败的原因
+enum ReviewError {
+    Database(DieselError),
+    NotFound,
+    // 订单不在待审核状态
+    NotPending,
+}
+
+impl From<DieselError> for ReviewError {
+    fn from(error: DieselError) -> Self {
+        ReviewError::Database(error)
+    }
+}
+
+fn load_review(conn: &mut MysqlConnection, order_id: &str) -> QueryResult<RiskReviewResponse> {
+    let (assessment, order_number, order_status, email) = order_risk_assessments::table
+        .inner_join(orders::table)
+        .inner_join(users::table)
+        .filter(order_risk_assessments::order_id.eq(order_id))
+        .select((OrderRiskAssessment::as_select(), orders::order_number, orders::status, users::email))
+        .first::<(OrderRiskAssessment, String, String, String)>(conn)?;
+    Ok(RiskReviewResponse::new(assessment, order_number, order_status, email))
+}
+
+// 处理待审核订单：锁定订单确认仍在待审核状态后，通过则放行，拒绝则取消订单并归还库存
+fn resolve_review(
+    conn: &mut MysqlConnection,
+    order_id: &str,
+    admin_id: &str,
+    outcome: RiskReviewStatus,
+    note: Option<String>,
+) -> Result<RiskReviewResponse, ReviewError> {
+    conn.transaction::<_, ReviewError, _>(|conn| {
+        let (status, on_hold) = orders::table
+            .find(order_id)
+            .select((orders::status, orders::on_hold))
+            .for_update()
+            .first::<(String, bool)>(conn)
+            .optional()?
+            .ok_or(ReviewError::NotFound)?;
+
+        let assessment_id = order_risk_assessments::table
+            .filter(order_risk_assessments::order_id.eq(order_id))
+            .filter(order_risk_assessments::review_status.eq(RiskReviewStatus::Pending.to_string()))
+            .select(order_risk_assessments::id)
+            .first::<String>(conn)
+            .optional()?;
+
+        let assessment_id = match assessment_id {
+            Some(id) if on_hold && OrderStatus::from_str(&status) == Ok(OrderStatus::Pending) => id,
+            _ => return Err(ReviewError::NotPending),
+        };
+
+        match outcome {
+            RiskReviewStatus::Rejected => {
+                cancel_pending_orders(conn, &[order_id.to_string()], REVIEW_REJECTED_REASON, Some(admin_id))?;
+            }
+            _ => {
+                diesel::update(orders::table.find(order_id))
+                    .set((
+                        orders::on_hold.eq(false),
+                        orders::updated_at.eq(chrono::Utc::now().naive_utc()),
+                    ))
+                    .execute(conn)?;
+
+                // 下单时已用钱包余额付清的订单，审核通过后直接进入处理中
+                let (total, wallet_amount) = orders::table
+                    .find(order_id)
+                    .select((orders::total, orders::wallet_amount))
+                    .first::<(f64, f64)>(conn)?;
+                if wallet_amount > 0.0 && amount_due(total, wallet_amount) <= 0.0 {
+                    mark_order_paid(conn, order_id, WALLET_PAID_REASON)?;
+                }
+            }
+        }
+
+        diesel::update(order_risk_assessments::table.find(&assessment_id))
+            .set((
+                order_risk_assessments::review_status.eq(outcome.to_string()),
+                order_risk_assessments::reviewed_by.eq(admin_id),
+                order_risk_assessments::reviewed_at.eq(chrono::Utc::now().naive_utc()),
+                order_risk_assessments::review_note.eq(note),
+            ))
+            .execute(conn)?;
+
+        Ok(load_review(conn, order_id)?)
+    })
+}
+
+async fn review_order(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    review_dto: web::Json<RiskReviewDto>,
+    outcome: RiskReviewStatus,
+) -> HttpResponse {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    let admin_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let order_id = path.into_inner();
+    let note = review_dto.into_inner().note
+        .map(|note| note.trim().to_string())
+        .filter(|note| !note.is_empty());
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    match resolve_review(&mut conn, &order_id, &admin_id, outcome, note) {
+        Ok(review) => {
+            println!("管理员 {} 风控审核订单 {}: {}", admin_id, order_id, outcome);
+            HttpResponse::Ok().json(json!({
+                "message": match outcome {
+                    RiskReviewStatus::Rejected => "订单已拒绝并取消",
+                    _ => "订单已通过审核",
+                },
+                "review": review
+            }))
+        }
+        Err(ReviewError::NotFound) => HttpResponse::NotFound().json(json!({
+            "message": "订单不存在"
+        })),
+        Err(ReviewError::NotPending) => HttpResponse::Conflict().json(json!({
+            "message": "该订单不在风控待审核状态"
+        })),
+        Err(ReviewError::Database(e)) => {
+            println!("处理风控审核失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "处理风控审核失败"
+            }))
+        }
+    }
+}
+
+// 审核通过：放行订单，之后可正常支付和发货
+pub async fn approve_risk_review(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    review_dto: web::Json<RiskReviewDto>,
+) -> impl Responder {
+    review_order(req, pool, path, review_dto, RiskReviewStatus::Approved).await
+}
+
+// 审核拒绝：取消订单并归还库存
+pub async fn reject_risk_review(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    review_dto: web::Json<RiskReviewDto>,
+) -> impl Responder {
+    review_order(req, pool, path, review_dto, RiskReviewStatus::Rejected).await
+}
@@ -0,0 +1,433 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest, HttpMessage};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::mysql::MysqlConnection;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde_json::json;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::config::jwt::Claims;
+use crate::handlers::order::find_accessible_order;
+use crate::services::fulfillment::{set_fulfillment_status, sync_order_status};
+use crate::middleware::get_user_id_from_request;
+use crate::models::fulfillment::OrderFulfillment;
+use crate::models::order::{OrderItem, OrderStatus};
+use crate::models::shipment::{
+    plan_shipment_items, CreateShipmentDto, NewShipment, NewShipmentItem, Shipment, ShipmentItem,
+    ShipmentResponse, ShipmentTrackingResponse, SHIPMENT_STATUS_DELIVERED, SHIPMENT_STATUS_EXCEPTION,
+};
+use crate::models::user::UserRole;
+use crate::schema::{orders, order_items, order_fulfillments, shipments, shipment_items};
+use crate::services::carrier::{carrier_for, TrackingStatus};
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+// 批量加载订单的发货记录及包裹明细，按订单ID分组
+pub fn load_order_shipments(conn: &mut MysqlConnection, order_ids: &[String]) -> HashMap<String, Vec<ShipmentResponse>> {
+    let mut grouped: HashMap<String, Vec<ShipmentResponse>> = HashMap::new();
+
+    let order_shipments = match shipments::table
+        .filter(shipments::order_id.eq_any(order_ids))
+        .order(shipments::shipped_at.asc())
+        .select(Shipment::as_select())
+        .load(conn) {
+        Ok(items) => items,
+        Err(e) => {
+            println!("读取发货记录失败: {:?}", e);
+            return grouped;
+        }
+    };
+
+    let items = ShipmentItem::belonging_to(&order_shipments)
+        .select(ShipmentItem::as_select())
+        .load(conn)
+        .unwrap_or_default()
+        .grouped_by(&order_shipments);
+
+    for (shipment, items) in order_shipments.into_iter().zip(items) {
+        grouped
+            .entry(shipment.order_id.clone())
+            .or_default()
+            .push(ShipmentResponse::new(shipment, items));
+    }
+
+    grouped
+}
+
+// 商家创建发货记录
+pub async fn create_shipment(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    shipment_dto: web::Json<CreateShipmentDto>,
+) -> impl Responder {
+    let vendor_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let is_vendor = req.extensions()
+        .get::<Claims>()
+        .map(|claims| claims.role == UserRole::Vendor)
+        .unwrap_or(false);
+
+    if !is_vendor {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有商家可以创建发货记录"
+        }));
+    }
+
+    let carrier = shipment_dto.carrier.trim().to_lowercase();
+    let tracking_number = shipment_dto.tracking_number.trim().to_string();
+    if carrier.is_empty() || tracking_number.is_empty() {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "承运商和运单号不能为空"
+        }));
+    }
+
+    let order_id = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let fulfillment = match order_fulfillments::table
+        .filter(order_fulfillments::order_id.eq(&order_id))
+        .filter(order_fulfillments::vendor_id.eq(&vendor_id))
+        .select(OrderFulfillment::as_select())
+        .first(&mut conn) {
+        Ok(fulfillment) => fulfillment,
+        Err(_) => return HttpResponse::Forbidden().json(json!({
+            "message": "无权修改不包含您产品的订单"
+        })),
+    };
+
+    let fulfillment_status = fulfillment.get_status().unwrap_or(OrderStatus::Pending);
+    if matches!(fulfillment_status, OrderStatus::Cancelled | OrderStatus::Delivered | OrderStatus::Completed) {
+        return HttpResponse::BadRequest().json(json!({
+            "message": format!("子订单状态为 {}，无法发货", fulfillment_status)
+        }));
+    }
+
+    // 收到支付成功回调前不能发货
+    if fulfillment_status == OrderStatus::Pending {
+        return HttpResponse::Conflict().json(json!({
+            "message": "订单尚未支付，暂不能发货"
+        }));
+    }
+
+    // 风控审核通过前不能发货
+    let on_hold = orders::table
+        .find(&order_id)
+        .select(orders::on_hold)
+        .first::<bool>(&mut conn)
+        .unwrap_or(false);
+    if on_hold {
+        return HttpResponse::Conflict().json(json!({
+            "message": "订单正在风控审核中，暂不能发货"
+        }));
+    }
+
+    // 子订单的订单项和已发货数量
+    let items = match order_items::table
+        .filter(order_items::fulfillment_id.eq(&fulfillment.id))
+        .select(OrderItem::as_select())
+        .load(&mut conn) {
+        Ok(items) => items,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "获取订单项目失败"
+        })),
+    };
+    let ordered: Vec<(String, i32)> = items.iter().map(|item| (item.id.clone(), item.quantity)).collect();
+    // 等待到货的数量不能发货
+    let shippable: Vec<(String, i32)> = items.iter()
+        .map(|item| (item.id.clone(), item.quantity - item.backordered_quantity))
+        .collect();
+
+    let shipped = match load_shipped_quantities(&mut conn, &fulfillment.id) {
+        Ok(shipped) => shipped,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "获取已发货数量失败"
+        })),
+    };
+
+    let plan = match plan_shipment_items(&shippable, &shipped, shipment_dto.items.as_deref()) {
+        Ok(plan) => plan,
+        Err(message) => return HttpResponse::BadRequest().json(json!({
+            "message": message
+        })),
+    };
+
+    // 本次发货后子订单是否已全部发出
+    let fully_shipped = ordered.iter().all(|(id, quantity)| {
+        let already = shipped.get(id).copied().unwrap_or(0);
+        let now = plan.iter().find(|(plan_id, _)| plan_id == id).map(|(_, q)| *q).unwrap_or(0);
+        already + now >= *quantity
+    });
+
+    let new_shipment = NewShipment::new(
+        order_id.clone(),
+        fulfillment.id.clone(),
+        vendor_id.clone(),
+        carrier,
+        tracking_number,
+    );
+
+    let result = conn.transaction::<_, DieselError, _>(|conn| {
+        diesel::insert_into(shipments::table)
+            .values(&new_shipment)
+            .execute(conn)?;
+
+        for (order_item_id, quantity) in &plan {
+            diesel::insert_into(shipment_items::table)
+                .values(&NewShipmentItem {
+                    id: Uuid::new_v4().to_string(),
+                    shipment_id: new_shipment.id.clone(),
+                    order_item_id: order_item_id.clone(),
+                    quantity: *quantity,
+                })
+                .execute(conn)?;
+        }
+
+        // 全部发出时子订单变为已发货，部分发货时至少为处理中
+        let new_status = if fully_shipped { OrderStatus::Shipped } else { OrderStatus::Processing };
+        set_fulfillment_status(conn, std::slice::from_ref(&fulfillment.id), &new_status)?;
+
+        let order_status = sync_order_status(conn, &order_id, None, Some(&vendor_id))?;
+
+        let shipment = shipments::table
+            .find(&new_shipment.id)
+            .select(Shipment::as_select())
+            .first(conn)?;
+        let items = ShipmentItem::belonging_to(&shipment)
+            .select(ShipmentItem::as_select())
+            .load(conn)?;
+
+        Ok((ShipmentResponse::new(shipment, items), new_status, order_status))
+    });
+
+    match result {
+        Ok((shipment, fulfillment_status, order_status)) => HttpResponse::Created().json(json!({
+            "message": "发货记录已创建",
+            "shipment": shipment,
+            "fulfillment_status": fulfillment_status,
+            "order_status": order_status
+        })),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => HttpResponse::Conflict().json(json!({
+            "message": "该运单号已被使用"
+        })),
+        Err(e) => {
+            println!("创建发货记录失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "创建发货记录失败"
+            }))
+        }
+    }
+}
+
+// 子订单中每个订单项的已发货数量
+fn load_shipped_quantities(conn: &mut MysqlConnection, fulfillment_id: &str) -> QueryResult<HashMap<String, i32>> {
+    let rows = shipment_items::table
+        .inner_join(shipments::table)
+        .filter(shipments::fulfillment_id.eq(fulfillment_id))
+        .select((shipment_items::order_item_id, shipment_items::quantity))
+        .load::<(String, i32)>(conn)?;
+
+    let mut shipped = HashMap::new();
+    for (order_item_id, quantity) in rows {
+        *shipped.entry(order_item_id).or_insert(0) += quantity;
+    }
+    Ok(shipped)
+}
+
+// 获取订单的发货记录
+pub async fn get_order_shipments(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let user_role = match req.extensions().get::<Claims>() {
+        Some(claims) => claims.role.clone(),
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户角色"
+        })),
+    };
+
+    let order_id = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let order = match find_accessible_order(&mut conn, &order_id, &user_id, &user_role) {
+        Ok(order) => order,
+        Err(response) => return response,
+    };
+
+    let mut order_shipments = load_order_shipments(&mut conn, std::slice::from_ref(&order.id))
+        .remove(&order.id)
+        .unwrap_or_default();
+
+    // 商家只能看到自己的包裹
+    if user_role == UserRole::Vendor && order.user_id != user_id {
+        order_shipments.retain(|shipment| shipment.vendor_id == user_id);
+    }
+
+    HttpResponse::Ok().json(json!({
+        "shipments": order_shipments
+    }))
+}
+
+// 查询包裹的物流轨迹，并根据轨迹更新包裹状态
+pub async fn track_shipment(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let user_role = match req.extensions().get::<Claims>() {
+        Some(claims) => claims.role.clone(),
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户角色"
+        })),
+    };
+
+    let (order_id, shipment_id) = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let order = match find_accessible_order(&mut conn, &order_id, &user_id, &user_role) {
+        Ok(order) => order,
+        Err(response) => return response,
+    };
+
+    let mut shipment = match shipments::table
+        .find(&shipment_id)
+        .filter(shipments::order_id.eq(&order.id))
+        .select(Shipment::as_select())
+        .first(&mut conn) {
+        Ok(shipment) => shipment,
+        Err(_) => return HttpResponse::NotFound().json(json!({
+            "message": "发货记录不存在"
+        })),
+    };
+
+    if user_role == UserRole::Vendor && order.user_id != user_id && shipment.vendor_id != user_id {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "无权查看其他商家的发货记录"
+        }));
+    }
+
+    // 未接入轨迹查询的承运商只返回发货信息
+    let tracker = carrier_for(&shipment.carrier);
+    let events = match &tracker {
+        Some(tracker) => match tracker.track(&shipment.tracking_number, shipment.shipped_at) {
+            Ok(events) => events,
+            Err(e) => {
+                println!("查询物流轨迹失败: {}", e);
+                return HttpResponse::BadGateway().json(json!({
+                    "message": format!("查询物流轨迹失败: {}", e)
+                }));
+            }
+        },
+        None => Vec::new(),
+    };
+
+    // 根据最新轨迹更新包裹状态
+    let latest_status = match events.last().map(|event| &event.status) {
+        Some(TrackingStatus::Delivered) => Some(SHIPMENT_STATUS_DELIVERED),
+        Some(TrackingStatus::Exception) => Some(SHIPMENT_STATUS_EXCEPTION),
+        _ => None,
+    };
+
+    if let Some(new_status) = latest_status.filter(|status| *status != shipment.status) {
+        let delivered_at = events.last()
+            .filter(|_| new_status == SHIPMENT_STATUS_DELIVERED)
+            .map(|event| event.occurred_at);
+
+        let result = conn.transaction::<_, DieselError, _>(|conn| {
+            diesel::update(shipments::table.find(&shipment.id))
+                .set((
+                    shipments::status.eq(new_status),
+                    shipments::delivered_at.eq(delivered_at),
+                    shipments::updated_at.eq(chrono::Utc::now().naive_utc()),
+                ))
+                .execute(conn)?;
+
+            if new_status == SHIPMENT_STATUS_DELIVERED {
+                mark_fulfillment_delivered_if_complete(conn, &shipment.fulfillment_id)?;
+            }
+            Ok(())
+        });
+
+        match result {
+            Ok(_) => {
+                shipment.status = new_status.to_string();
+                shipment.delivered_at = delivered_at;
+            }
+            Err(e) => println!("更新包裹状态失败: {:?}", e),
+        }
+    }
+
+    let items = ShipmentItem::belonging_to(&shipment)
+        .select(ShipmentItem::as_select())
+        .load(&mut conn)
+        .unwrap_or_default();
+
+    HttpResponse::Ok().json(ShipmentTrackingResponse {
+        shipment: ShipmentResponse::new(shipment, items),
+        tracking_available: tracker.is_some(),
+        events,
+    })
+}
+
+// 子订单已全部发出且所有包裹都已签收时，将子订单标记为已送达并同步父订单状态
+fn mark_fulfillment_delivered_if_complete(conn: &mut MysqlConnection, fulfillment_id: &str) -> QueryResult<()> {
+    let (order_id, fulfillment_status) = order_fulfillments::table
+        .find(fulfillment_id)
+        .select((order_fulfillments::order_id, order_fulfillments::status))
+        .first::<(String, String)>(conn)?;
+
+    if fulfillment_status != OrderStatus::Shipped.to_string() {
+        return Ok(());
+    }
+
+    let undelivered = shipments::table
+        .filter(shipments::fulfillment_id.eq(fulfillment_id))
+        .filter(shipments::status.ne(SHIPMENT_STATUS_DELIVERED))
+        .count()
+        .get_result::<i64>(conn)?;
+
+    if undelivered > 0 {
+        return Ok(());
+    }
+
+    set_fulfillment_status(conn, &[fulfillment_id.to_string()], &OrderStatus::Delivered)?;
+
+    sync_order_status(conn, &order_id, Some("包裹已全部签收"), None)?;
+    Ok(())
+}
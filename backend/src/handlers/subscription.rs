@@ -0,0 +1,472 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use chrono::Datelike;
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::mysql::MysqlConnection;
+use diesel::result::Error as DieselError;
+use serde_json::json;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::middleware::get_user_id_from_request;
+use crate::models::order::merge_order_items;
+use crate::models::subscription::{
+    CreateSubscriptionDto, NewSubscription, NewSubscriptionItem, NewSubscriptionRun, Subscription,
+    SubscriptionFrequency, SubscriptionItem, SubscriptionResponse, SubscriptionRun, SubscriptionRunStatus,
+    SubscriptionStatus,
+};
+use crate::schema::{addresses, products, subscription_items, subscription_runs, subscriptions};
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+// 执行记录最多返回的条数
+const MAX_RUNS: i64 = 50;
+
+// 加载订购的商品清单并构建响应
+fn subscription_response(conn: &mut MysqlConnection, subscription: Subscription) -> QueryResult<SubscriptionResponse> {
+    let items = SubscriptionItem::belonging_to(&subscription)
+        .select(SubscriptionItem::as_select())
+        .load(conn)?;
+    Ok(SubscriptionResponse::new(subscription, items))
+}
+
+// 获取当前用户的定期订购
+pub async fn get_subscriptions(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let result = subscriptions::table
+        .filter(subscriptions::user_id.eq(&user_id))
+        .order(subscriptions::created_at.desc())
+        .select(Subscription::as_select())
+        .load(&mut conn)
+        .and_then(|list| {
+            let items = SubscriptionItem::belonging_to(&list)
+                .select(SubscriptionItem::as_select())
+                .load(&mut conn)?
+                .grouped_by(&list);
+            Ok(list
+                .into_iter()
+                .zip(items)
+                .map(|(subscription, items)| SubscriptionResponse::new(subscription, items))
+                .collect::<Vec<_>>())
+        });
+
+    match result {
+        Ok(list) => HttpResponse::Ok().json(list),
+        Err(e) => {
+            println!("读取定期订购失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "读取定期订购失败"
+            }))
+        }
+    }
+}
+
+// 创建定期订购
+pub async fn create_subscription(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    subscription_dto: web::Json<CreateSubscriptionDto>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let frequency = match SubscriptionFrequency::from_str(&subscription_dto.frequency) {
+        Ok(frequency) => frequency,
+        Err(_) => return HttpResponse::BadRequest().json(json!({
+            "message": "下单周期只能是 weekly 或 monthly"
+        })),
+    };
+
+    let lines = match merge_order_items(&subscription_dto.items) {
+        Ok(lines) => lines,
+        Err(message) => return HttpResponse::BadRequest().json(json!({
+            "message": message
+        })),
+    };
+
+    // 首次下单时间：指定日期的零点（UTC），默认为一个周期之后
+    let now = chrono::Utc::now().naive_utc();
+    let next_run_at = match subscription_dto.start_date {
+        Some(date) if date < now.date() => return HttpResponse::BadRequest().json(json!({
+            "message": "首次下单日期不能早于今天"
+        })),
+        Some(date) => date.and_time(chrono::NaiveTime::MIN),
+        None => frequency.advance(now, now.day()),
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let product_ids: Vec<&String> = lines.iter().map(|(product_id, _)| product_id).collect();
+    let existing: Vec<String> = match products::table
+        .filter(products::id.eq_any(&product_ids))
+        .select(products::id)
+        .load(&mut conn) {
+        Ok(ids) => ids,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "读取产品信息失败"
+        })),
+    };
+    let missing: Vec<&String> = product_ids.into_iter().filter(|id| !existing.contains(id)).collect();
+    if !missing.is_empty() {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "部分产品不存在",
+            "product_ids": missing
+        }));
+    }
+
+    if let Some(address_id) = &subscription_dto.address_id {
+        let owned = addresses::table
+            .find(address_id)
+            .filter(addresses::user_id.eq(&user_id))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .unwrap_or(0);
+        if owned == 0 {
+            return HttpResponse::NotFound().json(json!({
+                "message": "地址不存在或不属于当前用户"
+            }));
+        }
+    }
+
+    let new_subscription = NewSubscription::new(user_id, frequency, subscription_dto.address_id.clone(), next_run_at);
+    let result = conn.transaction::<_, DieselError, _>(|conn| {
+        diesel::insert_into(subscriptions::table)
+            .values(&new_subscription)
+            .execute(conn)?;
+
+        let items: Vec<NewSubscriptionItem> = lines
+            .iter()
+            .map(|(product_id, quantity)| NewSubscriptionItem {
+                id: Uuid::new_v4().to_string(),
+                subscription_id: new_subscription.id.clone(),
+                product_id: product_id.clone(),
+                quantity: *quantity,
+            })
+            .collect();
+        diesel::insert_into(subscription_items::table)
+            .values(&items)
+            .execute(conn)?;
+
+        let subscription = subscriptions::table
+            .find(&new_subscription.id)
+            .select(Subscription::as_select())
+            .first(conn)?;
+        subscription_response(conn, subscription)
+    });
+
+    match result {
+        Ok(subscription) => HttpResponse::Created().json(subscription),
+        Err(e) => {
+            println!("创建定期订购失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "创建定期订购失败"
+            }))
+        }
+    }
+}
+
+// 获取定期订购详情
+pub async fn get_subscription(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let subscription_id = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let result = subscriptions::table
+        .find(&subscription_id)
+        .filter(subscriptions::user_id.eq(&user_id))
+        .select(Subscription::as_select())
+        .first(&mut conn)
+        .optional()
+        .and_then(|subscription| subscription.map(|s| subscription_response(&mut conn, s)).transpose());
+
+    match result {
+        Ok(Some(subscription)) => HttpResponse::Ok().json(subscription),
+        Ok(None) => HttpResponse::NotFound().json(json!({
+            "message": "定期订购不存在"
+        })),
+        Err(e) => {
+            println!("读取定期订购失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "读取定期订购失败"
+            }))
+        }
+    }
+}
+
+// 获取定期订购最近的执行记录，最新的在前
+pub async fn get_subscription_runs(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let subscription_id = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let owned = subscriptions::table
+        .find(&subscription_id)
+        .filter(subscriptions::user_id.eq(&user_id))
+        .count()
+        .get_result::<i64>(&mut conn)
+        .unwrap_or(0);
+    if owned == 0 {
+        return HttpResponse::NotFound().json(json!({
+            "message": "定期订购不存在"
+        }));
+    }
+
+    match subscription_runs::table
+        .filter(subscription_runs::subscription_id.eq(&subscription_id))
+        .order(subscription_runs::created_at.desc())
+        .limit(MAX_RUNS)
+        .select(SubscriptionRun::as_select())
+        .load(&mut conn) {
+        Ok(runs) => HttpResponse::Ok().json(runs),
+        Err(e) => {
+            println!("读取定期订购执行记录失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "读取定期订购执行记录失败"
+            }))
+        }
+    }
+}
+
+// 用户对定期订购的操作
+#[derive(Clone, Copy)]
+enum SubscriptionAction {
+    // 跳过下一次下单
+    Skip,
+    Pause,
+    Resume,
+    Cancel,
+}
+
+enum ActionError {
+    Database(DieselError),
+    NotFound,
+    // 当前状态不允许该操作
+    InvalidState(&'static str),
+}
+
+impl From<DieselError> for ActionError {
+    fn from(error: DieselError) -> Self {
+        ActionError::Database(error)
+    }
+}
+
+fn apply_action(
+    conn: &mut MysqlConnection,
+    user_id: &str,
+    subscription_id: &str,
+    action: SubscriptionAction,
+) -> Result<SubscriptionResponse, ActionError> {
+    conn.transaction::<_, ActionError, _>(|conn| {
+        // 锁定订购，避免与定期订购任务同时修改下次下单时间
+        let subscription = subscriptions::table
+            .find(subscription_id)
+            .filter(subscriptions::user_id.eq(user_id))
+            .select(Subscription::as_select())
+            .for_update()
+            .first(conn)
+            .optional()?
+            .ok_or(ActionError::NotFound)?;
+
+        let now = chrono::Utc::now().naive_utc();
+        let status = subscription.get_status();
+        let target = subscriptions::table.find(subscription_id);
+
+        match action {
+            SubscriptionAction::Skip => {
+                if status != SubscriptionStatus::Active {
+                    return Err(ActionError::InvalidState("只有生效中的定期订购可以跳过下一次下单"));
+                }
+                diesel::insert_into(subscription_runs::table)
+                    .values(&NewSubscriptionRun::new(
+                        subscription.id.clone(),
+                        subscription.next_run_at,
+                        SubscriptionRunStatus::Skipped,
+                        None,
+                        None,
+                    ))
+                    .execute(conn)?;
+                diesel::update(target)
+                    .set((
+                        subscriptions::next_run_at.eq(subscription.next_run_after(now)),
+                        subscriptions::updated_at.eq(now),
+                    ))
+                    .execute(conn)?;
+            }
+            SubscriptionAction::Pause => {
+                if status != SubscriptionStatus::Active {
+                    return Err(ActionError::InvalidState("只有生效中的定期订购可以暂停"));
+                }
+                diesel::update(target)
+                    .set((
+                        subscriptions::status.eq(SubscriptionStatus::Paused.to_string()),
+                        subscriptions::updated_at.eq(now),
+                    ))
+                    .execute(conn)?;
+            }
+            SubscriptionAction::Resume => {
+                if status != SubscriptionStatus::Paused {
+                    return Err(ActionError::InvalidState("只有已暂停的定期订购可以恢复"));
+                }
+                // 暂停期间错过的周期不再补单
+                let next_run_at = if subscription.next_run_at <= now {
+                    subscription.next_run_after(now)
+                } else {
+                    subscription.next_run_at
+                };
+                diesel::update(target)
+                    .set((
+                        subscriptions::status.eq(SubscriptionStatus::Active.to_string()),
+                        subscriptions::next_run_at.eq(next_run_at),
+                        subscriptions::updated_at.eq(now),
+                    ))
+                    .execute(conn)?;
+            }
+            SubscriptionAction::Cancel => {
+                if status == SubscriptionStatus::Cancelled {
+                    return Err(ActionError::InvalidState("定期订购已取消"));
+                }
+                diesel::update(target)
+                    .set((
+                        subscriptions::status.eq(SubscriptionStatus::Cancelled.to_string()),
+                        subscriptions::updated_at.eq(now),
+                    ))
+                    .execute(conn)?;
+            }
+        }
+
+        let subscription = subscriptions::table
+            .find(subscription_id)
+            .select(Subscription::as_select())
+            .first(conn)?;
+        Ok(subscription_response(conn, subscription)?)
+    })
+}
+
+async fn update_subscription(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    action: SubscriptionAction,
+) -> HttpResponse {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let subscription_id = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    match apply_action(&mut conn, &user_id, &subscription_id, action) {
+        Ok(subscription) => HttpResponse::Ok().json(subscription),
+        Err(ActionError::NotFound) => HttpResponse::NotFound().json(json!({
+            "message": "定期订购不存在"
+        })),
+        Err(ActionError::InvalidState(message)) => HttpResponse::Conflict().json(json!({
+            "message": message
+        })),
+        Err(ActionError::Database(e)) => {
+            println!("更新定期订购失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "更新定期订购失败"
+            }))
+        }
+    }
+}
+
+// 跳过下一次下单
+pub async fn skip_subscription(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+) -> impl Responder {
+    update_subscription(req, pool, path, SubscriptionAction::Skip).await
+}
+
+// 暂停定期订购
+pub async fn pause_subscription(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+) -> impl Responder {
+    update_subscription(req, pool, path, SubscriptionAction::Pause).await
+}
+
+// 恢复已暂停的定期订购
+pub async fn resume_subscription(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+) -> impl Responder {
+    update_subscription(req, pool, path, SubscriptionAction::Resume).await
+}
+
+// 取消定期订购，取消后不可恢复
+pub async fn cancel_subscription(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+) -> impl Responder {
+    update_subscription(req, pool, path, SubscriptionAction::Cancel).await
+}
@@ -0,0 +1,161 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::mysql::MysqlConnection;
+use serde_json::json;
+
+use crate::middleware::is_admin;
+use crate::models::tax_rate::{NewTaxRate, SetTaxRateDto, TaxRate};
+use crate::schema::tax_rates;
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+// 获取分类税率列表
+pub async fn get_tax_rates(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+) -> impl Responder {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    match tax_rates::table
+        .order(tax_rates::category.asc())
+        .select(TaxRate::as_select())
+        .load(&mut conn) {
+        Ok(rates) => HttpResponse::Ok().json(json!({
+            "tax_rates": rates
+        })),
+        Err(e) => {
+            println!("读取分类税率失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "读取分类税率失败"
+            }))
+        }
+    }
+}
+
+// 设置分类税率，分类不存在时新增
+pub async fn set_tax_rate(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    rate_dto: web::Json<SetTaxRateDto>,
+) -> impl Responder {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    let category = path.into_inner().trim().to_string();
+    if category.is_empty() {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "商品分类不能为空"
+        }));
+    }
+
+    if !(0.0..=100.0).contains(&rate_dto.rate) {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "税率必须在0到100之间"
+        }));
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        let existing = tax_rates::table
+            .filter(tax_rates::category.eq(&category))
+            .select(tax_rates::id)
+            .first::<String>(conn)
+            .optional()?;
+
+        let id = match existing {
+            Some(id) => {
+                diesel::update(tax_rates::table.find(&id))
+                    .set((
+                        tax_rates::rate.eq(rate_dto.rate),
+                        tax_rates::updated_at.eq(chrono::Utc::now().naive_utc()),
+                    ))
+                    .execute(conn)?;
+                id
+            }
+            None => {
+                let new_rate = NewTaxRate::new(category.clone(), rate_dto.rate);
+                diesel::insert_into(tax_rates::table)
+                    .values(&new_rate)
+                    .execute(conn)?;
+                new_rate.id
+            }
+        };
+
+        tax_rates::table
+            .find(&id)
+            .select(TaxRate::as_select())
+            .first(conn)
+    });
+
+    match result {
+        Ok(rate) => HttpResponse::Ok().json(json!({
+            "message": "分类税率已更新",
+            "tax_rate": rate
+        })),
+        Err(e) => {
+            println!("设置分类税率失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "设置分类税率失败"
+            }))
+        }
+    }
+}
+
+// 删除分类税率，该分类恢复使用默认税率
+pub async fn delete_tax_rate(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+) -> impl Responder {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    let category = path.into_inner();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    match diesel::delete(tax_rates::table.filter(tax_rates::category.eq(category.trim())))
+        .execute(&mut conn) {
+        Ok(0) => HttpResponse::NotFound().json(json!({
+            "message": "该分类未单独设置税率"
+        })),
+        Ok(_) => HttpResponse::Ok().json(json!({
+            "message": "分类税率已删除"
+        })),
+        Err(e) => {
+            println!("删除分类税率失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "删除分类税率失败"
+            }))
+        }
+    }
+}
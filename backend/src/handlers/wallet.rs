@@ -0,0 +1,175 @@
+use actix_web::{web, HttpResponse, Responder, HttpRequest};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::mysql::MysqlConnection;
+use serde_json::json;
+
+use crate::middleware::{get_user_id_from_request, is_admin};
+use crate::models::wallet::{AdjustWalletDto, WalletQuery, WalletTransaction, WalletTransactionKind};
+use crate::schema::{users, wallet_transactions};
+use crate::services::wallet::{balance, post_transaction, WalletError};
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+const DEFAULT_WALLET_PAGE_SIZE: i64 = 20;
+const MAX_WALLET_PAGE_SIZE: i64 = 100;
+
+// 获取当前用户的钱包余额和流水，最新的在前
+pub async fn get_wallet(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    query: web::Query<WalletQuery>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(DEFAULT_WALLET_PAGE_SIZE).clamp(1, MAX_WALLET_PAGE_SIZE);
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    let current_balance = match balance(&mut conn, &user_id) {
+        Ok(balance) => balance,
+        Err(e) => {
+            println!("读取钱包余额失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "读取钱包失败"
+            }));
+        }
+    };
+
+    match wallet_transactions::table
+        .filter(wallet_transactions::user_id.eq(&user_id))
+        .order((wallet_transactions::created_at.desc(), wallet_transactions::id.desc()))
+        .offset((page - 1) * limit)
+        .limit(limit)
+        .select(WalletTransaction::as_select())
+        .load(&mut conn) {
+        Ok(transactions) => HttpResponse::Ok().json(json!({
+            "balance": current_balance,
+            "transactions": transactions,
+            "pagination": {
+                "page": page,
+                "limit": limit
+            }
+        })),
+        Err(e) => {
+            println!("读取钱包流水失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "读取钱包失败"
+            }))
+        }
+    }
+}
+
+// 管理员入账或扣减，金额必须大于0且必须填写原因
+async fn adjust_wallet(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    adjust_dto: web::Json<AdjustWalletDto>,
+    kind: WalletTransactionKind,
+) -> HttpResponse {
+    if !is_admin(&req) {
+        return HttpResponse::Forbidden().json(json!({
+            "message": "只有管理员可以访问此资源"
+        }));
+    }
+
+    let admin_id = match get_user_id_from_request(&req) {
+        Some(id) => id,
+        None => return HttpResponse::Unauthorized().json(json!({
+            "message": "无法获取用户ID"
+        })),
+    };
+
+    let user_id = path.into_inner();
+    let adjust_dto = adjust_dto.into_inner();
+    if !adjust_dto.amount.is_finite() || adjust_dto.amount <= 0.0 {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "金额必须大于0"
+        }));
+    }
+    let reason = adjust_dto.reason.trim();
+    if reason.is_empty() {
+        return HttpResponse::BadRequest().json(json!({
+            "message": "请填写调整原因"
+        }));
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => return HttpResponse::InternalServerError().json(json!({
+            "message": "数据库连接错误"
+        })),
+    };
+
+    match users::table.find(&user_id).select(users::id).first::<String>(&mut conn).optional() {
+        Ok(Some(_)) => {}
+        Ok(None) => return HttpResponse::NotFound().json(json!({
+            "message": "用户不存在"
+        })),
+        Err(e) => {
+            println!("查询用户失败: {:?}", e);
+            return HttpResponse::InternalServerError().json(json!({
+                "message": "调整钱包余额失败"
+            }));
+        }
+    }
+
+    let amount = match kind {
+        WalletTransactionKind::AdminDebit => -adjust_dto.amount,
+        _ => adjust_dto.amount,
+    };
+    let result = conn.transaction::<_, WalletError, _>(|conn| {
+        post_transaction(conn, &user_id, kind, amount, Some(reason), None, Some(&admin_id))
+    });
+
+    match result {
+        Ok(transaction) => {
+            println!("管理员 {} 调整用户 {} 钱包余额 {:.2}: {}", admin_id, user_id, amount, reason);
+            HttpResponse::Ok().json(json!({
+                "balance": transaction.balance_after,
+                "transaction": transaction
+            }))
+        }
+        Err(WalletError::InsufficientBalance(balance)) => HttpResponse::Conflict().json(json!({
+            "message": format!("钱包余额不足，当前余额 {:.2}", balance)
+        })),
+        Err(WalletError::Database(e)) => {
+            println!("调整钱包余额失败: {:?}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "message": "调整钱包余额失败"
+            }))
+        }
+    }
+}
+
+// 管理员给用户钱包入账
+pub async fn credit_wallet(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    adjust_dto: web::Json<AdjustWalletDto>,
+) -> impl Responder {
+    adjust_wallet(req, pool, path, adjust_dto, WalletTransactionKind::AdminCredit).await
+}
+
+// 管理员扣减用户钱包余额，余额不足时返回 409
+pub async fn debit_wallet(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    adjust_dto: web::Json<AdjustWalletDto>,
+) -> impl Responder {
+    adjust_wallet(req, pool, path, adjust_dto, WalletTransactionKind::AdminDebit).await
+}
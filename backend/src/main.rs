@@ -12,6 +12,9 @@ mod routes;
 mod middleware;
 mod utils;
 mod schema;
+mod services;
+#[cfg(test)]
+mod test_fixtures;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -30,6 +33,15 @@ async fn main() -> std::io::Result<()> {
         .build(manager)
         .expect("Failed to create DB connection pool");
     
+    // 启动超时未支付订单自动取消任务
+    services::order_expiry::spawn(pool.clone(), services::order_expiry::OrderExpiryConfig::from_env());
+    
+    // 启动自动确认收货及退货期结束后自动完成订单任务
+    services::order_completion::spawn(pool.clone(), services::order_completion::OrderCompletionConfig::from_env());
+    
+    // 启动定期订购自动下单任务
+    services::subscription::spawn(pool.clone(), services::subscription::SubscriptionConfig::from_env());
+    
     // 获取配置
     let host = env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
     let port = env::var("PORT").unwrap_or_else(|_| "8080".to_string());
@@ -58,7 +70,10 @@ async fn main() -> std::io::Result<()> {
             .configure(routes::auth::config)
             .configure(routes::product::config)
             .configure(routes::cart::config)
+            .configure(routes::guest::config)
             .configure(routes::order::config)
+            .configure(routes::subscription::config)
+            .configure(routes::payment::config)
             .configure(routes::favorite::config)
             .configure(routes::user_profile::config)
             .configure(routes::vendor_profile::config)
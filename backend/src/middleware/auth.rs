@@ -144,4 +144,12 @@ pub fn get_user_id_from_request(req: &HttpRequest) -> Option<String> {
     }
     
     user_id
-} 
\ No newline at end of file
+}
+
+// 判断当前请求用户是否为管理员
+pub fn is_admin(req: &HttpRequest) -> bool {
+    req.extensions()
+        .get::<Claims>()
+        .map(|claims| claims.role == UserRole::Admin)
+        .unwrap_or(false)
+}
@@ -0,0 +1,108 @@
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    error::{ErrorInternalServerError, ErrorUnauthorized},
+    web,
+    Error,
+    HttpMessage,
+};
+use diesel::prelude::*;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::mysql::MysqlConnection;
+use futures::future::{ready, LocalBoxFuture, Ready};
+
+use crate::config::jwt::Claims;
+use crate::models::user::UserRole;
+use crate::schema::users;
+use crate::services::cart_token::{verify, CART_TOKEN_HEADER};
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+// 游客购物车认证中间件：校验 X-Cart-Token 购物车令牌，
+// 通过后以游客用户身份写入请求扩展，购物车接口可按普通顾客处理
+pub struct GuestCartAuthentication;
+
+impl<S, B> Transform<S, ServiceRequest> for GuestCartAuthentication
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = GuestCartAuthenticationMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(GuestCartAuthenticationMiddleware { service }))
+    }
+}
+
+pub struct GuestCartAuthenticationMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for GuestCartAuthenticationMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let token = req
+            .headers()
+            .get(CART_TOKEN_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        if token.is_empty() {
+            println!("拒绝游客请求: 缺少购物车令牌");
+            return Box::pin(async move {
+                Err(ErrorUnauthorized("Missing cart token"))
+            });
+        }
+
+        let guest_id = match verify(&token) {
+            Ok(guest_id) => guest_id,
+            Err(message) => {
+                println!("购物车令牌验证失败: {}", message);
+                return Box::pin(async move {
+                    Err(ErrorUnauthorized("Invalid or expired cart token"))
+                });
+            }
+        };
+
+        // 游客注册为正式用户后，原购物车令牌失效
+        let pool = match req.app_data::<web::Data<DbPool>>() {
+            Some(pool) => pool.clone(),
+            None => return Box::pin(async move {
+                Err(ErrorInternalServerError("Database pool not configured"))
+            }),
+        };
+        let is_guest = pool.get().ok().and_then(|mut conn| {
+            users::table
+                .find(&guest_id)
+                .select(users::is_guest)
+                .first::<bool>(&mut conn)
+                .ok()
+        });
+
+        if is_guest != Some(true) {
+            println!("购物车令牌对应的游客不存在或已注册: {}", guest_id);
+            return Box::pin(async move {
+                Err(ErrorUnauthorized("Invalid or expired cart token"))
+            });
+        }
+
+        req.extensions_mut().insert(Claims::new(guest_id, UserRole::Customer));
+
+        Box::pin(self.service.call(req))
+    }
+}
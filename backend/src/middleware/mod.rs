@@ -1,3 +1,5 @@
 pub mod auth;
+pub mod guest;
 
-pub use auth::{Authentication, RequireAuth, get_user_id_from_request}; 
\ No newline at end of file
+pub use auth::{Authentication, RequireAuth, get_user_id_from_request, is_admin};
+pub use guest::GuestCartAuthentication; 
\ No newline at end of file
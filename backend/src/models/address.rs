@@ -0,0 +1,156 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use diesel::prelude::*;
+use uuid::Uuid;
+use crate::schema::addresses;
+use crate::models::order::ShippingAddressDto;
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Clone)]
+#[diesel(table_name = addresses)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct Address {
+    pub id: String,
+    pub user_id: String,
+    pub recipient_name: String,
+    pub phone: String,
+    pub province: String,
+    pub city: String,
+    pub district: String,
+    pub street: String,
+    pub postal_code: Option<String>,
+    pub is_default_shipping: bool,
+    pub is_default_billing: bool,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = addresses)]
+pub struct NewAddress {
+    pub id: String,
+    pub user_id: String,
+    pub recipient_name: String,
+    pub phone: String,
+    pub province: String,
+    pub city: String,
+    pub district: String,
+    pub street: String,
+    pub postal_code: Option<String>,
+    pub is_default_shipping: bool,
+    pub is_default_billing: bool,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, AsChangeset)]
+#[diesel(table_name = addresses)]
+pub struct UpdateAddress {
+    pub recipient_name: Option<String>,
+    pub phone: Option<String>,
+    pub province: Option<String>,
+    pub city: Option<String>,
+    pub district: Option<String>,
+    pub street: Option<String>,
+    // Some(None) 表示清空邮编
+    pub postal_code: Option<Option<String>>,
+    pub is_default_shipping: Option<bool>,
+    pub is_default_billing: Option<bool>,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+// 邮编去掉首尾空白，空字符串视为未填写
+pub fn normalize_postal_code(postal_code: Option<String>) -> Option<String> {
+    postal_code
+        .map(|code| code.trim().to_string())
+        .filter(|code| !code.is_empty())
+}
+
+// 区分未提供的字段和显式提交的 null：未提供为 None，null 为 Some(None)
+fn nullable<'de, D>(deserializer: D) -> Result<Option<Option<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer).map(Some)
+}
+
+// 新增地址请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateAddressDto {
+    pub recipient_name: String,
+    pub phone: String,
+    pub province: Option<String>,
+    pub city: Option<String>,
+    pub district: Option<String>,
+    pub street: String,
+    pub postal_code: Option<String>,
+    pub is_default_shipping: Option<bool>,
+    pub is_default_billing: Option<bool>,
+}
+
+// 修改地址请求，未提供的字段保持不变；postal_code 提交 null 或空字符串时清空
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateAddressDto {
+    pub recipient_name: Option<String>,
+    pub phone: Option<String>,
+    pub province: Option<String>,
+    pub city: Option<String>,
+    pub district: Option<String>,
+    pub street: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub postal_code: Option<Option<String>>,
+    pub is_default_shipping: Option<bool>,
+    pub is_default_billing: Option<bool>,
+}
+
+impl NewAddress {
+    pub fn new(user_id: String, dto: &CreateAddressDto) -> Self {
+        let now = chrono::Utc::now().naive_utc();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            recipient_name: dto.recipient_name.trim().to_string(),
+            phone: dto.phone.trim().to_string(),
+            province: dto.province.clone().unwrap_or_default(),
+            city: dto.city.clone().unwrap_or_default(),
+            district: dto.district.clone().unwrap_or_default(),
+            street: dto.street.trim().to_string(),
+            postal_code: normalize_postal_code(dto.postal_code.clone()),
+            is_default_shipping: dto.is_default_shipping.unwrap_or(false),
+            is_default_billing: dto.is_default_billing.unwrap_or(false),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+impl From<Address> for ShippingAddressDto {
+    fn from(address: Address) -> Self {
+        Self {
+            recipient_name: address.recipient_name,
+            phone: address.phone,
+            province: Some(address.province),
+            city: Some(address.city),
+            district: Some(address.district),
+            street: address.street,
+            postal_code: address.postal_code,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_postal_code() {
+        let dto: UpdateAddressDto = serde_json::from_str(r#"{"street": "人民路1号"}"#).unwrap();
+        assert_eq!(dto.postal_code, None);
+
+        let dto: UpdateAddressDto = serde_json::from_str(r#"{"postal_code": null}"#).unwrap();
+        assert_eq!(dto.postal_code, Some(None));
+
+        let dto: UpdateAddressDto = serde_json::from_str(r#"{"postal_code": " 100000 "}"#).unwrap();
+        assert_eq!(dto.postal_code.map(normalize_postal_code), Some(Some("100000".to_string())));
+
+        assert_eq!(normalize_postal_code(Some("  ".to_string())), None);
+    }
+}
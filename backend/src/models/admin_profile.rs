@@ -22,6 +22,7 @@ pub struct AdminProfile {
     pub tax_rate: f64,
     pub payment_gateways: String,
     pub log_level: String,
+    pub prices_include_tax: bool,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
 }
@@ -43,6 +44,7 @@ pub struct NewAdminProfile {
     pub tax_rate: f64,
     pub payment_gateways: String,
     pub log_level: String,
+    pub prices_include_tax: bool,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
 }
@@ -62,6 +64,7 @@ pub struct UpdateAdminProfile {
     pub tax_rate: Option<f64>,
     pub payment_gateways: Option<String>,
     pub log_level: Option<String>,
+    pub prices_include_tax: Option<bool>,
     pub updated_at: chrono::NaiveDateTime,
 }
 
@@ -80,6 +83,7 @@ pub struct AdminSettingsRequest {
     pub tax_rate: Option<f64>,
     pub payment_gateways: Option<Vec<String>>,
     pub log_level: Option<String>,
+    pub prices_include_tax: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -96,6 +100,7 @@ pub struct AdminSettingsResponse {
     pub tax_rate: f64,
     pub payment_gateways: Vec<String>,
     pub log_level: String,
+    pub prices_include_tax: bool,
 }
 
 impl AdminProfile {
@@ -115,6 +120,7 @@ impl AdminProfile {
             tax_rate: 13.0,
             payment_gateways: "alipay,wechatpay".to_string(),
             log_level: "info".to_string(),
+            prices_include_tax: false,
             created_at: Utc::now().naive_utc(),
             updated_at: Utc::now().naive_utc(),
         }
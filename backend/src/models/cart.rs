@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use diesel::prelude::*;
+use std::collections::HashMap;
 use crate::schema::cart_items;
+use crate::models::product::{BackorderMode, Product};
+use crate::models::promotion::AppliedPromotion;
 use chrono::Utc;
 
 #[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable)]
@@ -77,7 +80,23 @@ impl From<CartItem> for CartItemResponse {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CartResponse {
     pub items: Vec<CartItemWithProductResponse>,
+    // 商品标价合计
+    pub subtotal: f64,
+    // 自动促销的优惠合计
+    pub promotion_discount: f64,
+    // 促销和优惠券的优惠合计
+    pub discount: f64,
+    // 优惠后的金额，不含税
     pub total: f64,
+    pub coupon: Option<CartCouponResponse>,
+}
+
+// 购物车中已使用的优惠券；优惠券当前不可用时 error 为原因，优惠金额为0，结账时会被拒绝
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CartCouponResponse {
+    pub code: String,
+    pub discount: f64,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -88,4 +107,117 @@ pub struct CartItemWithProductResponse {
     pub product_price: f64,
     pub quantity: i32,
     pub subtotal: f64,
-} 
\ No newline at end of file
+    pub discount: f64,
+    // 该商品享受的自动促销及各自的优惠金额
+    pub promotions: Vec<AppliedPromotion>,
+}
+
+// 再次购买时加入购物车的商品，价格为当前价格
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ReorderAddedItem {
+    pub product_id: String,
+    pub product_name: String,
+    pub price: f64,
+    // 原订单中的数量
+    pub requested_quantity: i32,
+    // 本次加入购物车的数量，库存不足时少于原订单数量
+    pub quantity: i32,
+}
+
+// 再次购买时未能加入购物车的商品
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ReorderSkippedItem {
+    pub product_id: String,
+    pub product_name: Option<String>,
+    pub requested_quantity: i32,
+    pub reason: String,
+}
+
+// 计算再次购买的结果：已下架的商品跳过；与购物车中已有数量合并后不超过当前库存（接受预订的商品除外），
+// 库存不足时尽量加入剩余库存，没有剩余库存时跳过
+pub fn plan_reorder(
+    lines: &[(String, i32)],
+    products: &[Product],
+    cart_quantities: &HashMap<String, i32>,
+) -> (Vec<ReorderAddedItem>, Vec<ReorderSkippedItem>) {
+    let mut added = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (product_id, requested) in lines {
+        let product = match products.iter().find(|p| &p.id == product_id) {
+            Some(product) => product,
+            None => {
+                skipped.push(ReorderSkippedItem {
+                    product_id: product_id.clone(),
+                    product_name: None,
+                    requested_quantity: *requested,
+                    reason: "产品已下架".to_string(),
+                });
+                continue;
+            }
+        };
+
+        // 接受缺货预订的商品不受库存限制，预订上限在结账时检查
+        let in_cart = cart_quantities.get(product_id).copied().unwrap_or(0);
+        let quantity = if product.get_backorder_mode() == BackorderMode::None {
+            (*requested).min((product.stock - in_cart).max(0))
+        } else {
+            *requested
+        };
+
+        if quantity == 0 {
+            skipped.push(ReorderSkippedItem {
+                product_id: product_id.clone(),
+                product_name: Some(product.name.clone()),
+                requested_quantity: *requested,
+                reason: if product.stock <= 0 { "库存不足".to_string() } else { "购物车中数量已达库存上限".to_string() },
+            });
+            continue;
+        }
+
+        added.push(ReorderAddedItem {
+            product_id: product_id.clone(),
+            product_name: product.name.clone(),
+            price: product.price,
+            requested_quantity: *requested,
+            quantity,
+        });
+    }
+
+    (added, skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::product;
+
+    #[test]
+    fn test_plan_reorder() {
+        let lines = vec![
+            ("p1".to_string(), 2),
+            ("p2".to_string(), 5),
+            ("gone".to_string(), 1),
+            ("p3".to_string(), 1),
+            ("p4".to_string(), 1),
+            ("p5".to_string(), 4),
+        ];
+        let mut products: Vec<Product> = [("p1", 10), ("p2", 3), ("p3", 0), ("p4", 2), ("p5", 0)]
+            .into_iter()
+            .map(|(id, stock)| Product { stock, ..product(id, 10.0) })
+            .collect();
+        // p5 缺货但接受预订
+        products[4].backorder_mode = BackorderMode::Preorder.to_string();
+        let mut cart = HashMap::new();
+        cart.insert("p1".to_string(), 1);
+        cart.insert("p4".to_string(), 2);
+
+        let (added, skipped) = plan_reorder(&lines, &products, &cart);
+
+        let added: Vec<(&str, i32)> = added.iter().map(|item| (item.product_id.as_str(), item.quantity)).collect();
+        assert_eq!(added, vec![("p1", 2), ("p2", 3), ("p5", 4)]);
+
+        let skipped: Vec<&str> = skipped.iter().map(|item| item.product_id.as_str()).collect();
+        assert_eq!(skipped, vec!["gone", "p3", "p4"]);
+    }
+}
@@ -0,0 +1,245 @@
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+use uuid::Uuid;
+use std::fmt;
+use std::str::FromStr;
+use crate::schema::{coupons, coupon_redemptions};
+
+// 优惠方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscountType {
+    // 按适用商品金额的百分比优惠
+    Percentage,
+    // 固定金额，不超过适用商品的金额
+    Fixed,
+}
+
+impl fmt::Display for DiscountType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DiscountType::Percentage => write!(f, "percentage"),
+            DiscountType::Fixed => write!(f, "fixed"),
+        }
+    }
+}
+
+impl FromStr for DiscountType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "percentage" => Ok(DiscountType::Percentage),
+            "fixed" => Ok(DiscountType::Fixed),
+            _ => Err(()),
+        }
+    }
+}
+
+// 优惠码统一转为大写保存和查询
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+// 逗号分隔的适用范围，空列表表示不限
+fn scope_values(value: &Option<String>) -> Vec<String> {
+    value
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(|item| item.trim().to_lowercase())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+// 商品是否在适用范围内，同时设置多个范围时需全部满足；优惠券和自动促销共用
+pub fn in_scope(
+    product_ids: &Option<String>,
+    categories: &Option<String>,
+    vendor_ids: &Option<String>,
+    product_id: &str,
+    category: Option<&str>,
+    vendor_id: &str,
+) -> bool {
+    let matches = |scope: &Option<String>, value: Option<&str>| {
+        let values = scope_values(scope);
+        values.is_empty()
+            || value.map(|value| values.contains(&value.trim().to_lowercase())).unwrap_or(false)
+    };
+    matches(product_ids, Some(product_id)) && matches(categories, category) && matches(vendor_ids, Some(vendor_id))
+}
+
+// 保存适用范围，去掉空白项，全部为空时保存为 NULL
+pub fn join_scope(values: Option<Vec<String>>) -> Option<String> {
+    let values: Vec<String> = values
+        .unwrap_or_default()
+        .into_iter()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect();
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.join(","))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Clone)]
+#[diesel(table_name = coupons)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct Coupon {
+    pub id: String,
+    pub code: String,
+    pub discount_type: String,
+    // 百分比优惠时为百分比，固定金额优惠时为金额
+    pub discount_value: f64,
+    // 适用商品金额达到该值才能使用
+    pub min_spend: f64,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at: Option<chrono::NaiveDateTime>,
+    // 总使用次数上限，为空表示不限
+    pub usage_limit: Option<i32>,
+    // 每个用户的使用次数上限，为空表示不限
+    pub per_user_limit: Option<i32>,
+    pub used_count: i32,
+    // 以下适用范围为逗号分隔，为空表示不限
+    pub product_ids: Option<String>,
+    pub categories: Option<String>,
+    pub vendor_ids: Option<String>,
+    pub active: bool,
+    pub created_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl Coupon {
+    pub fn get_discount_type(&self) -> DiscountType {
+        DiscountType::from_str(&self.discount_type).unwrap_or(DiscountType::Fixed)
+    }
+
+    // 商品是否在优惠券的适用范围内
+    pub fn applies_to(&self, product_id: &str, category: Option<&str>, vendor_id: &str) -> bool {
+        in_scope(&self.product_ids, &self.categories, &self.vendor_ids, product_id, category, vendor_id)
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = coupons)]
+pub struct NewCoupon {
+    pub id: String,
+    pub code: String,
+    pub discount_type: String,
+    pub discount_value: f64,
+    pub min_spend: f64,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at: Option<chrono::NaiveDateTime>,
+    pub usage_limit: Option<i32>,
+    pub per_user_limit: Option<i32>,
+    pub used_count: i32,
+    pub product_ids: Option<String>,
+    pub categories: Option<String>,
+    pub vendor_ids: Option<String>,
+    pub active: bool,
+    pub created_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+// 优惠券使用记录，每张使用了优惠券的订单一条
+#[derive(Insertable)]
+#[diesel(table_name = coupon_redemptions)]
+pub struct NewCouponRedemption {
+    pub id: String,
+    pub coupon_id: String,
+    pub user_id: String,
+    pub order_id: String,
+    pub discount: f64,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl NewCouponRedemption {
+    pub fn new(coupon_id: String, user_id: String, order_id: String, discount: f64) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            coupon_id,
+            user_id,
+            order_id,
+            discount,
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+// 创建优惠券请求
+#[derive(Debug, Deserialize)]
+pub struct CreateCouponDto {
+    pub code: String,
+    pub discount_type: DiscountType,
+    pub discount_value: f64,
+    pub min_spend: Option<f64>,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at: Option<chrono::NaiveDateTime>,
+    pub usage_limit: Option<i32>,
+    pub per_user_limit: Option<i32>,
+    pub product_ids: Option<Vec<String>>,
+    pub categories: Option<Vec<String>>,
+    pub vendor_ids: Option<Vec<String>>,
+}
+
+// 修改优惠券请求，只修改提交的字段；优惠方式、金额和适用范围创建后不能修改
+#[derive(Debug, Deserialize)]
+pub struct UpdateCouponDto {
+    pub active: Option<bool>,
+    pub min_spend: Option<f64>,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at: Option<chrono::NaiveDateTime>,
+    pub usage_limit: Option<i32>,
+    pub per_user_limit: Option<i32>,
+}
+
+#[derive(Debug, AsChangeset)]
+#[diesel(table_name = coupons)]
+pub struct CouponChangeset {
+    pub active: Option<bool>,
+    pub min_spend: Option<f64>,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at: Option<chrono::NaiveDateTime>,
+    pub usage_limit: Option<i32>,
+    pub per_user_limit: Option<i32>,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+// 购物车使用优惠券请求
+#[derive(Debug, Deserialize)]
+pub struct ApplyCouponDto {
+    pub code: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::coupon;
+
+    #[test]
+    fn test_applies_to() {
+        let mut coupon = coupon(DiscountType::Percentage, 10.0);
+        assert!(coupon.applies_to("p1", None, "v1"));
+
+        coupon.categories = Some("Books, Toys & Games".to_string());
+        assert!(coupon.applies_to("p1", Some("books"), "v1"));
+        assert!(!coupon.applies_to("p1", Some("Electronics"), "v1"));
+        assert!(!coupon.applies_to("p1", None, "v1"));
+
+        // 多个范围需全部满足
+        coupon.vendor_ids = Some("v2".to_string());
+        assert!(!coupon.applies_to("p1", Some("books"), "v1"));
+        assert!(coupon.applies_to("p1", Some("books"), "v2"));
+    }
+
+    #[test]
+    fn test_join_scope() {
+        assert_eq!(join_scope(None), None);
+        assert_eq!(join_scope(Some(vec![" ".to_string()])), None);
+        assert_eq!(join_scope(Some(vec!["p1".to_string(), " p2 ".to_string()])), Some("p1,p2".to_string()));
+    }
+}
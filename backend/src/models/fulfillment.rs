@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+use uuid::Uuid;
+use std::str::FromStr;
+use crate::schema::order_fulfillments;
+use crate::models::order::{Order, OrderStatus};
+
+// 按商家拆分的子订单，每个商家独立维护状态、运费和金额
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Associations, Clone)]
+#[diesel(belongs_to(Order))]
+#[diesel(table_name = order_fulfillments)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct OrderFulfillment {
+    pub id: String,
+    pub order_id: String,
+    pub vendor_id: String,
+    pub status: String,
+    pub subtotal: f64,
+    pub tax: f64,
+    pub shipping_fee: f64,
+    pub total: f64,
+    pub shipped_at: Option<chrono::NaiveDateTime>,
+    pub delivered_at: Option<chrono::NaiveDateTime>,
+    pub return_window_ends_at: Option<chrono::NaiveDateTime>,
+    pub completed_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = order_fulfillments)]
+pub struct NewOrderFulfillment {
+    pub id: String,
+    pub order_id: String,
+    pub vendor_id: String,
+    pub status: String,
+    pub subtotal: f64,
+    pub tax: f64,
+    pub shipping_fee: f64,
+    pub total: f64,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl NewOrderFulfillment {
+    pub fn new(order_id: String, vendor_id: String) -> Self {
+        let now = chrono::Utc::now().naive_utc();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            order_id,
+            vendor_id,
+            status: OrderStatus::Pending.to_string(),
+            subtotal: 0.0,
+            tax: 0.0,
+            shipping_fee: 0.0,
+            total: 0.0,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+impl OrderFulfillment {
+    pub fn get_status(&self) -> Result<OrderStatus, ()> {
+        OrderStatus::from_str(&self.status)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderFulfillmentResponse {
+    pub id: String,
+    pub vendor_id: String,
+    pub status: OrderStatus,
+    pub subtotal: f64,
+    pub tax: f64,
+    pub shipping_fee: f64,
+    pub total: f64,
+    pub shipped_at: Option<chrono::NaiveDateTime>,
+    pub delivered_at: Option<chrono::NaiveDateTime>,
+    pub return_window_ends_at: Option<chrono::NaiveDateTime>,
+    pub completed_at: Option<chrono::NaiveDateTime>,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl From<OrderFulfillment> for OrderFulfillmentResponse {
+    fn from(fulfillment: OrderFulfillment) -> Self {
+        Self {
+            status: fulfillment.get_status().unwrap_or(OrderStatus::Pending),
+            id: fulfillment.id,
+            vendor_id: fulfillment.vendor_id,
+            subtotal: fulfillment.subtotal,
+            tax: fulfillment.tax,
+            shipping_fee: fulfillment.shipping_fee,
+            total: fulfillment.total,
+            shipped_at: fulfillment.shipped_at,
+            delivered_at: fulfillment.delivered_at,
+            return_window_ends_at: fulfillment.return_window_ends_at,
+            completed_at: fulfillment.completed_at,
+            updated_at: fulfillment.updated_at,
+        }
+    }
+}
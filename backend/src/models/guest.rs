@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use crate::models::order::{CheckoutDto, ShippingAddressDto};
+
+// 游客结账请求，联系邮箱和收货地址必填
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GuestCheckoutDto {
+    pub email: String,
+    pub shipping_address: ShippingAddressDto,
+}
+
+impl From<GuestCheckoutDto> for CheckoutDto {
+    fn from(dto: GuestCheckoutDto) -> Self {
+        Self {
+            shipping_address: Some(dto.shipping_address),
+            address_id: None,
+            email: Some(dto.email),
+            wallet_amount: None,
+        }
+    }
+}
+
+// 游客按订单号和联系邮箱查询订单
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GuestOrderLookupDto {
+    pub order_number: String,
+    pub email: String,
+}
+
+// 游客注册为正式用户
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConvertGuestDto {
+    pub email: String,
+    pub password: String,
+}
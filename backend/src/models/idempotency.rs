@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+use uuid::Uuid;
+use crate::schema::idempotency_keys;
+
+// 带 Idempotency-Key 的请求记录：response_status 为空表示请求仍在处理中
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Clone)]
+#[diesel(table_name = idempotency_keys)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct IdempotencyKey {
+    pub id: String,
+    pub user_id: String,
+    pub endpoint: String,
+    pub idempotency_key: String,
+    pub request_fingerprint: String,
+    pub order_id: Option<String>,
+    pub response_status: Option<i32>,
+    pub response_body: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = idempotency_keys)]
+pub struct NewIdempotencyKey {
+    pub id: String,
+    pub user_id: String,
+    pub endpoint: String,
+    pub idempotency_key: String,
+    pub request_fingerprint: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl NewIdempotencyKey {
+    pub fn new(user_id: String, endpoint: String, idempotency_key: String, request_fingerprint: String) -> Self {
+        let now = chrono::Utc::now().naive_utc();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            endpoint,
+            idempotency_key,
+            request_fingerprint,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+use uuid::Uuid;
+use crate::schema::invoices;
+use crate::models::order::Order;
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Associations, Clone)]
+#[diesel(belongs_to(Order))]
+#[diesel(table_name = invoices)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct Invoice {
+    pub id: String,
+    pub order_id: String,
+    pub invoice_number: String,
+    pub issued_at: chrono::NaiveDateTime,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = invoices)]
+pub struct NewInvoice {
+    pub id: String,
+    pub order_id: String,
+    pub invoice_number: String,
+    pub issued_at: chrono::NaiveDateTime,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl NewInvoice {
+    pub fn new(order_id: String, invoice_number: String) -> Self {
+        let now = chrono::Utc::now().naive_utc();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            order_id,
+            invoice_number,
+            issued_at: now,
+            created_at: now,
+        }
+    }
+}
+
+// 发票查询参数，format 可选 html（默认）、pdf、json
+#[derive(Debug, Deserialize)]
+pub struct InvoiceQuery {
+    pub format: Option<String>,
+}
+
+// 发票上的卖方信息
+#[derive(Debug, Serialize, Clone)]
+pub struct InvoiceSeller {
+    pub vendor_id: String,
+    pub name: String,
+    pub address: Option<String>,
+    pub email: Option<String>,
+}
+
+// 发票明细行，单价、优惠、税率和税额取自下单时保存的订单项；金额为扣除优惠后的价税合计
+#[derive(Debug, Serialize, Clone)]
+pub struct InvoiceLine {
+    pub product_id: String,
+    pub description: String,
+    pub seller: String,
+    pub quantity: i32,
+    pub unit_price: f64,
+    pub discount: f64,
+    pub tax_rate: f64,
+    pub tax_amount: f64,
+    pub amount: f64,
+}
+
+// 渲染发票所需的完整数据
+#[derive(Debug, Serialize)]
+pub struct InvoiceDocument {
+    pub invoice_number: String,
+    pub issued_at: chrono::NaiveDateTime,
+    pub site_name: String,
+    pub currency_symbol: String,
+    pub order_id: String,
+    pub order_number: String,
+    pub order_date: chrono::NaiveDateTime,
+    pub bill_to_name: String,
+    pub bill_to_phone: String,
+    pub bill_to_address: String,
+    pub sellers: Vec<InvoiceSeller>,
+    pub lines: Vec<InvoiceLine>,
+    // 按单价和数量计算的商品金额合计
+    pub list_total: f64,
+    // 订单的促销和优惠券优惠合计
+    pub discount: f64,
+    pub coupon_code: Option<String>,
+    pub subtotal: f64,
+    pub tax: f64,
+    pub total: f64,
+}
@@ -5,4 +5,21 @@ pub mod product;
 pub mod order;
 pub mod cart;
 pub mod admin_profile;
-pub mod favorite; 
\ No newline at end of file
+pub mod favorite;
+pub mod address;
+pub mod fulfillment;
+pub mod shipment;
+pub mod invoice;
+pub mod tax_rate;
+pub mod idempotency;
+pub mod order_history;
+pub mod order_message;
+pub mod guest;
+pub mod order_export;
+pub mod risk; 
+pub mod subscription; 
+pub mod notification; 
+pub mod payment; 
+pub mod wallet; 
+pub mod coupon; 
+pub mod promotion; 
\ No newline at end of file
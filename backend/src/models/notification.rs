@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+use uuid::Uuid;
+use crate::schema::notifications;
+
+// 通知类型
+pub const NOTIFICATION_SUBSCRIPTION_ORDER_CREATED: &str = "subscription_order_created";
+pub const NOTIFICATION_SUBSCRIPTION_RUN_FAILED: &str = "subscription_run_failed";
+
+// 站内通知
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Clone)]
+#[diesel(table_name = notifications)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct Notification {
+    pub id: String,
+    pub user_id: String,
+    pub kind: String,
+    pub title: String,
+    pub content: String,
+    // 关联对象的ID，例如定期订购ID
+    pub related_id: Option<String>,
+    pub read_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = notifications)]
+pub struct NewNotification {
+    pub id: String,
+    pub user_id: String,
+    pub kind: String,
+    pub title: String,
+    pub content: String,
+    pub related_id: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl NewNotification {
+    pub fn new(user_id: String, kind: &str, title: String, content: String, related_id: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            kind: kind.to_string(),
+            title,
+            content,
+            related_id,
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+// 通知列表查询参数
+#[derive(Debug, Deserialize)]
+pub struct NotificationQuery {
+    pub unread: Option<bool>,  // 为 true 时只返回未读通知
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
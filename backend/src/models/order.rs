@@ -3,7 +3,10 @@ use uuid::Uuid;
 use diesel::prelude::*;
 use std::fmt;
 use std::str::FromStr;
-use crate::schema::{orders, order_items};
+use crate::schema::{orders, order_items, order_addresses};
+use crate::models::fulfillment::OrderFulfillmentResponse;
+use crate::models::shipment::ShipmentResponse;
+use crate::models::promotion::AppliedPromotion;
 use chrono::Utc;
 use diesel::sql_types::*;
 
@@ -13,6 +16,8 @@ pub enum OrderStatus {
     Processing,
     Shipped,
     Delivered,
+    // 退货期结束，订单关闭
+    Completed,
     Cancelled,
 }
 
@@ -23,11 +28,50 @@ impl fmt::Display for OrderStatus {
             OrderStatus::Processing => write!(f, "processing"),
             OrderStatus::Shipped => write!(f, "shipped"),
             OrderStatus::Delivered => write!(f, "delivered"),
+            OrderStatus::Completed => write!(f, "completed"),
             OrderStatus::Cancelled => write!(f, "cancelled"),
         }
     }
 }
 
+impl OrderStatus {
+    // 履约进度，数值越大表示越接近完成
+    fn progress(&self) -> u8 {
+        match self {
+            OrderStatus::Pending => 0,
+            OrderStatus::Processing => 1,
+            OrderStatus::Shipped => 2,
+            OrderStatus::Delivered => 3,
+            OrderStatus::Completed => 4,
+            OrderStatus::Cancelled => 5,
+        }
+    }
+
+    // 根据各商家子订单的状态推导父订单状态：
+    // 已取消的子订单不参与计算，其余子订单中进度最慢的状态即父订单状态；
+    // 全部取消时父订单为已取消
+    pub fn from_fulfillments(statuses: &[OrderStatus]) -> OrderStatus {
+        if statuses.is_empty() {
+            return OrderStatus::Pending;
+        }
+
+        statuses
+            .iter()
+            .filter(|status| **status != OrderStatus::Cancelled)
+            .min_by_key(|status| status.progress())
+            .cloned()
+            .unwrap_or(OrderStatus::Cancelled)
+    }
+
+    // 商家可以进行的子订单状态变更，只能向前推进：处理中可以改为处理中或已发货，其余状态都不能再由商家修改
+    pub fn vendor_can_transition(&self, to: &OrderStatus) -> bool {
+        matches!(
+            (self, to),
+            (OrderStatus::Processing, OrderStatus::Processing) | (OrderStatus::Processing, OrderStatus::Shipped)
+        )
+    }
+}
+
 impl FromStr for OrderStatus {
     type Err = ();
 
@@ -37,6 +81,7 @@ impl FromStr for OrderStatus {
             "processing" => Ok(OrderStatus::Processing),
             "shipped" => Ok(OrderStatus::Shipped),
             "delivered" => Ok(OrderStatus::Delivered),
+            "completed" => Ok(OrderStatus::Completed),
             "cancelled" => Ok(OrderStatus::Cancelled),
             _ => Err(()),
         }
@@ -58,6 +103,22 @@ pub struct OrderItem {
     pub quantity: i32,
     #[diesel(sql_type = Double)]
     pub price: f64,
+    #[diesel(sql_type = Nullable<VarChar>)]
+    pub fulfillment_id: Option<String>,
+    #[diesel(sql_type = Double)]
+    pub tax_rate: f64,
+    #[diesel(sql_type = Double)]
+    pub tax_amount: f64,
+    #[diesel(sql_type = Double)]
+    pub total: f64,
+    // 尚未分配到库存、等待到货的数量
+    #[diesel(sql_type = Integer)]
+    pub backordered_quantity: i32,
+    #[diesel(sql_type = Nullable<Date>)]
+    pub expected_ship_date: Option<chrono::NaiveDate>,
+    // 按商品标价计算的优惠金额，total 为优惠后的金额
+    #[diesel(sql_type = Double)]
+    pub discount: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, QueryableByName)]
@@ -67,8 +128,14 @@ pub struct Order {
     #[diesel(sql_type = VarChar)]
     pub id: String,
     #[diesel(sql_type = VarChar)]
+    pub order_number: String,
+    #[diesel(sql_type = VarChar)]
     pub user_id: String,
     #[diesel(sql_type = Double)]
+    pub subtotal: f64,
+    #[diesel(sql_type = Double)]
+    pub tax: f64,
+    #[diesel(sql_type = Double)]
     pub total: f64,
     #[diesel(sql_type = VarChar)]
     pub status: String,
@@ -76,17 +143,40 @@ pub struct Order {
     pub created_at: chrono::NaiveDateTime,
     #[diesel(sql_type = Timestamp)]
     pub updated_at: chrono::NaiveDateTime,
+    // 下单时填写的联系邮箱，游客凭订单号和该邮箱查询订单
+    #[diesel(sql_type = Nullable<VarChar>)]
+    pub contact_email: Option<String>,
+    // 风控待审核，审核通过前不能处理发货
+    #[diesel(sql_type = Bool)]
+    pub on_hold: bool,
+    // 使用钱包余额支付的金额
+    #[diesel(sql_type = Double)]
+    pub wallet_amount: f64,
+    // 按商品标价计算的优惠金额合计
+    #[diesel(sql_type = Double)]
+    pub discount: f64,
+    // 下单时使用的优惠券
+    #[diesel(sql_type = Nullable<VarChar>)]
+    pub coupon_code: Option<String>,
 }
 
 #[derive(Insertable)]
 #[diesel(table_name = orders)]
 pub struct NewOrder {
     pub id: String,
+    pub order_number: String,
     pub user_id: String,
     pub status: String,
+    pub subtotal: f64,
+    pub tax: f64,
     pub total: f64,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    pub contact_email: Option<String>,
+    pub on_hold: bool,
+    pub wallet_amount: f64,
+    pub discount: f64,
+    pub coupon_code: Option<String>,
 }
 
 #[derive(Insertable)]
@@ -97,16 +187,162 @@ pub struct NewOrderItem {
     pub product_id: String,
     pub quantity: i32,
     pub price: f64,
+    pub fulfillment_id: Option<String>,
+    pub tax_rate: f64,
+    pub tax_amount: f64,
+    pub total: f64,
+    pub backordered_quantity: i32,
+    pub expected_ship_date: Option<chrono::NaiveDate>,
+    pub discount: f64,
+}
+
+// 订单收货地址快照，下单后不再修改
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Associations, Clone)]
+#[diesel(belongs_to(Order))]
+#[diesel(table_name = order_addresses)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct OrderAddress {
+    pub id: String,
+    pub order_id: String,
+    pub recipient_name: String,
+    pub phone: String,
+    pub province: String,
+    pub city: String,
+    pub district: String,
+    pub street: String,
+    pub postal_code: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl OrderAddress {
+    // 省市区和详细地址拼接为一行
+    pub fn full_address(&self) -> String {
+        [&self.province, &self.city, &self.district, &self.street]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .map(String::as_str)
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = order_addresses)]
+pub struct NewOrderAddress {
+    pub id: String,
+    pub order_id: String,
+    pub recipient_name: String,
+    pub phone: String,
+    pub province: String,
+    pub city: String,
+    pub district: String,
+    pub street: String,
+    pub postal_code: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl NewOrderAddress {
+    pub fn from_dto(order_id: String, dto: &ShippingAddressDto) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            order_id,
+            recipient_name: dto.recipient_name.trim().to_string(),
+            phone: dto.phone.trim().to_string(),
+            province: dto.province.clone().unwrap_or_default(),
+            city: dto.city.clone().unwrap_or_default(),
+            district: dto.district.clone().unwrap_or_default(),
+            street: dto.street.trim().to_string(),
+            postal_code: dto.postal_code.clone(),
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+// 结账时提交的收货地址
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShippingAddressDto {
+    pub recipient_name: String,
+    pub phone: String,
+    pub province: Option<String>,
+    pub city: Option<String>,
+    pub district: Option<String>,
+    pub street: String,
+    pub postal_code: Option<String>,
+}
+
+// DTO for checkout
+// shipping_address 优先；否则使用 address_id 指定的地址簿地址；都未提供时使用默认地址
+// email 为订单联系邮箱，游客结账时必填
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckoutDto {
+    pub shipping_address: Option<ShippingAddressDto>,
+    pub address_id: Option<String>,
+    pub email: Option<String>,
+    // 使用钱包余额支付的金额，超过订单金额时按订单金额扣除
+    pub wallet_amount: Option<f64>,
+}
+
+impl CheckoutDto {
+    // 解析结账请求体：请求体为空时使用默认值，有内容但格式错误时返回错误，不能忽略客户端提交的地址
+    pub fn from_body(body: &[u8]) -> Result<Option<CheckoutDto>, serde_json::Error> {
+        if body.iter().all(u8::is_ascii_whitespace) {
+            return Ok(None);
+        }
+        serde_json::from_slice(body).map(Some)
+    }
+}
+
+// 扣除钱包余额后还需支付的金额，按分取整
+pub fn amount_due(total: f64, wallet_amount: f64) -> f64 {
+    (((total - wallet_amount) * 100.0).round() / 100.0).max(0.0)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrderAddressResponse {
+    pub recipient_name: String,
+    pub phone: String,
+    pub province: String,
+    pub city: String,
+    pub district: String,
+    pub street: String,
+    pub postal_code: Option<String>,
+}
+
+impl From<OrderAddress> for OrderAddressResponse {
+    fn from(address: OrderAddress) -> Self {
+        Self {
+            recipient_name: address.recipient_name,
+            phone: address.phone,
+            province: address.province,
+            city: address.city,
+            district: address.district,
+            street: address.street,
+            postal_code: address.postal_code,
+        }
+    }
 }
 
 // DTO for order responses
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OrderResponse {
     pub id: String,
+    pub order_number: String,
     pub user_id: String,
+    pub subtotal: f64,
+    pub tax: f64,
     pub total: f64,
     pub status: OrderStatus,
     pub items: Vec<OrderItemResponse>,
+    pub shipping_address: Option<OrderAddressResponse>,
+    pub fulfillments: Vec<OrderFulfillmentResponse>,
+    pub shipments: Vec<ShipmentResponse>,
+    pub contact_email: Option<String>,
+    pub on_hold: bool,
+    pub wallet_amount: f64,
+    // 还需通过支付渠道支付的金额
+    pub amount_due: f64,
+    pub discount: f64,
+    pub coupon_code: Option<String>,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
 }
@@ -117,51 +353,403 @@ pub struct OrderItemResponse {
     pub product_id: String,
     pub quantity: i32,
     pub price: f64,
+    pub tax_rate: f64,
+    pub tax_amount: f64,
+    pub total: f64,
+    pub backordered_quantity: i32,
+    pub expected_ship_date: Option<chrono::NaiveDate>,
+    pub discount: f64,
+    // 享受的自动促销及各自的优惠金额
+    pub promotions: Vec<AppliedPromotion>,
 }
 
 // DTO for updating order status
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateOrderStatusDto {
     pub status: String,
+    // 变更原因，写入订单状态记录
+    pub reason: Option<String>,
 }
 
 impl Order {
-    pub fn new(user_id: String, total: f64) -> Self {
+    pub fn new(order_number: String, user_id: String, total: f64) -> Self {
         let now = chrono::Utc::now();
         Self {
             id: Uuid::new_v4().to_string(),
+            order_number,
             user_id,
+            subtotal: total,
+            tax: 0.0,
             total,
             status: OrderStatus::Pending.to_string(),
             created_at: now.naive_utc(),
             updated_at: now.naive_utc(),
+            contact_email: None,
+            on_hold: false,
+            wallet_amount: 0.0,
+            discount: 0.0,
+            coupon_code: None,
         }
     }
 
     pub fn get_status(&self) -> Result<OrderStatus, ()> {
         OrderStatus::from_str(&self.status)
     }
+
+    // 扣除钱包余额后还需通过支付渠道支付的金额
+    pub fn amount_due(&self) -> f64 {
+        amount_due(self.total, self.wallet_amount)
+    }
     
     // 从数据库记录转换为Diesel兼容的Order
-    pub fn from_db_order(order_id: i32, user_id: i32, status: &str, total: f64, created_at: chrono::NaiveDateTime, updated_at: chrono::NaiveDateTime) -> Self {
+    pub fn from_db_order(order_id: i32, order_number: &str, user_id: i32, status: &str, total: f64, created_at: chrono::NaiveDateTime, updated_at: chrono::NaiveDateTime) -> Self {
         Self {
             id: order_id.to_string(),
+            order_number: order_number.to_string(),
             user_id: user_id.to_string(),
             status: status.to_string(),
+            subtotal: total,
+            tax: 0.0,
             total,
             created_at,
             updated_at,
+            contact_email: None,
+            on_hold: false,
+            wallet_amount: 0.0,
+            discount: 0.0,
+            coupon_code: None,
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+// 立即购买：直接提交商品和数量，收货地址规则与结账相同
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CreateOrderDto {
     pub items: Vec<OrderItemDto>,
+    pub shipping_address: Option<ShippingAddressDto>,
+    pub address_id: Option<String>,
+    pub wallet_amount: Option<f64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OrderItemDto {
     pub product_id: String,
     pub quantity: i32,
-} 
\ No newline at end of file
+}
+
+// 校验立即购买的商品列表，同一商品出现多次时合并数量，保持首次出现的顺序
+pub fn merge_order_items(items: &[OrderItemDto]) -> Result<Vec<(String, i32)>, String> {
+    if items.is_empty() {
+        return Err("订单商品不能为空".to_string());
+    }
+
+    let mut merged: Vec<(String, i32)> = Vec::new();
+    for item in items {
+        let product_id = item.product_id.trim();
+        if product_id.is_empty() {
+            return Err("产品ID不能为空".to_string());
+        }
+        if item.quantity <= 0 {
+            return Err("数量必须大于0".to_string());
+        }
+
+        match merged.iter_mut().find(|(id, _)| id == product_id) {
+            Some((_, quantity)) => {
+                *quantity = quantity.checked_add(item.quantity).ok_or_else(|| "商品数量过大".to_string())?;
+            }
+            None => merged.push((product_id.to_string(), item.quantity)),
+        }
+    }
+
+    Ok(merged)
+}
+
+// 订单列表默认每页数量与上限
+pub const DEFAULT_ORDER_PAGE_SIZE: i64 = 20;
+pub const MAX_ORDER_PAGE_SIZE: i64 = 100;
+
+// 订单列表查询参数：page/limit 偏移分页，或传入上一页返回的 cursor 进行游标分页
+#[derive(Debug, Deserialize, Default)]
+pub struct OrderListQuery {
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    pub status: Option<String>,
+    pub start_date: Option<String>,  // 格式: YYYY-MM-DD
+    pub end_date: Option<String>,    // 格式: YYYY-MM-DD
+    pub customer_id: Option<String>,
+    pub product_id: Option<String>,
+    pub sort_by: Option<String>,        // created_at（默认）或 total
+    pub sort_direction: Option<String>, // desc（默认）或 asc
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderSortField {
+    CreatedAt,
+    Total,
+}
+
+impl OrderSortField {
+    fn key(&self) -> &'static str {
+        match self {
+            OrderSortField::CreatedAt => "created_at",
+            OrderSortField::Total => "total",
+        }
+    }
+}
+
+// 游标记录上一页最后一条订单的排序值和ID，ID 用于排序值相同时确定先后
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderCursor {
+    CreatedAt(chrono::NaiveDateTime, String),
+    Total(f64, String),
+}
+
+impl OrderCursor {
+    pub fn from_order(order: &Order, sort_by: OrderSortField) -> Self {
+        match sort_by {
+            OrderSortField::CreatedAt => OrderCursor::CreatedAt(order.created_at, order.id.clone()),
+            OrderSortField::Total => OrderCursor::Total(order.total, order.id.clone()),
+        }
+    }
+
+    // 编码为 URL 安全的字符串，例如 created_at~1760659200000000~<订单ID>
+    pub fn encode(&self) -> String {
+        match self {
+            OrderCursor::CreatedAt(created_at, id) => {
+                format!("created_at~{}~{}", created_at.and_utc().timestamp_micros(), id)
+            }
+            OrderCursor::Total(total, id) => format!("total~{}~{}", total, id),
+        }
+    }
+
+    pub fn decode(cursor: &str, sort_by: OrderSortField) -> Result<Self, String> {
+        let invalid = || "无效的分页游标".to_string();
+        let mut parts = cursor.splitn(3, '~');
+        let (key, value, id) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(key), Some(value), Some(id)) if !id.is_empty() => (key, value, id.to_string()),
+            _ => return Err(invalid()),
+        };
+
+        if key != sort_by.key() {
+            return Err("分页游标与排序字段不一致".to_string());
+        }
+
+        match sort_by {
+            OrderSortField::CreatedAt => {
+                let micros = value.parse::<i64>().map_err(|_| invalid())?;
+                let created_at = chrono::DateTime::from_timestamp_micros(micros)
+                    .ok_or_else(invalid)?
+                    .naive_utc();
+                Ok(OrderCursor::CreatedAt(created_at, id))
+            }
+            OrderSortField::Total => {
+                let total = value.parse::<f64>().map_err(|_| invalid())?;
+                if !total.is_finite() {
+                    return Err(invalid());
+                }
+                Ok(OrderCursor::Total(total, id))
+            }
+        }
+    }
+}
+
+// 校验后的订单列表查询条件
+#[derive(Debug, Clone)]
+pub struct OrderListParams {
+    pub page: i64,
+    pub limit: i64,
+    pub cursor: Option<OrderCursor>,
+    pub status: Option<OrderStatus>,
+    pub start_date: Option<chrono::NaiveDateTime>,
+    pub end_date: Option<chrono::NaiveDateTime>,
+    pub customer_id: Option<String>,
+    pub product_id: Option<String>,
+    pub sort_by: OrderSortField,
+    pub descending: bool,
+}
+
+impl OrderListQuery {
+    pub fn parse(&self) -> Result<OrderListParams, String> {
+        let non_empty = |value: &Option<String>| {
+            value.as_ref().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+        };
+
+        let sort_by = match non_empty(&self.sort_by).as_deref() {
+            None | Some("created_at") => OrderSortField::CreatedAt,
+            Some("total") => OrderSortField::Total,
+            Some(_) => return Err("不支持的排序字段，可选 created_at、total".to_string()),
+        };
+
+        let descending = match non_empty(&self.sort_direction).as_deref() {
+            None | Some("desc") => true,
+            Some("asc") => false,
+            Some(_) => return Err("排序方向只能是 asc 或 desc".to_string()),
+        };
+
+        let status = match non_empty(&self.status) {
+            Some(status) => Some(OrderStatus::from_str(&status).map_err(|_| "无效的订单状态".to_string())?),
+            None => None,
+        };
+
+        let parse_date = |value: &Option<String>, time: &str| -> Result<Option<chrono::NaiveDateTime>, String> {
+            match non_empty(value) {
+                Some(date) => chrono::NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M:%S")
+                    .map(Some)
+                    .map_err(|_| "日期格式应为 YYYY-MM-DD".to_string()),
+                None => Ok(None),
+            }
+        };
+        let start_date = parse_date(&self.start_date, "00:00:00")?;
+        let end_date = parse_date(&self.end_date, "23:59:59")?;
+
+        let cursor = match non_empty(&self.cursor) {
+            Some(cursor) => Some(OrderCursor::decode(&cursor, sort_by)?),
+            None => None,
+        };
+
+        Ok(OrderListParams {
+            page: self.page.unwrap_or(1).max(1),
+            limit: self.limit.unwrap_or(DEFAULT_ORDER_PAGE_SIZE).clamp(1, MAX_ORDER_PAGE_SIZE),
+            cursor,
+            status,
+            start_date,
+            end_date,
+            customer_id: non_empty(&self.customer_id),
+            product_id: non_empty(&self.product_id),
+            sort_by,
+            descending,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parent_status_waits_for_slowest_vendor() {
+        let statuses = vec![OrderStatus::Shipped, OrderStatus::Processing];
+        assert_eq!(OrderStatus::from_fulfillments(&statuses), OrderStatus::Processing);
+
+        let statuses = vec![OrderStatus::Shipped, OrderStatus::Shipped];
+        assert_eq!(OrderStatus::from_fulfillments(&statuses), OrderStatus::Shipped);
+
+        let statuses = vec![OrderStatus::Delivered, OrderStatus::Shipped];
+        assert_eq!(OrderStatus::from_fulfillments(&statuses), OrderStatus::Shipped);
+
+        let statuses = vec![OrderStatus::Completed, OrderStatus::Delivered];
+        assert_eq!(OrderStatus::from_fulfillments(&statuses), OrderStatus::Delivered);
+
+        let statuses = vec![OrderStatus::Completed, OrderStatus::Cancelled];
+        assert_eq!(OrderStatus::from_fulfillments(&statuses), OrderStatus::Completed);
+    }
+
+    #[test]
+    fn test_parent_status_ignores_cancelled_vendors() {
+        let statuses = vec![OrderStatus::Cancelled, OrderStatus::Shipped];
+        assert_eq!(OrderStatus::from_fulfillments(&statuses), OrderStatus::Shipped);
+
+        let statuses = vec![OrderStatus::Cancelled, OrderStatus::Cancelled];
+        assert_eq!(OrderStatus::from_fulfillments(&statuses), OrderStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_vendor_transitions_only_move_forward() {
+        assert!(OrderStatus::Processing.vendor_can_transition(&OrderStatus::Processing));
+        assert!(OrderStatus::Processing.vendor_can_transition(&OrderStatus::Shipped));
+
+        // 已取消的子订单不能恢复
+        assert!(!OrderStatus::Cancelled.vendor_can_transition(&OrderStatus::Processing));
+        assert!(!OrderStatus::Cancelled.vendor_can_transition(&OrderStatus::Shipped));
+        // 已签收和已完成的子订单不能退回已发货或处理中
+        assert!(!OrderStatus::Delivered.vendor_can_transition(&OrderStatus::Shipped));
+        assert!(!OrderStatus::Delivered.vendor_can_transition(&OrderStatus::Processing));
+        assert!(!OrderStatus::Completed.vendor_can_transition(&OrderStatus::Shipped));
+        assert!(!OrderStatus::Completed.vendor_can_transition(&OrderStatus::Processing));
+        // 已发货后不能退回处理中，也不能重复发货
+        assert!(!OrderStatus::Shipped.vendor_can_transition(&OrderStatus::Processing));
+        assert!(!OrderStatus::Shipped.vendor_can_transition(&OrderStatus::Shipped));
+        // 待支付的子订单不能由商家处理
+        assert!(!OrderStatus::Pending.vendor_can_transition(&OrderStatus::Processing));
+        assert!(!OrderStatus::Pending.vendor_can_transition(&OrderStatus::Shipped));
+    }
+
+    #[test]
+    fn test_checkout_body() {
+        assert!(CheckoutDto::from_body(b"").unwrap().is_none());
+        assert!(CheckoutDto::from_body(b"  \n").unwrap().is_none());
+        let dto = CheckoutDto::from_body(br#"{"address_id": "a-1"}"#).unwrap().unwrap();
+        assert_eq!(dto.address_id.as_deref(), Some("a-1"));
+        assert!(CheckoutDto::from_body(br#"{"address_id": "#).is_err());
+        assert!(CheckoutDto::from_body(br#"{"wallet_amount": "all"}"#).is_err());
+    }
+
+    #[test]
+    fn test_parent_status_without_fulfillments() {
+        assert_eq!(OrderStatus::from_fulfillments(&[]), OrderStatus::Pending);
+    }
+
+    #[test]
+    fn test_order_list_query_defaults() {
+        let params = OrderListQuery::default().parse().unwrap();
+        assert_eq!(params.page, 1);
+        assert_eq!(params.limit, DEFAULT_ORDER_PAGE_SIZE);
+        assert_eq!(params.sort_by, OrderSortField::CreatedAt);
+        assert!(params.descending);
+        assert!(params.cursor.is_none());
+
+        let query = OrderListQuery {
+            limit: Some(1000),
+            status: Some("Shipped".to_string()),
+            sort_by: Some("total".to_string()),
+            sort_direction: Some("asc".to_string()),
+            end_date: Some("2026-10-17".to_string()),
+            ..Default::default()
+        };
+        let params = query.parse().unwrap();
+        assert_eq!(params.limit, MAX_ORDER_PAGE_SIZE);
+        assert_eq!(params.status, Some(OrderStatus::Shipped));
+        assert_eq!(params.sort_by, OrderSortField::Total);
+        assert!(!params.descending);
+        assert_eq!(params.end_date.unwrap().to_string(), "2026-10-17 23:59:59");
+    }
+
+    #[test]
+    fn test_order_list_query_rejects_invalid_values() {
+        let query = OrderListQuery { status: Some("lost".to_string()), ..Default::default() };
+        assert!(query.parse().is_err());
+
+        let query = OrderListQuery { start_date: Some("17/10/2026".to_string()), ..Default::default() };
+        assert!(query.parse().is_err());
+
+        let query = OrderListQuery { sort_by: Some("user_id".to_string()), ..Default::default() };
+        assert!(query.parse().is_err());
+    }
+
+    #[test]
+    fn test_order_cursor_round_trip() {
+        let created_at = chrono::NaiveDate::from_ymd_opt(2026, 10, 17).unwrap()
+            .and_hms_micro_opt(8, 30, 0, 123456).unwrap();
+        let cursor = OrderCursor::CreatedAt(created_at, "a1b2-c3".to_string());
+        assert_eq!(OrderCursor::decode(&cursor.encode(), OrderSortField::CreatedAt), Ok(cursor.clone()));
+        assert!(OrderCursor::decode(&cursor.encode(), OrderSortField::Total).is_err());
+
+        let cursor = OrderCursor::Total(113.45, "d4".to_string());
+        assert_eq!(OrderCursor::decode(&cursor.encode(), OrderSortField::Total), Ok(cursor));
+        assert!(OrderCursor::decode("garbage", OrderSortField::Total).is_err());
+    }
+
+    #[test]
+    fn test_merge_order_items() {
+        let item = |product_id: &str, quantity: i32| OrderItemDto { product_id: product_id.to_string(), quantity };
+
+        let merged = merge_order_items(&[item("p2", 1), item("p1", 2), item("p2", 3)]).unwrap();
+        assert_eq!(merged, vec![("p2".to_string(), 4), ("p1".to_string(), 2)]);
+
+        assert!(merge_order_items(&[]).is_err());
+        assert!(merge_order_items(&[item("p1", 0)]).is_err());
+        assert!(merge_order_items(&[item(" ", 1)]).is_err());
+        assert!(merge_order_items(&[item("p1", i32::MAX), item("p1", 1)]).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+use serde::Deserialize;
+
+// 订单导出查询参数，筛选条件与订单列表相同（OrderListQuery），分页参数不生效
+#[derive(Debug, Deserialize)]
+pub struct OrderExportQuery {
+    pub format: Option<String>,  // csv（默认）或 xlsx
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn parse(format: Option<&str>) -> Result<Self, String> {
+        match format.map(|f| f.trim().to_lowercase()).as_deref() {
+            None | Some("") | Some("csv") => Ok(ExportFormat::Csv),
+            Some("xlsx") => Ok(ExportFormat::Xlsx),
+            Some(_) => Err("不支持的导出格式，可选 csv、xlsx".to_string()),
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+// 导出表头，与 VendorOrderExportRow 字段顺序一致
+pub const VENDOR_ORDER_EXPORT_HEADERS: [&str; 15] = [
+    "订单号", "下单时间", "订单状态", "发货状态", "商品ID", "商品名称", "数量", "单价",
+    "优惠", "税额", "金额", "收货人", "联系电话", "收货地址", "邮编",
+];
+
+// 商家订单导出的一行：订单中属于该商家的一个订单项
+#[derive(Debug, Clone)]
+pub struct VendorOrderExportRow {
+    pub order_number: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub order_status: String,
+    pub fulfillment_status: String,
+    pub product_id: String,
+    pub product_name: String,
+    pub quantity: i32,
+    pub price: f64,
+    pub discount: f64,
+    pub tax_amount: f64,
+    pub total: f64,
+    pub recipient_name: String,
+    pub phone: String,
+    pub address: String,
+    pub postal_code: String,
+}
+
+impl VendorOrderExportRow {
+    pub fn created_at_text(&self) -> String {
+        self.created_at.format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+
+    pub fn csv_record(&self) -> [String; 15] {
+        [
+            self.order_number.clone(),
+            self.created_at_text(),
+            self.order_status.clone(),
+            self.fulfillment_status.clone(),
+            self.product_id.clone(),
+            self.product_name.clone(),
+            self.quantity.to_string(),
+            format!("{:.2}", self.price),
+            format!("{:.2}", self.discount),
+            format!("{:.2}", self.tax_amount),
+            format!("{:.2}", self.total),
+            self.recipient_name.clone(),
+            self.phone.clone(),
+            self.address.clone(),
+            self.postal_code.clone(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_format_parse() {
+        assert_eq!(ExportFormat::parse(None), Ok(ExportFormat::Csv));
+        assert_eq!(ExportFormat::parse(Some(" XLSX ")), Ok(ExportFormat::Xlsx));
+        assert!(ExportFormat::parse(Some("pdf")).is_err());
+    }
+}
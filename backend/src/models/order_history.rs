@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+use uuid::Uuid;
+use crate::schema::order_status_history;
+use crate::models::order::Order;
+
+// 订单状态变更记录，changed_by 为空表示系统自动变更（例如超时未支付自动取消）
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Associations, Clone)]
+#[diesel(belongs_to(Order))]
+#[diesel(table_name = order_status_history)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct OrderStatusHistory {
+    pub id: String,
+    pub order_id: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub reason: Option<String>,
+    pub changed_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = order_status_history)]
+pub struct NewOrderStatusHistory {
+    pub id: String,
+    pub order_id: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub reason: Option<String>,
+    pub changed_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl NewOrderStatusHistory {
+    pub fn new(
+        order_id: String,
+        from_status: Option<String>,
+        to_status: String,
+        reason: Option<String>,
+        changed_by: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            order_id,
+            from_status,
+            to_status,
+            reason,
+            changed_by,
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, VarChar};
+use uuid::Uuid;
+use crate::schema::{order_messages, order_message_attachments, order_message_reads};
+use crate::models::order::Order;
+
+// 单条留言的最大长度（字符）和最多附件数
+pub const MAX_MESSAGE_LENGTH: usize = 2000;
+pub const MAX_ATTACHMENTS: usize = 5;
+const MAX_ATTACHMENT_URL_LENGTH: usize = 1024;
+
+// 订单留言，sender_role 保存发言时的用户角色
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Associations, Clone)]
+#[diesel(belongs_to(Order))]
+#[diesel(table_name = order_messages)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct OrderMessage {
+    pub id: String,
+    pub order_id: String,
+    pub sender_id: String,
+    pub sender_role: String,
+    pub body: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = order_messages)]
+pub struct NewOrderMessage {
+    pub id: String,
+    pub order_id: String,
+    pub sender_id: String,
+    pub sender_role: String,
+    pub body: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl NewOrderMessage {
+    pub fn new(order_id: String, sender_id: String, sender_role: String, body: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            order_id,
+            sender_id,
+            sender_role,
+            body,
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+// 留言附件，文件由客户端上传到其他存储，这里只保存地址
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Associations, Clone)]
+#[diesel(belongs_to(OrderMessage, foreign_key = message_id))]
+#[diesel(table_name = order_message_attachments)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct OrderMessageAttachment {
+    pub id: String,
+    pub message_id: String,
+    pub url: String,
+    pub file_name: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = order_message_attachments)]
+pub struct NewOrderMessageAttachment {
+    pub id: String,
+    pub message_id: String,
+    pub url: String,
+    pub file_name: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl NewOrderMessageAttachment {
+    pub fn new(message_id: String, attachment: &MessageAttachmentDto) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            message_id,
+            url: attachment.url.trim().to_string(),
+            file_name: attachment.file_name.clone(),
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+// 用户在订单留言中的已读位置，早于等于 last_read_at 的留言视为已读
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Clone)]
+#[diesel(table_name = order_message_reads)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct OrderMessageRead {
+    pub id: String,
+    pub order_id: String,
+    pub user_id: String,
+    pub last_read_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = order_message_reads)]
+pub struct NewOrderMessageRead {
+    pub id: String,
+    pub order_id: String,
+    pub user_id: String,
+    pub last_read_at: chrono::NaiveDateTime,
+}
+
+impl NewOrderMessageRead {
+    pub fn new(order_id: String, user_id: String, last_read_at: chrono::NaiveDateTime) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            order_id,
+            user_id,
+            last_read_at,
+        }
+    }
+}
+
+// 留言附件请求
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MessageAttachmentDto {
+    pub url: String,
+    pub file_name: Option<String>,
+}
+
+// 发送留言请求，正文和附件至少提供一项
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SendOrderMessageDto {
+    pub body: Option<String>,
+    pub attachments: Option<Vec<MessageAttachmentDto>>,
+}
+
+impl SendOrderMessageDto {
+    // 校验并返回去掉首尾空白的正文
+    pub fn validate(&self) -> Result<String, String> {
+        let body = self.body.as_deref().unwrap_or("").trim().to_string();
+        let attachments = self.attachments.as_deref().unwrap_or(&[]);
+
+        if body.is_empty() && attachments.is_empty() {
+            return Err("留言内容不能为空".to_string());
+        }
+        if body.chars().count() > MAX_MESSAGE_LENGTH {
+            return Err(format!("留言内容不能超过{}个字符", MAX_MESSAGE_LENGTH));
+        }
+        if attachments.len() > MAX_ATTACHMENTS {
+            return Err(format!("每条留言最多{}个附件", MAX_ATTACHMENTS));
+        }
+
+        for attachment in attachments {
+            let url = attachment.url.trim();
+            if !(url.starts_with("https://") || url.starts_with("http://")) {
+                return Err(format!("无效的附件地址: {}", url));
+            }
+            if url.len() > MAX_ATTACHMENT_URL_LENGTH {
+                return Err("附件地址过长".to_string());
+            }
+        }
+
+        Ok(body)
+    }
+}
+
+// 留言响应，read_by 为已读过这条留言的其他参与者
+#[derive(Debug, Serialize)]
+pub struct OrderMessageResponse {
+    pub id: String,
+    pub order_id: String,
+    pub sender_id: String,
+    pub sender_role: String,
+    pub body: String,
+    pub attachments: Vec<OrderMessageAttachment>,
+    pub read_by: Vec<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl OrderMessageResponse {
+    pub fn new(message: OrderMessage, attachments: Vec<OrderMessageAttachment>, reads: &[OrderMessageRead]) -> Self {
+        Self {
+            read_by: readers_of(&message, reads),
+            id: message.id,
+            order_id: message.order_id,
+            sender_id: message.sender_id,
+            sender_role: message.sender_role,
+            body: message.body,
+            attachments,
+            created_at: message.created_at,
+        }
+    }
+}
+
+// 已读位置不早于留言时间的其他用户即已读该留言
+pub fn readers_of(message: &OrderMessage, reads: &[OrderMessageRead]) -> Vec<String> {
+    reads
+        .iter()
+        .filter(|read| read.user_id != message.sender_id && read.last_read_at >= message.created_at)
+        .map(|read| read.user_id.clone())
+        .collect()
+}
+
+// 每个订单的未读留言数
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct UnreadMessageCount {
+    #[diesel(sql_type = VarChar)]
+    pub order_id: String,
+    #[diesel(sql_type = BigInt)]
+    pub unread: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(sender_id: &str, created_at: chrono::NaiveDateTime) -> OrderMessage {
+        OrderMessage {
+            id: "m1".to_string(),
+            order_id: "o1".to_string(),
+            sender_id: sender_id.to_string(),
+            sender_role: "customer".to_string(),
+            body: "hello".to_string(),
+            created_at,
+        }
+    }
+
+    fn read(user_id: &str, last_read_at: chrono::NaiveDateTime) -> OrderMessageRead {
+        OrderMessageRead {
+            id: format!("r-{}", user_id),
+            order_id: "o1".to_string(),
+            user_id: user_id.to_string(),
+            last_read_at,
+        }
+    }
+
+    #[test]
+    fn test_readers_exclude_sender_and_earlier_reads() {
+        let sent_at = chrono::NaiveDate::from_ymd_opt(2026, 10, 18).unwrap().and_hms_opt(12, 0, 0).unwrap();
+        let reads = vec![
+            read("customer", sent_at + chrono::Duration::minutes(5)),
+            read("vendor", sent_at),
+            read("admin", sent_at - chrono::Duration::seconds(1)),
+        ];
+
+        assert_eq!(readers_of(&message("customer", sent_at), &reads), vec!["vendor".to_string()]);
+    }
+
+    #[test]
+    fn test_send_message_validation() {
+        let dto = SendOrderMessageDto { body: Some("  请问什么时候发货？ ".to_string()), attachments: None };
+        assert_eq!(dto.validate().unwrap(), "请问什么时候发货？");
+
+        let dto = SendOrderMessageDto { body: Some("   ".to_string()), attachments: None };
+        assert!(dto.validate().is_err());
+
+        let attachment = MessageAttachmentDto { url: "https://cdn.example.com/a.jpg".to_string(), file_name: None };
+        let dto = SendOrderMessageDto { body: None, attachments: Some(vec![attachment]) };
+        assert_eq!(dto.validate().unwrap(), "");
+
+        let attachment = MessageAttachmentDto { url: "javascript:alert(1)".to_string(), file_name: None };
+        let dto = SendOrderMessageDto { body: Some("看图".to_string()), attachments: Some(vec![attachment]) };
+        assert!(dto.validate().is_err());
+
+        let attachment = MessageAttachmentDto { url: "https://cdn.example.com/a.jpg".to_string(), file_name: None };
+        let dto = SendOrderMessageDto { body: None, attachments: Some(vec![attachment; MAX_ATTACHMENTS + 1]) };
+        assert!(dto.validate().is_err());
+    }
+}
@@ -0,0 +1,294 @@
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+use uuid::Uuid;
+use std::fmt;
+use std::str::FromStr;
+use crate::schema::{payments, payment_callbacks};
+use crate::models::order::Order;
+use crate::services::payment_gateway::{GatewayPaymentStatus, PaymentNotification};
+
+// 支付使用的币种
+pub const DEFAULT_CURRENCY: &str = "CNY";
+
+// 支付状态
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PaymentStatus {
+    Pending,
+    Succeeded,
+    Failed,
+    // 已全额退款，部分退款时仍为 Succeeded
+    Refunded,
+    // 渠道通知的支付金额与订单金额不符，订单保持待支付，需管理员核对后退款
+    Mismatched,
+}
+
+impl fmt::Display for PaymentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PaymentStatus::Pending => write!(f, "pending"),
+            PaymentStatus::Succeeded => write!(f, "succeeded"),
+            PaymentStatus::Failed => write!(f, "failed"),
+            PaymentStatus::Refunded => write!(f, "refunded"),
+            PaymentStatus::Mismatched => write!(f, "mismatched"),
+        }
+    }
+}
+
+impl FromStr for PaymentStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(PaymentStatus::Pending),
+            "succeeded" => Ok(PaymentStatus::Succeeded),
+            "failed" => Ok(PaymentStatus::Failed),
+            "refunded" => Ok(PaymentStatus::Refunded),
+            "mismatched" => Ok(PaymentStatus::Mismatched),
+            _ => Err(()),
+        }
+    }
+}
+
+impl PaymentStatus {
+    // 根据渠道通知决定新的支付状态，返回 None 表示通知不改变状态：
+    // 状态只前进不后退，支付失败后仍可能收到迟到的成功通知，已支付、已退款或金额不符的支付不再变化
+    pub fn apply(self, incoming: GatewayPaymentStatus) -> Option<PaymentStatus> {
+        match (self, incoming) {
+            (PaymentStatus::Pending, GatewayPaymentStatus::Succeeded) => Some(PaymentStatus::Succeeded),
+            (PaymentStatus::Pending, GatewayPaymentStatus::Failed) => Some(PaymentStatus::Failed),
+            (PaymentStatus::Failed, GatewayPaymentStatus::Succeeded) => Some(PaymentStatus::Succeeded),
+            _ => None,
+        }
+    }
+
+    // 渠道确认收款后按实收金额对账：实收金额与支付单金额、订单应付金额都一致才算支付成功，否则为金额不符
+    pub fn settled(paid_amount: f64, payment_amount: f64, order_due: f64) -> PaymentStatus {
+        if amounts_match(paid_amount, payment_amount) && amounts_match(payment_amount, order_due) {
+            PaymentStatus::Succeeded
+        } else {
+            PaymentStatus::Mismatched
+        }
+    }
+}
+
+// 金额按分比较
+pub fn amounts_match(a: f64, b: f64) -> bool {
+    ((a * 100.0).round() - (b * 100.0).round()).abs() < 0.5
+}
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Associations, Clone)]
+#[diesel(belongs_to(Order))]
+#[diesel(table_name = payments)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct Payment {
+    pub id: String,
+    pub order_id: String,
+    pub user_id: String,
+    pub gateway: String,
+    pub gateway_payment_id: String,
+    pub status: String,
+    pub amount: f64,
+    // 渠道通知的实际支付金额
+    pub paid_amount: Option<f64>,
+    pub currency: String,
+    // 用户完成支付的地址，由支付渠道返回
+    pub payment_url: Option<String>,
+    pub refunded_amount: f64,
+    pub failure_reason: Option<String>,
+    pub paid_at: Option<chrono::NaiveDateTime>,
+    pub refunded_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl Payment {
+    pub fn get_status(&self) -> PaymentStatus {
+        PaymentStatus::from_str(&self.status).unwrap_or(PaymentStatus::Failed)
+    }
+
+    // 尚可退款的金额，按渠道通知的实际支付金额计算
+    pub fn refundable_amount(&self) -> f64 {
+        match self.get_status() {
+            PaymentStatus::Succeeded | PaymentStatus::Mismatched => {
+                (self.paid_amount.unwrap_or(self.amount) - self.refunded_amount).max(0.0)
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = payments)]
+pub struct NewPayment {
+    pub id: String,
+    pub order_id: String,
+    pub user_id: String,
+    pub gateway: String,
+    pub gateway_payment_id: String,
+    pub status: String,
+    pub amount: f64,
+    pub currency: String,
+    pub payment_url: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl NewPayment {
+    // 渠道侧支付单号在发起支付后填写
+    pub fn new(order_id: String, user_id: String, gateway: &str, amount: f64) -> Self {
+        let now = chrono::Utc::now().naive_utc();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            order_id,
+            user_id,
+            gateway: gateway.to_lowercase(),
+            gateway_payment_id: String::new(),
+            status: PaymentStatus::Pending.to_string(),
+            amount,
+            currency: DEFAULT_CURRENCY.to_string(),
+            payment_url: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+// 回调处理结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CallbackOutcome {
+    // 支付成功，订单进入处理中
+    Paid,
+    // 支付失败，订单保持待支付，可以重新发起支付
+    Failed,
+    // 支付成功但订单已取消或已由其他支付完成，需要退款
+    RefundRequired,
+    // 支付金额与订单金额不符，订单不变
+    Mismatched,
+    // 通知不改变支付状态，例如重复、乱序或处理中的通知
+    Unchanged,
+}
+
+impl fmt::Display for CallbackOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CallbackOutcome::Paid => write!(f, "paid"),
+            CallbackOutcome::Failed => write!(f, "failed"),
+            CallbackOutcome::RefundRequired => write!(f, "refund_required"),
+            CallbackOutcome::Mismatched => write!(f, "mismatched"),
+            CallbackOutcome::Unchanged => write!(f, "unchanged"),
+        }
+    }
+}
+
+// 已处理的渠道通知，用于识别重放
+#[derive(Insertable)]
+#[diesel(table_name = payment_callbacks)]
+pub struct NewPaymentCallback {
+    pub id: String,
+    pub payment_id: String,
+    pub gateway: String,
+    pub notification_id: String,
+    pub status: String,
+    pub amount: f64,
+    pub sent_at: chrono::NaiveDateTime,
+    pub outcome: String,
+    pub received_at: chrono::NaiveDateTime,
+}
+
+impl NewPaymentCallback {
+    pub fn new(payment_id: String, gateway: &str, notification: &PaymentNotification, outcome: CallbackOutcome) -> Self {
+        let status = match notification.status {
+            GatewayPaymentStatus::Pending => PaymentStatus::Pending,
+            GatewayPaymentStatus::Succeeded => PaymentStatus::Succeeded,
+            GatewayPaymentStatus::Failed => PaymentStatus::Failed,
+        };
+        Self {
+            id: Uuid::new_v4().to_string(),
+            payment_id,
+            gateway: gateway.to_string(),
+            notification_id: notification.notification_id.clone(),
+            status: status.to_string(),
+            amount: notification.amount,
+            sent_at: notification.sent_at,
+            outcome: outcome.to_string(),
+            received_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+// 发起支付请求
+#[derive(Debug, Deserialize)]
+pub struct CreatePaymentDto {
+    pub gateway: String,
+}
+
+// 退款请求，金额为空时退还全部可退金额
+#[derive(Debug, Deserialize)]
+pub struct RefundPaymentDto {
+    pub amount: Option<f64>,
+    pub reason: Option<String>,
+    // 为 true 时退入用户钱包，否则原路退回
+    pub to_wallet: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refundable_amount() {
+        let now = chrono::Utc::now().naive_utc();
+        let mut payment = Payment {
+            id: "p-1".to_string(),
+            order_id: "o-1".to_string(),
+            user_id: "u-1".to_string(),
+            gateway: "mock".to_string(),
+            gateway_payment_id: "mock_p-1".to_string(),
+            status: PaymentStatus::Pending.to_string(),
+            amount: 100.0,
+            paid_amount: None,
+            currency: DEFAULT_CURRENCY.to_string(),
+            payment_url: None,
+            refunded_amount: 0.0,
+            failure_reason: None,
+            paid_at: None,
+            refunded_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        assert_eq!(payment.refundable_amount(), 0.0);
+
+        payment.status = PaymentStatus::Succeeded.to_string();
+        payment.refunded_amount = 30.0;
+        assert_eq!(payment.refundable_amount(), 70.0);
+
+        payment.status = PaymentStatus::Refunded.to_string();
+        assert_eq!(payment.refundable_amount(), 0.0);
+
+        // 金额不符时按实际支付金额退款
+        payment.status = PaymentStatus::Mismatched.to_string();
+        payment.paid_amount = Some(50.0);
+        payment.refunded_amount = 0.0;
+        assert_eq!(payment.refundable_amount(), 50.0);
+    }
+
+    #[test]
+    fn test_status_only_moves_forward() {
+        assert_eq!(PaymentStatus::Pending.apply(GatewayPaymentStatus::Succeeded), Some(PaymentStatus::Succeeded));
+        assert_eq!(PaymentStatus::Pending.apply(GatewayPaymentStatus::Failed), Some(PaymentStatus::Failed));
+        assert_eq!(PaymentStatus::Pending.apply(GatewayPaymentStatus::Pending), None);
+        // 迟到的成功通知覆盖失败，迟到的失败通知不覆盖成功
+        assert_eq!(PaymentStatus::Failed.apply(GatewayPaymentStatus::Succeeded), Some(PaymentStatus::Succeeded));
+        assert_eq!(PaymentStatus::Succeeded.apply(GatewayPaymentStatus::Failed), None);
+        assert_eq!(PaymentStatus::Succeeded.apply(GatewayPaymentStatus::Succeeded), None);
+        assert_eq!(PaymentStatus::Refunded.apply(GatewayPaymentStatus::Succeeded), None);
+        assert_eq!(PaymentStatus::Mismatched.apply(GatewayPaymentStatus::Succeeded), None);
+    }
+
+    #[test]
+    fn test_amounts_match() {
+        assert!(amounts_match(99.5, 99.50));
+        assert!(amounts_match(0.1 + 0.2, 0.3));
+        assert!(!amounts_match(99.5, 99.51));
+    }
+}
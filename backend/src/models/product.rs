@@ -2,8 +2,44 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use diesel::prelude::*;
 use chrono::Utc;
+use std::fmt;
+use std::str::FromStr;
 use crate::schema::products;
 
+// 商品库存不足时的下单方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BackorderMode {
+    // 不允许超出库存下单
+    None,
+    // 缺货预订：超出库存的部分等待补货后发货
+    Backorder,
+    // 新品预售：商品到货前即可下单，需要设置预计发货日期
+    Preorder,
+}
+
+impl fmt::Display for BackorderMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BackorderMode::None => write!(f, "none"),
+            BackorderMode::Backorder => write!(f, "backorder"),
+            BackorderMode::Preorder => write!(f, "preorder"),
+        }
+    }
+}
+
+impl FromStr for BackorderMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "none" => Ok(BackorderMode::None),
+            "backorder" => Ok(BackorderMode::Backorder),
+            "preorder" => Ok(BackorderMode::Preorder),
+            _ => Err(()),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Queryable, Identifiable, Selectable)]
 #[diesel(table_name = products)]
 #[diesel(check_for_backend(diesel::mysql::Mysql))]
@@ -17,6 +53,10 @@ pub struct Product {
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
     pub category: Option<String>,
+    pub backorder_mode: String,
+    // 允许预订的最大未到货数量，None 表示不限
+    pub backorder_limit: Option<i32>,
+    pub expected_ship_date: Option<chrono::NaiveDate>,
 }
 
 #[derive(Insertable)]
@@ -31,6 +71,10 @@ pub struct NewProduct {
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
     pub category: Option<String>,
+    pub backorder_mode: String,
+    // 允许预订的最大未到货数量，None 表示不限
+    pub backorder_limit: Option<i32>,
+    pub expected_ship_date: Option<chrono::NaiveDate>,
 }
 
 impl Product {
@@ -46,8 +90,15 @@ impl Product {
             created_at: now.naive_utc(),
             updated_at: now.naive_utc(),
             category,
+            backorder_mode: BackorderMode::None.to_string(),
+            backorder_limit: None,
+            expected_ship_date: None,
         }
     }
+
+    pub fn get_backorder_mode(&self) -> BackorderMode {
+        BackorderMode::from_str(&self.backorder_mode).unwrap_or(BackorderMode::None)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -62,6 +113,9 @@ pub struct ProductResponse {
     pub updated_at: chrono::DateTime<chrono::Utc>,
     pub category: Option<String>,
     pub in_stock: bool,
+    pub backorder_mode: BackorderMode,
+    pub backorder_limit: Option<i32>,
+    pub expected_ship_date: Option<chrono::NaiveDate>,
 }
 
 impl From<Product> for ProductResponse {
@@ -75,8 +129,11 @@ impl From<Product> for ProductResponse {
             stock: product.stock,
             created_at: chrono::DateTime::from_naive_utc_and_offset(product.created_at, chrono::Utc),
             updated_at: chrono::DateTime::from_naive_utc_and_offset(product.updated_at, chrono::Utc),
-            category: product.category,
             in_stock: product.stock > 0,
+            backorder_mode: BackorderMode::from_str(&product.backorder_mode).unwrap_or(BackorderMode::None),
+            backorder_limit: product.backorder_limit,
+            expected_ship_date: product.expected_ship_date,
+            category: product.category,
         }
     }
 }
@@ -88,6 +145,79 @@ pub struct CreateProductDto {
     pub price: f64,
     pub stock: i32,
     pub category: Option<String>,
+    // 缺货预订设置，未提供时不允许超出库存下单
+    pub backorder_mode: Option<String>,
+    pub backorder_limit: Option<i32>,
+    pub expected_ship_date: Option<chrono::NaiveDate>,
+}
+
+impl CreateProductDto {
+    pub fn backorder_settings(&self) -> BackorderSettingsDto {
+        BackorderSettingsDto {
+            backorder_mode: self.backorder_mode.clone().unwrap_or_else(|| BackorderMode::None.to_string()),
+            backorder_limit: self.backorder_limit,
+            expected_ship_date: self.expected_ship_date,
+        }
+    }
+}
+
+// 修改商品缺货预订设置请求，三项设置整体替换
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackorderSettingsDto {
+    pub backorder_mode: String,
+    pub backorder_limit: Option<i32>,
+    pub expected_ship_date: Option<chrono::NaiveDate>,
+}
+
+impl BackorderSettingsDto {
+    pub fn validate(&self) -> Result<BackorderMode, String> {
+        let mode = BackorderMode::from_str(&self.backorder_mode)
+            .map_err(|_| "无效的预订方式，可选 none、backorder、preorder".to_string())?;
+        if let Some(limit) = self.backorder_limit {
+            if limit < 0 {
+                return Err("预订数量上限不能为负数".to_string());
+            }
+        }
+        if mode == BackorderMode::Preorder && self.expected_ship_date.is_none() {
+            return Err("预售商品必须设置预计发货日期".to_string());
+        }
+        Ok(mode)
+    }
+}
+
+// 等待到货的订单项
+#[derive(Debug, Serialize)]
+pub struct BackorderLineResponse {
+    pub order_item_id: String,
+    pub order_id: String,
+    pub order_number: String,
+    pub product_id: String,
+    pub product_name: String,
+    pub backorder_mode: BackorderMode,
+    pub quantity: i32,
+    pub backordered_quantity: i32,
+    pub expected_ship_date: Option<chrono::NaiveDate>,
+    pub ordered_at: chrono::NaiveDateTime,
+}
+
+type BackorderLineRow = (String, String, String, String, String, String, i32, i32, Option<chrono::NaiveDate>, chrono::NaiveDateTime);
+
+impl From<BackorderLineRow> for BackorderLineResponse {
+    fn from(row: BackorderLineRow) -> Self {
+        let (order_item_id, order_id, order_number, product_id, product_name, backorder_mode, quantity, backordered_quantity, expected_ship_date, ordered_at) = row;
+        Self {
+            order_item_id,
+            order_id,
+            order_number,
+            product_id,
+            product_name,
+            backorder_mode: BackorderMode::from_str(&backorder_mode).unwrap_or(BackorderMode::None),
+            quantity,
+            backordered_quantity,
+            expected_ship_date,
+            ordered_at,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -112,6 +242,9 @@ impl From<CreateProductDto> for NewProduct {
             created_at: now.naive_utc(),
             updated_at: now.naive_utc(),
             category: dto.category,
+            backorder_mode: dto.backorder_mode.unwrap_or_else(|| BackorderMode::None.to_string()),
+            backorder_limit: dto.backorder_limit,
+            expected_ship_date: dto.expected_ship_date,
         }
     }
 }
@@ -127,8 +260,11 @@ impl From<NewProduct> for ProductResponse {
             stock: product.stock,
             created_at: chrono::DateTime::from_naive_utc_and_offset(product.created_at, chrono::Utc),
             updated_at: chrono::DateTime::from_naive_utc_and_offset(product.updated_at, chrono::Utc),
-            category: product.category,
             in_stock: product.stock > 0,
+            backorder_mode: BackorderMode::from_str(&product.backorder_mode).unwrap_or(BackorderMode::None),
+            backorder_limit: product.backorder_limit,
+            expected_ship_date: product.expected_ship_date,
+            category: product.category,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+use uuid::Uuid;
+use std::fmt;
+use std::str::FromStr;
+use crate::schema::{promotions, order_item_promotions};
+use crate::models::coupon::in_scope;
+
+// 自动促销类型
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromotionKind {
+    // 满减：适用商品满指定金额减指定金额，按满足的最高档位计算
+    Tiered,
+    // 买 X 送 Y：同一商品每买 buy_quantity 件送 free_quantity 件
+    BuyXGetY,
+    // 按百分比折扣，例如分类促销
+    PercentageOff,
+}
+
+impl fmt::Display for PromotionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PromotionKind::Tiered => write!(f, "tiered"),
+            PromotionKind::BuyXGetY => write!(f, "buy_x_get_y"),
+            PromotionKind::PercentageOff => write!(f, "percentage_off"),
+        }
+    }
+}
+
+impl FromStr for PromotionKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "tiered" => Ok(PromotionKind::Tiered),
+            "buy_x_get_y" => Ok(PromotionKind::BuyXGetY),
+            "percentage_off" => Ok(PromotionKind::PercentageOff),
+            _ => Err(()),
+        }
+    }
+}
+
+// 满减档位：满 threshold 减 discount
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PromotionTier {
+    pub threshold: f64,
+    pub discount: f64,
+}
+
+// 满减档位保存为 "300:30,500:60"，按门槛从低到高排列
+pub fn format_tiers(tiers: &[PromotionTier]) -> String {
+    let mut tiers = tiers.to_vec();
+    tiers.sort_by(|a, b| a.threshold.total_cmp(&b.threshold));
+    tiers
+        .iter()
+        .map(|tier| format!("{}:{}", tier.threshold, tier.discount))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+pub fn parse_tiers(value: &str) -> Vec<PromotionTier> {
+    value
+        .split(',')
+        .filter_map(|tier| {
+            let (threshold, discount) = tier.split_once(':')?;
+            Some(PromotionTier {
+                threshold: threshold.trim().parse().ok()?,
+                discount: discount.trim().parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Clone)]
+#[diesel(table_name = promotions)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct Promotion {
+    pub id: String,
+    pub name: String,
+    pub kind: String,
+    // 数值越大越先计算
+    pub priority: i32,
+    // 为 false 时享受该促销的商品不再参与其他满减和优惠券
+    pub stackable: bool,
+    // 满减档位，见 parse_tiers
+    pub tiers: Option<String>,
+    pub buy_quantity: Option<i32>,
+    pub free_quantity: Option<i32>,
+    pub percentage: Option<f64>,
+    // 以下适用范围为逗号分隔，为空表示不限
+    pub product_ids: Option<String>,
+    pub categories: Option<String>,
+    pub vendor_ids: Option<String>,
+    // 出资商家，设置后只适用于该商家的商品，优惠由商家承担
+    pub funded_by: Option<String>,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at: Option<chrono::NaiveDateTime>,
+    pub active: bool,
+    pub created_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl Promotion {
+    pub fn get_kind(&self) -> Option<PromotionKind> {
+        PromotionKind::from_str(&self.kind).ok()
+    }
+
+    pub fn get_tiers(&self) -> Vec<PromotionTier> {
+        self.tiers.as_deref().map(parse_tiers).unwrap_or_default()
+    }
+
+    // 是否启用且在有效期内
+    pub fn is_running(&self, now: chrono::NaiveDateTime) -> bool {
+        self.active
+            && self.starts_at.map(|starts_at| now >= starts_at).unwrap_or(true)
+            && self.ends_at.map(|ends_at| now < ends_at).unwrap_or(true)
+    }
+
+    // 商品是否在促销的适用范围内，商家出资的促销只适用于该商家的商品
+    pub fn applies_to(&self, product_id: &str, category: Option<&str>, vendor_id: &str) -> bool {
+        self.funded_by.as_deref().map(|funded_by| funded_by == vendor_id).unwrap_or(true)
+            && in_scope(&self.product_ids, &self.categories, &self.vendor_ids, product_id, category, vendor_id)
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = promotions)]
+pub struct NewPromotion {
+    pub id: String,
+    pub name: String,
+    pub kind: String,
+    pub priority: i32,
+    pub stackable: bool,
+    pub tiers: Option<String>,
+    pub buy_quantity: Option<i32>,
+    pub free_quantity: Option<i32>,
+    pub percentage: Option<f64>,
+    pub product_ids: Option<String>,
+    pub categories: Option<String>,
+    pub vendor_ids: Option<String>,
+    pub funded_by: Option<String>,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at: Option<chrono::NaiveDateTime>,
+    pub active: bool,
+    pub created_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+// 订单项享受的促销快照，促销修改后不影响已下单的记录
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Clone)]
+#[diesel(table_name = order_item_promotions)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct OrderItemPromotion {
+    pub id: String,
+    pub order_id: String,
+    pub order_item_id: String,
+    pub promotion_id: String,
+    pub promotion_name: String,
+    pub kind: String,
+    pub discount: f64,
+    pub funded_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = order_item_promotions)]
+pub struct NewOrderItemPromotion {
+    pub id: String,
+    pub order_id: String,
+    pub order_item_id: String,
+    pub promotion_id: String,
+    pub promotion_name: String,
+    pub kind: String,
+    pub discount: f64,
+    pub funded_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl NewOrderItemPromotion {
+    pub fn new(order_id: String, order_item_id: String, applied: &AppliedPromotion) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            order_id,
+            order_item_id,
+            promotion_id: applied.promotion_id.clone(),
+            promotion_name: applied.name.clone(),
+            kind: applied.kind.to_string(),
+            discount: applied.discount,
+            funded_by: applied.funded_by.clone(),
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+// 订单项享受的一个促销及其优惠金额
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppliedPromotion {
+    pub promotion_id: String,
+    pub name: String,
+    pub kind: PromotionKind,
+    pub discount: f64,
+    pub funded_by: Option<String>,
+}
+
+impl From<OrderItemPromotion> for AppliedPromotion {
+    fn from(promotion: OrderItemPromotion) -> Self {
+        Self {
+            kind: PromotionKind::from_str(&promotion.kind).unwrap_or(PromotionKind::PercentageOff),
+            promotion_id: promotion.promotion_id,
+            name: promotion.promotion_name,
+            discount: promotion.discount,
+            funded_by: promotion.funded_by,
+        }
+    }
+}
+
+// 创建促销请求，按类型填写 tiers、buy_quantity/free_quantity 或 percentage
+#[derive(Debug, Deserialize)]
+pub struct CreatePromotionDto {
+    pub name: String,
+    pub kind: PromotionKind,
+    pub priority: Option<i32>,
+    pub stackable: Option<bool>,
+    pub tiers: Option<Vec<PromotionTier>>,
+    pub buy_quantity: Option<i32>,
+    pub free_quantity: Option<i32>,
+    pub percentage: Option<f64>,
+    pub product_ids: Option<Vec<String>>,
+    pub categories: Option<Vec<String>>,
+    pub vendor_ids: Option<Vec<String>>,
+    pub funded_by: Option<String>,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at: Option<chrono::NaiveDateTime>,
+}
+
+// 修改促销请求，只修改提交的字段；类型、优惠内容和适用范围创建后不能修改
+#[derive(Debug, Deserialize, AsChangeset)]
+#[diesel(table_name = promotions)]
+pub struct UpdatePromotionDto {
+    pub name: Option<String>,
+    pub priority: Option<i32>,
+    pub stackable: Option<bool>,
+    pub active: Option<bool>,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at: Option<chrono::NaiveDateTime>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiers_round_trip() {
+        let tiers = vec![
+            PromotionTier { threshold: 500.0, discount: 60.0 },
+            PromotionTier { threshold: 300.0, discount: 30.0 },
+        ];
+        let saved = format_tiers(&tiers);
+        assert_eq!(saved, "300:30,500:60");
+        assert_eq!(parse_tiers(&saved), vec![tiers[1], tiers[0]]);
+        assert_eq!(parse_tiers("bad, 100:10.5"), vec![PromotionTier { threshold: 100.0, discount: 10.5 }]);
+    }
+}
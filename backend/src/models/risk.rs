@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+use uuid::Uuid;
+use std::fmt;
+use std::str::FromStr;
+use crate::schema::{risk_rules, order_risk_assessments};
+use crate::models::order::Order;
+
+// 风控规则编码，阈值含义见迁移脚本
+pub const RULE_NEW_ACCOUNT: &str = "new_account";
+pub const RULE_FIRST_ORDER_VALUE: &str = "first_order_value";
+pub const RULE_ORDER_VALUE_VS_HISTORY: &str = "order_value_vs_history";
+pub const RULE_USER_VELOCITY: &str = "user_velocity";
+pub const RULE_IP_VELOCITY: &str = "ip_velocity";
+pub const RULE_ADDRESS_MISMATCH: &str = "address_mismatch";
+
+// 风控规则配置
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Clone)]
+#[diesel(table_name = risk_rules)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct RiskRule {
+    pub id: String,
+    pub rule: String,
+    pub enabled: bool,
+    pub threshold: f64,
+    pub score: i32,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+// 修改风控规则请求，未提供的字段保持不变
+#[derive(Debug, Deserialize, AsChangeset)]
+#[diesel(table_name = risk_rules)]
+pub struct UpdateRiskRuleDto {
+    pub enabled: Option<bool>,
+    pub threshold: Option<f64>,
+    pub score: Option<i32>,
+}
+
+impl UpdateRiskRuleDto {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.enabled.is_none() && self.threshold.is_none() && self.score.is_none() {
+            return Err("请至少提供 enabled、threshold、score 中的一项".to_string());
+        }
+        if let Some(threshold) = self.threshold {
+            if !threshold.is_finite() || threshold < 0.0 {
+                return Err("阈值不能为负数".to_string());
+            }
+        }
+        if let Some(score) = self.score {
+            if !(0..=100).contains(&score) {
+                return Err("风险分必须在0到100之间".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+// 风控结论
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RiskDecision {
+    // 自动通过
+    Accept,
+    // 暂扣订单，等待管理员审核
+    Review,
+    // 直接拒绝下单
+    Reject,
+}
+
+impl fmt::Display for RiskDecision {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RiskDecision::Accept => write!(f, "Accept"),
+            RiskDecision::Review => write!(f, "Review"),
+            RiskDecision::Reject => write!(f, "Reject"),
+        }
+    }
+}
+
+// 待审核订单的审核状态
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RiskReviewStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl fmt::Display for RiskReviewStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RiskReviewStatus::Pending => write!(f, "Pending"),
+            RiskReviewStatus::Approved => write!(f, "Approved"),
+            RiskReviewStatus::Rejected => write!(f, "Rejected"),
+        }
+    }
+}
+
+impl FromStr for RiskReviewStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(RiskReviewStatus::Pending),
+            "approved" => Ok(RiskReviewStatus::Approved),
+            "rejected" => Ok(RiskReviewStatus::Rejected),
+            _ => Err(()),
+        }
+    }
+}
+
+// 命中的规则及说明
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskReason {
+    pub rule: String,
+    pub score: i32,
+    pub detail: String,
+}
+
+// 一次结账的风控评估记录
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Associations, Clone)]
+#[diesel(belongs_to(Order))]
+#[diesel(table_name = order_risk_assessments)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct OrderRiskAssessment {
+    pub id: String,
+    pub order_id: Option<String>,
+    pub user_id: String,
+    pub client_ip: Option<String>,
+    pub order_total: f64,
+    pub score: i32,
+    pub decision: String,
+    // 命中规则列表（RiskReason 的 JSON 数组）
+    pub reasons: String,
+    pub review_status: Option<String>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<chrono::NaiveDateTime>,
+    pub review_note: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = order_risk_assessments)]
+pub struct NewOrderRiskAssessment {
+    pub id: String,
+    pub order_id: Option<String>,
+    pub user_id: String,
+    pub client_ip: Option<String>,
+    pub order_total: f64,
+    pub score: i32,
+    pub decision: String,
+    pub reasons: String,
+    pub review_status: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl NewOrderRiskAssessment {
+    pub fn new(
+        order_id: Option<String>,
+        user_id: String,
+        client_ip: Option<String>,
+        order_total: f64,
+        score: i32,
+        decision: RiskDecision,
+        reasons: &[RiskReason],
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            order_id,
+            user_id,
+            client_ip,
+            order_total,
+            score,
+            decision: decision.to_string(),
+            reasons: serde_json::to_string(reasons).unwrap_or_else(|_| "[]".to_string()),
+            review_status: match decision {
+                RiskDecision::Review => Some(RiskReviewStatus::Pending.to_string()),
+                _ => None,
+            },
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+// 风控审核队列查询参数
+#[derive(Debug, Deserialize)]
+pub struct RiskReviewQuery {
+    pub status: Option<String>,  // pending（默认）、approved、rejected
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+// 审核待审订单请求
+#[derive(Debug, Deserialize)]
+pub struct RiskReviewDto {
+    pub note: Option<String>,
+}
+
+// 审核队列中的一条记录
+#[derive(Debug, Serialize)]
+pub struct RiskReviewResponse {
+    pub id: String,
+    pub order_id: Option<String>,
+    pub order_number: String,
+    pub order_status: String,
+    pub user_id: String,
+    pub user_email: String,
+    pub client_ip: Option<String>,
+    pub order_total: f64,
+    pub score: i32,
+    pub decision: String,
+    pub reasons: Vec<RiskReason>,
+    pub review_status: Option<String>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<chrono::NaiveDateTime>,
+    pub review_note: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl RiskReviewResponse {
+    pub fn new(assessment: OrderRiskAssessment, order_number: String, order_status: String, user_email: String) -> Self {
+        Self {
+            reasons: serde_json::from_str(&assessment.reasons).unwrap_or_default(),
+            id: assessment.id,
+            order_id: assessment.order_id,
+            order_number,
+            order_status,
+            user_id: assessment.user_id,
+            user_email,
+            client_ip: assessment.client_ip,
+            order_total: assessment.order_total,
+            score: assessment.score,
+            decision: assessment.decision,
+            review_status: assessment.review_status,
+            reviewed_by: assessment.reviewed_by,
+            reviewed_at: assessment.reviewed_at,
+            review_note: assessment.review_note,
+            created_at: assessment.created_at,
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+use uuid::Uuid;
+use std::collections::HashMap;
+use crate::schema::{shipments, shipment_items};
+use crate::models::order::Order;
+use crate::services::carrier::TrackingEvent;
+
+pub const SHIPMENT_STATUS_SHIPPED: &str = "shipped";
+pub const SHIPMENT_STATUS_DELIVERED: &str = "delivered";
+pub const SHIPMENT_STATUS_EXCEPTION: &str = "exception";
+
+// 商家发货记录，一个商家子订单可以分多个包裹发出
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Associations, Clone)]
+#[diesel(belongs_to(Order))]
+#[diesel(table_name = shipments)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct Shipment {
+    pub id: String,
+    pub order_id: String,
+    pub fulfillment_id: String,
+    pub vendor_id: String,
+    pub carrier: String,
+    pub tracking_number: String,
+    pub status: String,
+    pub shipped_at: chrono::NaiveDateTime,
+    pub delivered_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = shipments)]
+pub struct NewShipment {
+    pub id: String,
+    pub order_id: String,
+    pub fulfillment_id: String,
+    pub vendor_id: String,
+    pub carrier: String,
+    pub tracking_number: String,
+    pub status: String,
+    pub shipped_at: chrono::NaiveDateTime,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl NewShipment {
+    pub fn new(order_id: String, fulfillment_id: String, vendor_id: String, carrier: String, tracking_number: String) -> Self {
+        let now = chrono::Utc::now().naive_utc();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            order_id,
+            fulfillment_id,
+            vendor_id,
+            carrier,
+            tracking_number,
+            status: SHIPMENT_STATUS_SHIPPED.to_string(),
+            shipped_at: now,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Associations, Clone)]
+#[diesel(belongs_to(Shipment))]
+#[diesel(table_name = shipment_items)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct ShipmentItem {
+    pub id: String,
+    pub shipment_id: String,
+    pub order_item_id: String,
+    pub quantity: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = shipment_items)]
+pub struct NewShipmentItem {
+    pub id: String,
+    pub shipment_id: String,
+    pub order_item_id: String,
+    pub quantity: i32,
+}
+
+// 创建发货记录请求，未指定 items 时发出该商家所有未发货的商品
+#[derive(Debug, Deserialize)]
+pub struct CreateShipmentDto {
+    pub carrier: String,
+    pub tracking_number: String,
+    pub items: Option<Vec<ShipmentItemDto>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShipmentItemDto {
+    pub order_item_id: String,
+    pub quantity: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShipmentItemResponse {
+    pub order_item_id: String,
+    pub quantity: i32,
+}
+
+impl From<ShipmentItem> for ShipmentItemResponse {
+    fn from(item: ShipmentItem) -> Self {
+        Self {
+            order_item_id: item.order_item_id,
+            quantity: item.quantity,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShipmentResponse {
+    pub id: String,
+    pub fulfillment_id: String,
+    pub vendor_id: String,
+    pub carrier: String,
+    pub tracking_number: String,
+    pub status: String,
+    pub items: Vec<ShipmentItemResponse>,
+    pub shipped_at: chrono::NaiveDateTime,
+    pub delivered_at: Option<chrono::NaiveDateTime>,
+}
+
+impl ShipmentResponse {
+    pub fn new(shipment: Shipment, items: Vec<ShipmentItem>) -> Self {
+        Self {
+            id: shipment.id,
+            fulfillment_id: shipment.fulfillment_id,
+            vendor_id: shipment.vendor_id,
+            carrier: shipment.carrier,
+            tracking_number: shipment.tracking_number,
+            status: shipment.status,
+            items: items.into_iter().map(Into::into).collect(),
+            shipped_at: shipment.shipped_at,
+            delivered_at: shipment.delivered_at,
+        }
+    }
+}
+
+// 物流轨迹查询响应
+#[derive(Debug, Serialize)]
+pub struct ShipmentTrackingResponse {
+    pub shipment: ShipmentResponse,
+    pub tracking_available: bool,
+    pub events: Vec<TrackingEvent>,
+}
+
+// 计算本次发货的订单项及数量
+// ordered: 子订单中每个订单项的购买数量；shipped: 已发货数量；requested: 商家指定的发货明细
+pub fn plan_shipment_items(
+    ordered: &[(String, i32)],
+    shipped: &HashMap<String, i32>,
+    requested: Option<&[ShipmentItemDto]>,
+) -> Result<Vec<(String, i32)>, String> {
+    let remaining: Vec<(String, i32)> = ordered
+        .iter()
+        .map(|(id, quantity)| (id.clone(), quantity - shipped.get(id).copied().unwrap_or(0)))
+        .collect();
+
+    let requested = match requested {
+        Some(items) if !items.is_empty() => items,
+        _ => {
+            let plan: Vec<(String, i32)> = remaining.into_iter().filter(|(_, quantity)| *quantity > 0).collect();
+            if plan.is_empty() {
+                return Err("没有待发货的商品".to_string());
+            }
+            return Ok(plan);
+        }
+    };
+
+    // 合并同一订单项的多条明细
+    let mut plan: Vec<(String, i32)> = Vec::new();
+    for item in requested {
+        if item.quantity <= 0 {
+            return Err(format!("订单项 {} 的发货数量必须大于0", item.order_item_id));
+        }
+        match plan.iter_mut().find(|(id, _)| *id == item.order_item_id) {
+            Some((_, quantity)) => *quantity += item.quantity,
+            None => plan.push((item.order_item_id.clone(), item.quantity)),
+        }
+    }
+
+    for (id, quantity) in &plan {
+        match remaining.iter().find(|(remaining_id, _)| remaining_id == id) {
+            None => return Err(format!("订单项 {} 不属于您的子订单", id)),
+            Some((_, left)) if quantity > left => {
+                return Err(format!("订单项 {} 的发货数量超过未发货数量 {}", id, left));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ordered() -> Vec<(String, i32)> {
+        vec![("item-1".to_string(), 2), ("item-2".to_string(), 1)]
+    }
+
+    #[test]
+    fn test_plan_ships_everything_remaining_by_default() {
+        let mut shipped = HashMap::new();
+        shipped.insert("item-1".to_string(), 1);
+
+        let plan = plan_shipment_items(&ordered(), &shipped, None).unwrap();
+        assert_eq!(plan, vec![("item-1".to_string(), 1), ("item-2".to_string(), 1)]);
+
+        shipped.insert("item-1".to_string(), 2);
+        shipped.insert("item-2".to_string(), 1);
+        assert!(plan_shipment_items(&ordered(), &shipped, None).is_err());
+    }
+
+    #[test]
+    fn test_plan_validates_requested_items() {
+        let shipped = HashMap::new();
+        let request = |id: &str, quantity: i32| ShipmentItemDto { order_item_id: id.to_string(), quantity };
+
+        let plan = plan_shipment_items(&ordered(), &shipped, Some(&[request("item-1", 1), request("item-1", 1)])).unwrap();
+        assert_eq!(plan, vec![("item-1".to_string(), 2)]);
+
+        assert!(plan_shipment_items(&ordered(), &shipped, Some(&[request("item-1", 3)])).is_err());
+        assert!(plan_shipment_items(&ordered(), &shipped, Some(&[request("item-2", 0)])).is_err());
+        assert!(plan_shipment_items(&ordered(), &shipped, Some(&[request("other", 1)])).is_err());
+    }
+}
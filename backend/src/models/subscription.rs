@@ -0,0 +1,355 @@
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+use uuid::Uuid;
+use chrono::{Datelike, Months};
+use std::fmt;
+use std::str::FromStr;
+use crate::schema::{subscriptions, subscription_items, subscription_runs};
+use crate::models::order::OrderItemDto;
+
+// 定期订购的下单周期
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SubscriptionFrequency {
+    Weekly,
+    Monthly,
+}
+
+impl fmt::Display for SubscriptionFrequency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SubscriptionFrequency::Weekly => write!(f, "weekly"),
+            SubscriptionFrequency::Monthly => write!(f, "monthly"),
+        }
+    }
+}
+
+impl FromStr for SubscriptionFrequency {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "weekly" => Ok(SubscriptionFrequency::Weekly),
+            "monthly" => Ok(SubscriptionFrequency::Monthly),
+            _ => Err(()),
+        }
+    }
+}
+
+impl SubscriptionFrequency {
+    // 下一个周期的时间；按月时每月在 anchor_day 下单，目标月份没有该日时取该月最后一天，
+    // 例如 1月31日、2月28日、3月31日，月末的下单日不会逐月前移
+    pub fn advance(&self, from: chrono::NaiveDateTime, anchor_day: u32) -> chrono::NaiveDateTime {
+        match self {
+            SubscriptionFrequency::Weekly => from + chrono::Duration::weeks(1),
+            SubscriptionFrequency::Monthly => from
+                .date()
+                .with_day(1)
+                .and_then(|date| date.checked_add_months(Months::new(1)))
+                .map(|month| day_of_month(month, anchor_day).and_time(from.time()))
+                .unwrap_or(from + chrono::Duration::days(30)),
+        }
+    }
+
+    // 从计划时间起推进到晚于 now 的第一个周期，服务停机期间错过的周期不再补单
+    pub fn next_run_after(&self, scheduled: chrono::NaiveDateTime, now: chrono::NaiveDateTime, anchor_day: u32) -> chrono::NaiveDateTime {
+        let mut next = self.advance(scheduled, anchor_day);
+        while next <= now {
+            next = self.advance(next, anchor_day);
+        }
+        next
+    }
+}
+
+// month 所在月份的第 day 天，超出该月天数时取月末
+fn day_of_month(month: chrono::NaiveDate, day: u32) -> chrono::NaiveDate {
+    (1..=day.clamp(1, 31))
+        .rev()
+        .find_map(|day| month.with_day(day))
+        .unwrap_or(month)
+}
+
+// 定期订购状态
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SubscriptionStatus {
+    Active,
+    Paused,
+    Cancelled,
+}
+
+impl fmt::Display for SubscriptionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SubscriptionStatus::Active => write!(f, "active"),
+            SubscriptionStatus::Paused => write!(f, "paused"),
+            SubscriptionStatus::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl FromStr for SubscriptionStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "active" => Ok(SubscriptionStatus::Active),
+            "paused" => Ok(SubscriptionStatus::Paused),
+            "cancelled" => Ok(SubscriptionStatus::Cancelled),
+            _ => Err(()),
+        }
+    }
+}
+
+// 单次执行结果
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SubscriptionRunStatus {
+    Succeeded,
+    Failed,
+    Skipped,
+}
+
+impl fmt::Display for SubscriptionRunStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SubscriptionRunStatus::Succeeded => write!(f, "succeeded"),
+            SubscriptionRunStatus::Failed => write!(f, "failed"),
+            SubscriptionRunStatus::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Clone)]
+#[diesel(table_name = subscriptions)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct Subscription {
+    pub id: String,
+    pub user_id: String,
+    pub frequency: String,
+    pub status: String,
+    // 为空时使用默认收货地址
+    pub address_id: Option<String>,
+    pub next_run_at: chrono::NaiveDateTime,
+    // 按月订购的下单日，取首次下单日期的日
+    pub anchor_day: i32,
+    pub last_run_at: Option<chrono::NaiveDateTime>,
+    pub last_order_id: Option<String>,
+    // 连续下单失败的次数，成功后清零
+    pub consecutive_failures: i32,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl Subscription {
+    pub fn get_frequency(&self) -> SubscriptionFrequency {
+        SubscriptionFrequency::from_str(&self.frequency).unwrap_or(SubscriptionFrequency::Monthly)
+    }
+
+    pub fn get_status(&self) -> SubscriptionStatus {
+        SubscriptionStatus::from_str(&self.status).unwrap_or(SubscriptionStatus::Cancelled)
+    }
+
+    // 晚于 now 的下一次下单时间
+    pub fn next_run_after(&self, now: chrono::NaiveDateTime) -> chrono::NaiveDateTime {
+        self.get_frequency().next_run_after(self.next_run_at, now, self.anchor_day.max(1) as u32)
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = subscriptions)]
+pub struct NewSubscription {
+    pub id: String,
+    pub user_id: String,
+    pub frequency: String,
+    pub status: String,
+    pub address_id: Option<String>,
+    pub next_run_at: chrono::NaiveDateTime,
+    pub anchor_day: i32,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl NewSubscription {
+    pub fn new(user_id: String, frequency: SubscriptionFrequency, address_id: Option<String>, next_run_at: chrono::NaiveDateTime) -> Self {
+        let now = chrono::Utc::now().naive_utc();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            frequency: frequency.to_string(),
+            status: SubscriptionStatus::Active.to_string(),
+            address_id,
+            next_run_at,
+            anchor_day: next_run_at.day() as i32,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Associations, Clone)]
+#[diesel(belongs_to(Subscription))]
+#[diesel(table_name = subscription_items)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct SubscriptionItem {
+    pub id: String,
+    pub subscription_id: String,
+    pub product_id: String,
+    pub quantity: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = subscription_items)]
+pub struct NewSubscriptionItem {
+    pub id: String,
+    pub subscription_id: String,
+    pub product_id: String,
+    pub quantity: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Associations, Clone)]
+#[diesel(belongs_to(Subscription))]
+#[diesel(table_name = subscription_runs)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct SubscriptionRun {
+    pub id: String,
+    pub subscription_id: String,
+    pub scheduled_for: chrono::NaiveDateTime,
+    pub status: String,
+    pub order_id: Option<String>,
+    pub failure_reason: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = subscription_runs)]
+pub struct NewSubscriptionRun {
+    pub id: String,
+    pub subscription_id: String,
+    pub scheduled_for: chrono::NaiveDateTime,
+    pub status: String,
+    pub order_id: Option<String>,
+    pub failure_reason: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl NewSubscriptionRun {
+    pub fn new(
+        subscription_id: String,
+        scheduled_for: chrono::NaiveDateTime,
+        status: SubscriptionRunStatus,
+        order_id: Option<String>,
+        failure_reason: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            subscription_id,
+            scheduled_for,
+            status: status.to_string(),
+            order_id,
+            failure_reason,
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+// 创建定期订购请求
+#[derive(Debug, Deserialize)]
+pub struct CreateSubscriptionDto {
+    pub frequency: String,  // weekly 或 monthly
+    pub items: Vec<OrderItemDto>,
+    pub address_id: Option<String>,
+    // 首次下单日期（UTC），默认为一个周期之后
+    pub start_date: Option<chrono::NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubscriptionItemResponse {
+    pub product_id: String,
+    pub quantity: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubscriptionResponse {
+    pub id: String,
+    pub frequency: SubscriptionFrequency,
+    pub status: SubscriptionStatus,
+    pub address_id: Option<String>,
+    pub items: Vec<SubscriptionItemResponse>,
+    pub next_run_at: Option<chrono::NaiveDateTime>,
+    pub last_run_at: Option<chrono::NaiveDateTime>,
+    pub last_order_id: Option<String>,
+    pub consecutive_failures: i32,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl SubscriptionResponse {
+    pub fn new(subscription: Subscription, items: Vec<SubscriptionItem>) -> Self {
+        let status = subscription.get_status();
+        Self {
+            id: subscription.id.clone(),
+            frequency: subscription.get_frequency(),
+            status,
+            address_id: subscription.address_id,
+            items: items
+                .into_iter()
+                .map(|item| SubscriptionItemResponse {
+                    product_id: item.product_id,
+                    quantity: item.quantity,
+                })
+                .collect(),
+            // 已取消的订购不再执行
+            next_run_at: if status == SubscriptionStatus::Cancelled { None } else { Some(subscription.next_run_at) },
+            last_run_at: subscription.last_run_at,
+            last_order_id: subscription.last_order_id,
+            consecutive_failures: subscription.consecutive_failures,
+            created_at: subscription.created_at,
+            updated_at: subscription.updated_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(year: i32, month: u32, day: u32) -> chrono::NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(year, month, day).unwrap().and_hms_opt(8, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_frequency_advance() {
+        assert_eq!(SubscriptionFrequency::from_str(" Weekly "), Ok(SubscriptionFrequency::Weekly));
+        assert!(SubscriptionFrequency::from_str("daily").is_err());
+
+        assert_eq!(SubscriptionFrequency::Weekly.advance(at(2026, 10, 18), 18), at(2026, 10, 25));
+        assert_eq!(SubscriptionFrequency::Monthly.advance(at(2026, 10, 18), 18), at(2026, 11, 18));
+        // 目标月份没有31日时取月末
+        assert_eq!(SubscriptionFrequency::Monthly.advance(at(2027, 1, 31), 31), at(2027, 2, 28));
+    }
+
+    #[test]
+    fn test_monthly_keeps_month_end_anchor() {
+        let monthly = SubscriptionFrequency::Monthly;
+        // 从1月31日开始，之后每月都在该月最后一天下单，不会停留在28日
+        let mut runs = vec![at(2027, 1, 31)];
+        for _ in 0..4 {
+            let last = *runs.last().unwrap();
+            runs.push(monthly.advance(last, 31));
+        }
+        assert_eq!(runs, vec![at(2027, 1, 31), at(2027, 2, 28), at(2027, 3, 31), at(2027, 4, 30), at(2027, 5, 31)]);
+
+        // 闰年2月取29日，30日开始的订购3月回到30日
+        assert_eq!(monthly.advance(at(2028, 1, 30), 30), at(2028, 2, 29));
+        assert_eq!(monthly.advance(at(2028, 2, 29), 30), at(2028, 3, 30));
+
+        // 错过的周期跳过后仍按下单日计算
+        assert_eq!(monthly.next_run_after(at(2027, 1, 31), at(2027, 3, 1), 31), at(2027, 3, 31));
+    }
+
+    #[test]
+    fn test_next_run_after_skips_missed_periods() {
+        let frequency = SubscriptionFrequency::Weekly;
+        assert_eq!(frequency.next_run_after(at(2026, 10, 18), at(2026, 10, 18), 18), at(2026, 10, 25));
+        assert_eq!(frequency.next_run_after(at(2026, 10, 1), at(2026, 10, 18), 1), at(2026, 10, 22));
+        assert_eq!(SubscriptionFrequency::Monthly.next_run_after(at(2026, 7, 18), at(2026, 10, 18), 18), at(2026, 11, 18));
+    }
+}
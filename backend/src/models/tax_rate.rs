@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+use uuid::Uuid;
+use crate::schema::tax_rates;
+
+// 按商品分类单独配置的税率（百分比），未配置的分类使用管理员设置中的默认税率
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Clone)]
+#[diesel(table_name = tax_rates)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct TaxRate {
+    pub id: String,
+    pub category: String,
+    pub rate: f64,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = tax_rates)]
+pub struct NewTaxRate {
+    pub id: String,
+    pub category: String,
+    pub rate: f64,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl NewTaxRate {
+    pub fn new(category: String, rate: f64) -> Self {
+        let now = chrono::Utc::now().naive_utc();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            category,
+            rate,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+// 设置分类税率请求
+#[derive(Debug, Deserialize)]
+pub struct SetTaxRateDto {
+    pub rate: f64,
+}
@@ -48,6 +48,8 @@ pub struct User {
     pub role: String, // Stored as string in database
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    // 游客结账时创建的临时用户，注册后转为正式用户
+    pub is_guest: bool,
 }
 
 #[derive(Insertable)]
@@ -61,6 +63,41 @@ pub struct NewUser {
     pub updated_at: chrono::NaiveDateTime,
 }
 
+// 游客临时用户：邮箱为占位地址、密码为空，不能登录，只能通过购物车令牌访问游客接口
+#[derive(Insertable)]
+#[diesel(table_name = users)]
+pub struct NewGuestUser {
+    pub id: String,
+    pub email: String,
+    pub password_hash: String,
+    pub role: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    pub is_guest: bool,
+}
+
+impl NewGuestUser {
+    pub fn new() -> Self {
+        let now = Utc::now().naive_utc();
+        let id = Uuid::new_v4().to_string();
+        Self {
+            email: format!("guest-{}@guest.invalid", id),
+            id,
+            password_hash: String::new(),
+            role: UserRole::Customer.to_string(),
+            created_at: now,
+            updated_at: now,
+            is_guest: true,
+        }
+    }
+}
+
+impl Default for NewGuestUser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl User {
     pub fn new(email: String, password_hash: String, role: UserRole) -> Self {
         let now = chrono::Utc::now();
@@ -71,6 +108,7 @@ impl User {
             role: role.to_string(),
             created_at: now.naive_utc(),
             updated_at: now.naive_utc(),
+            is_guest: false,
         }
     }
     
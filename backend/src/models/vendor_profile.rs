@@ -1,8 +1,18 @@
 use serde::{Deserialize, Serialize};
 use diesel::prelude::*;
 use uuid::Uuid;
+use regex::Regex;
+use lazy_static::lazy_static;
 use crate::schema::vendor_profiles;
 
+// 退货政策中未写明天数时的默认退货期（天）
+pub const DEFAULT_RETURN_WINDOW_DAYS: i64 = 7;
+
+lazy_static! {
+    // 匹配 "30-day"、"14 days"、"7天"、"15日" 等写法
+    static ref RETURN_DAYS_REGEX: Regex = Regex::new(r"(?i)(\d+)\s*-?\s*(?:days?|天|日)").unwrap();
+}
+
 #[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable)]
 #[diesel(table_name = vendor_profiles)]
 #[diesel(check_for_backend(diesel::mysql::Mysql))]
@@ -126,4 +136,33 @@ impl NewVendorProfile {
             notification_settings: None,
         }
     }
-} 
\ No newline at end of file
+}
+
+// 根据商家的退货设置计算送达后的退货期天数：
+// 不接受退货时为0；退货政策中写明天数时取第一个天数；否则使用默认值
+pub fn return_window_days(accepts_returns: bool, return_policy: Option<&str>) -> i64 {
+    if !accepts_returns {
+        return 0;
+    }
+
+    return_policy
+        .and_then(|policy| RETURN_DAYS_REGEX.captures(policy))
+        .and_then(|captures| captures[1].parse::<i64>().ok())
+        .unwrap_or(DEFAULT_RETURN_WINDOW_DAYS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_return_window_days() {
+        assert_eq!(return_window_days(true, Some("30-day return policy")), 30);
+        assert_eq!(return_window_days(true, Some("14-day return for unworn items")), 14);
+        assert_eq!(return_window_days(true, Some("Returns accepted within 10 Days")), 10);
+        assert_eq!(return_window_days(true, Some("支持15天无理由退货")), 15);
+        assert_eq!(return_window_days(true, Some("Final sale on discounted items")), DEFAULT_RETURN_WINDOW_DAYS);
+        assert_eq!(return_window_days(true, None), DEFAULT_RETURN_WINDOW_DAYS);
+        assert_eq!(return_window_days(false, Some("30-day return policy")), 0);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use diesel::prelude::*;
+use std::fmt;
+use std::str::FromStr;
+use crate::schema::{wallets, wallet_transactions};
+
+// 钱包流水类型
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletTransactionKind {
+    // 管理员入账，例如补偿
+    AdminCredit,
+    // 管理员扣减
+    AdminDebit,
+    // 下单时使用余额抵扣
+    OrderPayment,
+    // 订单取消或退款退回钱包
+    OrderRefund,
+}
+
+impl fmt::Display for WalletTransactionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WalletTransactionKind::AdminCredit => write!(f, "admin_credit"),
+            WalletTransactionKind::AdminDebit => write!(f, "admin_debit"),
+            WalletTransactionKind::OrderPayment => write!(f, "order_payment"),
+            WalletTransactionKind::OrderRefund => write!(f, "order_refund"),
+        }
+    }
+}
+
+impl FromStr for WalletTransactionKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "admin_credit" => Ok(WalletTransactionKind::AdminCredit),
+            "admin_debit" => Ok(WalletTransactionKind::AdminDebit),
+            "order_payment" => Ok(WalletTransactionKind::OrderPayment),
+            "order_refund" => Ok(WalletTransactionKind::OrderRefund),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Clone)]
+#[diesel(table_name = wallets)]
+#[diesel(primary_key(user_id))]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct Wallet {
+    pub user_id: String,
+    pub balance: f64,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+// 钱包流水，只追加不修改
+#[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, Selectable, Clone)]
+#[diesel(table_name = wallet_transactions)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct WalletTransaction {
+    pub id: String,
+    pub user_id: String,
+    pub kind: String,
+    // 入账为正，出账为负
+    pub amount: f64,
+    // 记账后的余额
+    pub balance_after: f64,
+    pub reason: Option<String>,
+    pub order_id: Option<String>,
+    // 操作的管理员，系统记账时为空
+    pub created_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = wallet_transactions)]
+pub struct NewWalletTransaction {
+    pub id: String,
+    pub user_id: String,
+    pub kind: String,
+    pub amount: f64,
+    pub balance_after: f64,
+    pub reason: Option<String>,
+    pub order_id: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+// 管理员调整钱包余额请求，reason 必填
+#[derive(Debug, Deserialize)]
+pub struct AdjustWalletDto {
+    pub amount: f64,
+    pub reason: String,
+}
+
+// 钱包流水查询参数
+#[derive(Debug, Deserialize)]
+pub struct WalletQuery {
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
@@ -1,12 +1,38 @@
 use actix_web::web;
 use crate::handlers::admin::{get_admin_settings, update_admin_settings};
-use crate::middleware::auth::Authentication;
+use crate::handlers::tax_rate::{get_tax_rates, set_tax_rate, delete_tax_rate};
+use crate::handlers::risk::{get_risk_rules, update_risk_rule, get_risk_reviews, approve_risk_review, reject_risk_review};
+use crate::handlers::payment::{refund_payment, sync_payment};
+use crate::handlers::wallet::{credit_wallet, debit_wallet};
+use crate::handlers::coupon::{get_coupons, create_coupon, update_coupon};
+use crate::handlers::promotion::{get_promotions, create_promotion, update_promotion};
+use crate::middleware::{Authentication, RequireAuth};
+use crate::models::user::UserRole;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/admin")
             .wrap(Authentication)
+            .app_data(RequireAuth(vec![UserRole::Admin]))
             .route("/settings/{user_id}", web::get().to(get_admin_settings))
             .route("/settings/{user_id}", web::put().to(update_admin_settings))
+            .route("/tax-rates", web::get().to(get_tax_rates))
+            .route("/tax-rates/{category}", web::put().to(set_tax_rate))
+            .route("/tax-rates/{category}", web::delete().to(delete_tax_rate))
+            .route("/risk-rules", web::get().to(get_risk_rules))
+            .route("/risk-rules/{rule}", web::put().to(update_risk_rule))
+            .route("/risk-reviews", web::get().to(get_risk_reviews))
+            .route("/risk-reviews/{order_id}/approve", web::post().to(approve_risk_review))
+            .route("/risk-reviews/{order_id}/reject", web::post().to(reject_risk_review))
+            .route("/payments/{id}/sync", web::post().to(sync_payment))
+            .route("/payments/{id}/refund", web::post().to(refund_payment))
+            .route("/wallets/{user_id}/credit", web::post().to(credit_wallet))
+            .route("/wallets/{user_id}/debit", web::post().to(debit_wallet))
+            .route("/coupons", web::get().to(get_coupons))
+            .route("/coupons", web::post().to(create_coupon))
+            .route("/coupons/{id}", web::put().to(update_coupon))
+            .route("/promotions", web::get().to(get_promotions))
+            .route("/promotions", web::post().to(create_promotion))
+            .route("/promotions/{id}", web::put().to(update_promotion))
     );
 } 
\ No newline at end of file
@@ -8,6 +8,8 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .wrap(Authentication)
             .route("", web::get().to(cart::get_cart))
             .route("/add", web::post().to(cart::add_to_cart))
+            .route("/coupon", web::post().to(cart::apply_coupon))
+            .route("/coupon", web::delete().to(cart::remove_coupon))
             .route("/{id}", web::put().to(cart::update_cart_item))
             .route("/{id}", web::delete().to(cart::remove_from_cart))
             .route("/checkout", web::post().to(cart::checkout))
@@ -0,0 +1,36 @@
+use actix_web::web;
+use crate::handlers::cart;
+use crate::handlers::guest;
+use crate::handlers::payment;
+use crate::middleware::GuestCartAuthentication;
+
+// 游客接口：购物车、结账、支付和注册通过 X-Cart-Token 购物车令牌识别游客，创建购物车和查询订单无需令牌
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/guest")
+            .route("/carts", web::post().to(guest::create_guest_cart))
+            .route("/orders/lookup", web::post().to(guest::lookup_guest_order))
+            .service(
+                web::resource("/orders/{id}/payments")
+                    .wrap(GuestCartAuthentication)
+                    .route(web::get().to(payment::get_order_payments))
+                    .route(web::post().to(payment::create_payment))
+            )
+            .service(
+                web::scope("/cart")
+                    .wrap(GuestCartAuthentication)
+                    .route("", web::get().to(cart::get_cart))
+                    .route("/add", web::post().to(cart::add_to_cart))
+                    .route("/checkout", web::post().to(guest::guest_checkout))
+                    .route("/coupon", web::post().to(cart::apply_coupon))
+                    .route("/coupon", web::delete().to(cart::remove_coupon))
+                    .route("/{id}", web::put().to(cart::update_cart_item))
+                    .route("/{id}", web::delete().to(cart::remove_from_cart))
+            )
+            .service(
+                web::scope("/account")
+                    .wrap(GuestCartAuthentication)
+                    .route("", web::post().to(guest::convert_guest_account))
+            )
+    );
+}
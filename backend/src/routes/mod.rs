@@ -1,6 +1,7 @@
 pub mod auth;
 pub mod product;
 pub mod cart;
+pub mod guest;
 pub mod order;
 pub mod favorite;
 pub mod user_profile;
@@ -8,6 +9,8 @@ pub mod vendor_profile;
 pub mod user;
 pub mod analytics;
 pub mod admin;
+pub mod subscription;
+pub mod payment;
 
 use actix_web::{HttpResponse, Responder};
 
@@ -1,5 +1,10 @@
 use actix_web::web;
 use crate::handlers::order;
+use crate::handlers::shipment;
+use crate::handlers::invoice;
+use crate::handlers::cart;
+use crate::handlers::order_message;
+use crate::handlers::payment;
 use crate::middleware::Authentication;
 use crate::middleware::RequireAuth;
 use crate::models::user::UserRole;
@@ -9,7 +14,14 @@ pub fn config(cfg: &mut web::ServiceConfig) {
         web::scope("/api/orders")
             .wrap(Authentication)
             .route("", web::get().to(order::get_user_orders))
+            .route("", web::post().to(order::create_order))
             .route("/vendor", web::get().to(order::get_vendor_orders))
+            .service(
+                web::resource("/vendor/export")
+                    .app_data(RequireAuth(vec![UserRole::Vendor]))
+                    .route(web::get().to(order::export_vendor_orders))
+            )
+            .route("/messages/unread", web::get().to(order_message::get_unread_message_count))
             .service(
                 web::resource("/all")
                     .app_data(RequireAuth(vec![UserRole::Admin]))
@@ -21,5 +33,26 @@ pub fn config(cfg: &mut web::ServiceConfig) {
                     .app_data(RequireAuth(vec![UserRole::Admin, UserRole::Vendor]))
                     .route(web::put().to(order::update_order_status))
             )
+            .service(
+                web::resource("/{id}/shipments")
+                    .route(web::get().to(shipment::get_order_shipments))
+                    .route(web::post().to(shipment::create_shipment))
+            )
+            .route("/{id}/shipments/{shipment_id}/tracking", web::get().to(shipment::track_shipment))
+            .service(
+                web::resource("/{id}/payments")
+                    .route(web::get().to(payment::get_order_payments))
+                    .route(web::post().to(payment::create_payment))
+            )
+            .route("/{id}/invoice", web::get().to(invoice::get_order_invoice))
+            .route("/{id}/history", web::get().to(order::get_order_history))
+            .route("/{id}/reorder", web::post().to(cart::reorder))
+            .route("/{id}/confirm-receipt", web::post().to(order::confirm_receipt))
+            .service(
+                web::resource("/{id}/messages")
+                    .route(web::get().to(order_message::get_order_messages))
+                    .route(web::post().to(order_message::send_order_message))
+            )
+            .route("/{id}/messages/read", web::post().to(order_message::mark_order_messages_read))
     );
 } 
\ No newline at end of file
@@ -0,0 +1,10 @@
+use actix_web::web;
+use crate::handlers::payment;
+
+// 支付渠道回调无需登录，由各渠道的签名校验保证来源可信
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/payments")
+            .route("/callback/{gateway}", web::post().to(payment::payment_callback))
+    );
+}
\ No newline at end of file
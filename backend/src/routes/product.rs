@@ -10,6 +10,7 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             
             // 供应商专用路由 - 放在/{id}路由之前
             .route("/vendor", web::get().to(product::get_vendor_products).wrap(Authentication))
+            .route("/vendor/backorders", web::get().to(product::get_vendor_backorders).wrap(Authentication))
             
             // 公开路由 - 单个商品
             .route("/{id}", web::get().to(product::get_product_by_id))
@@ -20,6 +21,7 @@ pub fn config(cfg: &mut web::ServiceConfig) {
                     .wrap(Authentication)
                     .route("", web::post().to(product::create_product))
                     .route("/{id}", web::put().to(product::update_product))
+                    .route("/{id}/backorder", web::put().to(product::update_backorder_settings))
                     .route("/{id}", web::delete().to(product::delete_product))
             )
     );
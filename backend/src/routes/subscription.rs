@@ -0,0 +1,18 @@
+use actix_web::web;
+use crate::handlers::subscription;
+use crate::middleware::Authentication;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/subscriptions")
+            .wrap(Authentication)
+            .route("", web::get().to(subscription::get_subscriptions))
+            .route("", web::post().to(subscription::create_subscription))
+            .route("/{id}", web::get().to(subscription::get_subscription))
+            .route("/{id}/runs", web::get().to(subscription::get_subscription_runs))
+            .route("/{id}/skip", web::post().to(subscription::skip_subscription))
+            .route("/{id}/pause", web::post().to(subscription::pause_subscription))
+            .route("/{id}/resume", web::post().to(subscription::resume_subscription))
+            .route("/{id}/cancel", web::post().to(subscription::cancel_subscription))
+    );
+}
\ No newline at end of file
@@ -1,5 +1,8 @@
 use actix_web::web;
 use crate::handlers::user_profile::{get_user_profile, update_user_profile};
+use crate::handlers::address::{get_addresses, create_address, update_address, delete_address};
+use crate::handlers::notification::{get_notifications, mark_notification_read};
+use crate::handlers::wallet::get_wallet;
 use crate::middleware::Authentication;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
@@ -9,6 +12,13 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .wrap(Authentication)
             .route("", web::get().to(get_user_profile))
             .route("", web::put().to(update_user_profile))
+            .route("/addresses", web::get().to(get_addresses))
+            .route("/addresses", web::post().to(create_address))
+            .route("/addresses/{id}", web::put().to(update_address))
+            .route("/addresses/{id}", web::delete().to(delete_address))
+            .route("/notifications", web::get().to(get_notifications))
+            .route("/notifications/{id}/read", web::post().to(mark_notification_read))
+            .route("/wallet", web::get().to(get_wallet))
     );
     println!("用户详细信息路由已配置: /api/profile");
 } 
\ No newline at end of file
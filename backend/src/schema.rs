@@ -8,6 +8,7 @@ diesel::table! {
         role -> Varchar,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        is_guest -> Bool,
     }
 }
 
@@ -42,6 +43,7 @@ diesel::table! {
         tax_rate -> Double,
         payment_gateways -> Text,
         log_level -> Varchar,
+        prices_include_tax -> Bool,
         created_at -> Timestamp,
         updated_at -> Timestamp,
     }
@@ -80,17 +82,35 @@ diesel::table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         category -> Nullable<Varchar>,
+        backorder_mode -> Varchar,
+        backorder_limit -> Nullable<Integer>,
+        expected_ship_date -> Nullable<Date>,
     }
 }
 
 diesel::table! {
     orders (id) {
         id -> Varchar,
+        order_number -> Varchar,
         user_id -> Varchar,
+        subtotal -> Double,
+        tax -> Double,
         total -> Double,
         status -> Varchar,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        contact_email -> Nullable<Varchar>,
+        on_hold -> Bool,
+        wallet_amount -> Double,
+        discount -> Double,
+        coupon_code -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    order_number_sequences (sequence_date) {
+        sequence_date -> Date,
+        last_value -> Bigint,
     }
 }
 
@@ -101,6 +121,204 @@ diesel::table! {
         product_id -> Varchar,
         quantity -> Integer,
         price -> Double,
+        fulfillment_id -> Nullable<Varchar>,
+        tax_rate -> Double,
+        tax_amount -> Double,
+        total -> Double,
+        backordered_quantity -> Integer,
+        expected_ship_date -> Nullable<Date>,
+        discount -> Double,
+    }
+}
+
+diesel::table! {
+    order_fulfillments (id) {
+        id -> Varchar,
+        order_id -> Varchar,
+        vendor_id -> Varchar,
+        status -> Varchar,
+        subtotal -> Double,
+        tax -> Double,
+        shipping_fee -> Double,
+        total -> Double,
+        shipped_at -> Nullable<Timestamp>,
+        delivered_at -> Nullable<Timestamp>,
+        return_window_ends_at -> Nullable<Timestamp>,
+        completed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    shipments (id) {
+        id -> Varchar,
+        order_id -> Varchar,
+        fulfillment_id -> Varchar,
+        vendor_id -> Varchar,
+        carrier -> Varchar,
+        tracking_number -> Varchar,
+        status -> Varchar,
+        shipped_at -> Timestamp,
+        delivered_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    shipment_items (id) {
+        id -> Varchar,
+        shipment_id -> Varchar,
+        order_item_id -> Varchar,
+        quantity -> Integer,
+    }
+}
+
+diesel::table! {
+    order_status_history (id) {
+        id -> Varchar,
+        order_id -> Varchar,
+        from_status -> Nullable<Varchar>,
+        to_status -> Varchar,
+        reason -> Nullable<Varchar>,
+        changed_by -> Nullable<Varchar>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    order_messages (id) {
+        id -> Varchar,
+        order_id -> Varchar,
+        sender_id -> Varchar,
+        sender_role -> Varchar,
+        body -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    order_message_attachments (id) {
+        id -> Varchar,
+        message_id -> Varchar,
+        url -> Varchar,
+        file_name -> Nullable<Varchar>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    order_message_reads (id) {
+        id -> Varchar,
+        order_id -> Varchar,
+        user_id -> Varchar,
+        last_read_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    invoices (id) {
+        id -> Varchar,
+        order_id -> Varchar,
+        invoice_number -> Varchar,
+        issued_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    invoice_number_sequences (sequence_year) {
+        sequence_year -> Integer,
+        last_value -> Bigint,
+    }
+}
+
+diesel::table! {
+    tax_rates (id) {
+        id -> Varchar,
+        category -> Varchar,
+        rate -> Double,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    risk_rules (id) {
+        id -> Varchar,
+        rule -> Varchar,
+        enabled -> Bool,
+        threshold -> Double,
+        score -> Integer,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    order_risk_assessments (id) {
+        id -> Varchar,
+        order_id -> Nullable<Varchar>,
+        user_id -> Varchar,
+        client_ip -> Nullable<Varchar>,
+        order_total -> Double,
+        score -> Integer,
+        decision -> Varchar,
+        reasons -> Text,
+        review_status -> Nullable<Varchar>,
+        reviewed_by -> Nullable<Varchar>,
+        reviewed_at -> Nullable<Timestamp>,
+        review_note -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    idempotency_keys (id) {
+        id -> Varchar,
+        user_id -> Varchar,
+        endpoint -> Varchar,
+        idempotency_key -> Varchar,
+        request_fingerprint -> Varchar,
+        order_id -> Nullable<Varchar>,
+        response_status -> Nullable<Integer>,
+        response_body -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    addresses (id) {
+        id -> Varchar,
+        user_id -> Varchar,
+        recipient_name -> Varchar,
+        phone -> Varchar,
+        province -> Varchar,
+        city -> Varchar,
+        district -> Varchar,
+        street -> Text,
+        postal_code -> Nullable<Varchar>,
+        is_default_shipping -> Bool,
+        is_default_billing -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    order_addresses (id) {
+        id -> Varchar,
+        order_id -> Varchar,
+        recipient_name -> Varchar,
+        phone -> Varchar,
+        province -> Varchar,
+        city -> Varchar,
+        district -> Varchar,
+        street -> Text,
+        postal_code -> Nullable<Varchar>,
+        created_at -> Timestamp,
     }
 }
 
@@ -124,6 +342,194 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    subscriptions (id) {
+        id -> Varchar,
+        user_id -> Varchar,
+        frequency -> Varchar,
+        status -> Varchar,
+        address_id -> Nullable<Varchar>,
+        next_run_at -> Timestamp,
+        anchor_day -> Integer,
+        last_run_at -> Nullable<Timestamp>,
+        last_order_id -> Nullable<Varchar>,
+        consecutive_failures -> Integer,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    subscription_items (id) {
+        id -> Varchar,
+        subscription_id -> Varchar,
+        product_id -> Varchar,
+        quantity -> Integer,
+    }
+}
+
+diesel::table! {
+    subscription_runs (id) {
+        id -> Varchar,
+        subscription_id -> Varchar,
+        scheduled_for -> Timestamp,
+        status -> Varchar,
+        order_id -> Nullable<Varchar>,
+        failure_reason -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    notifications (id) {
+        id -> Varchar,
+        user_id -> Varchar,
+        kind -> Varchar,
+        title -> Varchar,
+        content -> Text,
+        related_id -> Nullable<Varchar>,
+        read_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    payments (id) {
+        id -> Varchar,
+        order_id -> Varchar,
+        user_id -> Varchar,
+        gateway -> Varchar,
+        gateway_payment_id -> Varchar,
+        status -> Varchar,
+        amount -> Double,
+        paid_amount -> Nullable<Double>,
+        currency -> Varchar,
+        payment_url -> Nullable<Varchar>,
+        refunded_amount -> Double,
+        failure_reason -> Nullable<Text>,
+        paid_at -> Nullable<Timestamp>,
+        refunded_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    payment_callbacks (id) {
+        id -> Varchar,
+        payment_id -> Varchar,
+        gateway -> Varchar,
+        notification_id -> Varchar,
+        status -> Varchar,
+        amount -> Double,
+        sent_at -> Timestamp,
+        outcome -> Varchar,
+        received_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    wallets (user_id) {
+        user_id -> Varchar,
+        balance -> Double,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    wallet_transactions (id) {
+        id -> Varchar,
+        user_id -> Varchar,
+        kind -> Varchar,
+        amount -> Double,
+        balance_after -> Double,
+        reason -> Nullable<Text>,
+        order_id -> Nullable<Varchar>,
+        created_by -> Nullable<Varchar>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    coupons (id) {
+        id -> Varchar,
+        code -> Varchar,
+        discount_type -> Varchar,
+        discount_value -> Double,
+        min_spend -> Double,
+        starts_at -> Nullable<Timestamp>,
+        ends_at -> Nullable<Timestamp>,
+        usage_limit -> Nullable<Integer>,
+        per_user_limit -> Nullable<Integer>,
+        used_count -> Integer,
+        product_ids -> Nullable<Text>,
+        categories -> Nullable<Text>,
+        vendor_ids -> Nullable<Text>,
+        active -> Bool,
+        created_by -> Nullable<Varchar>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    coupon_redemptions (id) {
+        id -> Varchar,
+        coupon_id -> Varchar,
+        user_id -> Varchar,
+        order_id -> Varchar,
+        discount -> Double,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    cart_coupons (user_id) {
+        user_id -> Varchar,
+        coupon_id -> Varchar,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    promotions (id) {
+        id -> Varchar,
+        name -> Varchar,
+        kind -> Varchar,
+        priority -> Integer,
+        stackable -> Bool,
+        tiers -> Nullable<Text>,
+        buy_quantity -> Nullable<Integer>,
+        free_quantity -> Nullable<Integer>,
+        percentage -> Nullable<Double>,
+        product_ids -> Nullable<Text>,
+        categories -> Nullable<Text>,
+        vendor_ids -> Nullable<Text>,
+        funded_by -> Nullable<Varchar>,
+        starts_at -> Nullable<Timestamp>,
+        ends_at -> Nullable<Timestamp>,
+        active -> Bool,
+        created_by -> Nullable<Varchar>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    order_item_promotions (id) {
+        id -> Varchar,
+        order_id -> Varchar,
+        order_item_id -> Varchar,
+        promotion_id -> Varchar,
+        promotion_name -> Varchar,
+        kind -> Varchar,
+        discount -> Double,
+        funded_by -> Nullable<Varchar>,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::joinable!(user_profiles -> users (user_id));
 diesel::joinable!(admin_profiles -> users (admin_id));
 diesel::joinable!(vendor_profiles -> users (vendor_id));
@@ -131,10 +537,45 @@ diesel::joinable!(products -> users (vendor_id));
 diesel::joinable!(orders -> users (user_id));
 diesel::joinable!(order_items -> orders (order_id));
 diesel::joinable!(order_items -> products (product_id));
+diesel::joinable!(order_addresses -> orders (order_id));
+diesel::joinable!(addresses -> users (user_id));
+diesel::joinable!(order_fulfillments -> orders (order_id));
+diesel::joinable!(order_fulfillments -> users (vendor_id));
+diesel::joinable!(order_items -> order_fulfillments (fulfillment_id));
+diesel::joinable!(shipments -> orders (order_id));
+diesel::joinable!(order_status_history -> orders (order_id));
+diesel::joinable!(order_messages -> orders (order_id));
+diesel::joinable!(order_messages -> users (sender_id));
+diesel::joinable!(order_message_attachments -> order_messages (message_id));
+diesel::joinable!(order_message_reads -> orders (order_id));
+diesel::joinable!(order_message_reads -> users (user_id));
+diesel::joinable!(invoices -> orders (order_id));
+diesel::joinable!(shipments -> order_fulfillments (fulfillment_id));
+diesel::joinable!(shipments -> users (vendor_id));
+diesel::joinable!(shipment_items -> shipments (shipment_id));
+diesel::joinable!(shipment_items -> order_items (order_item_id));
+diesel::joinable!(idempotency_keys -> users (user_id));
+diesel::joinable!(idempotency_keys -> orders (order_id));
+diesel::joinable!(order_risk_assessments -> orders (order_id));
+diesel::joinable!(order_risk_assessments -> users (user_id));
 diesel::joinable!(cart_items -> users (user_id));
 diesel::joinable!(cart_items -> products (product_id));
 diesel::joinable!(favorites -> users (user_id));
 diesel::joinable!(favorites -> products (product_id));
+diesel::joinable!(subscriptions -> users (user_id));
+diesel::joinable!(subscriptions -> addresses (address_id));
+diesel::joinable!(subscription_items -> subscriptions (subscription_id));
+diesel::joinable!(subscription_items -> products (product_id));
+diesel::joinable!(subscription_runs -> subscriptions (subscription_id));
+diesel::joinable!(subscription_runs -> orders (order_id));
+diesel::joinable!(notifications -> users (user_id));
+diesel::joinable!(payments -> orders (order_id));
+diesel::joinable!(payment_callbacks -> payments (payment_id));
+diesel::joinable!(wallet_transactions -> wallets (user_id));
+diesel::joinable!(coupon_redemptions -> coupons (coupon_id));
+diesel::joinable!(coupon_redemptions -> orders (order_id));
+diesel::joinable!(cart_coupons -> coupons (coupon_id));
+diesel::joinable!(order_item_promotions -> order_items (order_item_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     users,
@@ -143,7 +584,36 @@ diesel::allow_tables_to_appear_in_same_query!(
     vendor_profiles,
     products,
     orders,
+    order_number_sequences,
     order_items,
+    order_addresses,
+    order_fulfillments,
+    shipments,
+    shipment_items,
+    order_status_history,
+    order_messages,
+    order_message_attachments,
+    order_message_reads,
+    invoices,
+    invoice_number_sequences,
+    tax_rates,
+    risk_rules,
+    order_risk_assessments,
+    idempotency_keys,
+    addresses,
     cart_items,
     favorites,
+    subscriptions,
+    subscription_items,
+    subscription_runs,
+    notifications,
+    payments,
+    payment_callbacks,
+    wallets,
+    wallet_transactions,
+    coupons,
+    coupon_redemptions,
+    cart_coupons,
+    promotions,
+    order_item_promotions,
 ); 
\ No newline at end of file
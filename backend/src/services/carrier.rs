@@ -0,0 +1,170 @@
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// 物流轨迹节点状态
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackingStatus {
+    InfoReceived,
+    InTransit,
+    OutForDelivery,
+    Delivered,
+    Exception,
+}
+
+// 单条物流轨迹
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrackingEvent {
+    pub status: TrackingStatus,
+    pub description: String,
+    pub location: Option<String>,
+    pub occurred_at: NaiveDateTime,
+}
+
+#[derive(Debug)]
+pub enum CarrierError {
+    // 承运商不认识该运单号
+    NotFound,
+    // 调用承运商接口失败
+    Unavailable(String),
+}
+
+impl fmt::Display for CarrierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CarrierError::NotFound => write!(f, "运单号不存在"),
+            CarrierError::Unavailable(reason) => write!(f, "承运商服务不可用: {}", reason),
+        }
+    }
+}
+
+// 承运商轨迹查询接口，接入新的快递公司时实现该trait并在 carrier_for 中注册
+pub trait CarrierTracker: Send + Sync {
+    // 承运商代码，与 shipments.carrier 字段对应
+    fn code(&self) -> &'static str;
+
+    // 查询运单轨迹，按时间先后排序
+    fn track(&self, tracking_number: &str, shipped_at: NaiveDateTime) -> Result<Vec<TrackingEvent>, CarrierError>;
+}
+
+// 本地模拟承运商，按发货后经过的时间生成轨迹，用于开发和测试
+pub struct FakeLocalCarrier {
+    now: Option<NaiveDateTime>,
+}
+
+impl FakeLocalCarrier {
+    pub const CODE: &'static str = "local";
+
+    pub fn new() -> Self {
+        Self { now: None }
+    }
+
+    // 固定当前时间，便于测试得到确定的轨迹
+    #[cfg(test)]
+    pub fn at(now: NaiveDateTime) -> Self {
+        Self { now: Some(now) }
+    }
+
+    fn now(&self) -> NaiveDateTime {
+        self.now.unwrap_or_else(|| chrono::Utc::now().naive_utc())
+    }
+}
+
+impl Default for FakeLocalCarrier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CarrierTracker for FakeLocalCarrier {
+    fn code(&self) -> &'static str {
+        Self::CODE
+    }
+
+    fn track(&self, tracking_number: &str, shipped_at: NaiveDateTime) -> Result<Vec<TrackingEvent>, CarrierError> {
+        if tracking_number.trim().is_empty() {
+            return Err(CarrierError::NotFound);
+        }
+
+        // 运单号以 TIMEOUT 开头时模拟承运商接口故障，以 LOST 开头的包裹在运输途中出现异常
+        let tracking_number = tracking_number.to_uppercase();
+        if tracking_number.starts_with("TIMEOUT") {
+            return Err(CarrierError::Unavailable("查询超时".to_string()));
+        }
+        let lost = tracking_number.starts_with("LOST");
+
+        let mut timeline = vec![
+            (Duration::zero(), TrackingStatus::InfoReceived, "商家已发货，等待揽收", "发货仓"),
+            (Duration::hours(2), TrackingStatus::InTransit, "快件已揽收，运输中", "本地分拨中心"),
+        ];
+        if lost {
+            timeline.push((Duration::hours(12), TrackingStatus::Exception, "快件异常，请联系承运商", "本地分拨中心"));
+        } else {
+            timeline.push((Duration::hours(24), TrackingStatus::OutForDelivery, "快件派送中", "收件人所在网点"));
+            timeline.push((Duration::hours(30), TrackingStatus::Delivered, "快件已签收", "收件地址"));
+        }
+
+        let now = self.now();
+        Ok(timeline
+            .into_iter()
+            .map(|(offset, status, description, location)| TrackingEvent {
+                status,
+                description: description.to_string(),
+                location: Some(location.to_string()),
+                occurred_at: shipped_at + offset,
+            })
+            .filter(|event| event.occurred_at <= now)
+            .collect())
+    }
+}
+
+// 根据承运商代码获取轨迹查询实现，未接入的承运商返回 None
+pub fn carrier_for(code: &str) -> Option<Box<dyn CarrierTracker>> {
+    let carriers: Vec<Box<dyn CarrierTracker>> = vec![
+        Box::new(FakeLocalCarrier::new()),
+    ];
+
+    carriers.into_iter().find(|carrier| carrier.code().eq_ignore_ascii_case(code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shipped_at() -> NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(2026, 10, 1)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_fake_carrier_reveals_events_over_time() {
+        let carrier = FakeLocalCarrier::at(shipped_at() + Duration::hours(3));
+        let events = carrier.track("LC0001", shipped_at()).unwrap();
+        let statuses: Vec<TrackingStatus> = events.into_iter().map(|e| e.status).collect();
+        assert_eq!(statuses, vec![TrackingStatus::InfoReceived, TrackingStatus::InTransit]);
+
+        let carrier = FakeLocalCarrier::at(shipped_at() + Duration::days(2));
+        let events = carrier.track("LC0001", shipped_at()).unwrap();
+        assert_eq!(events.last().unwrap().status, TrackingStatus::Delivered);
+    }
+
+    #[test]
+    fn test_fake_carrier_lost_parcel() {
+        let carrier = FakeLocalCarrier::at(shipped_at() + Duration::days(2));
+        let events = carrier.track("LOST-42", shipped_at()).unwrap();
+        assert_eq!(events.last().unwrap().status, TrackingStatus::Exception);
+        assert!(events.iter().all(|e| e.status != TrackingStatus::Delivered));
+    }
+
+    #[test]
+    fn test_carrier_registry() {
+        assert!(carrier_for("local").is_some());
+        assert!(carrier_for("LOCAL").is_some());
+        assert!(carrier_for("unknown-express").is_none());
+        assert!(matches!(FakeLocalCarrier::new().track(" ", shipped_at()), Err(CarrierError::NotFound)));
+        assert!(matches!(FakeLocalCarrier::new().track("timeout-1", shipped_at()), Err(CarrierError::Unavailable(_))));
+    }
+}
@@ -0,0 +1,100 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::env;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// 游客请求携带购物车令牌的请求头
+pub const CART_TOKEN_HEADER: &str = "X-Cart-Token";
+// 默认有效期（天）
+const DEFAULT_TTL_DAYS: i64 = 30;
+
+// 购物车令牌格式：<游客用户ID>.<签发时间戳>.<HMAC-SHA256签名>
+// 签名密钥取 CART_TOKEN_SECRET，未设置时使用 JWT_SECRET
+fn secret() -> String {
+    env::var("CART_TOKEN_SECRET")
+        .or_else(|_| env::var("JWT_SECRET"))
+        .unwrap_or_else(|_| "your_jwt_secret_key_change_in_production".to_string())
+}
+
+// 令牌有效期，从 GUEST_CART_TTL_DAYS 读取
+pub fn ttl_days() -> i64 {
+    env::var("GUEST_CART_TTL_DAYS")
+        .ok()
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(DEFAULT_TTL_DAYS)
+}
+
+fn sign(secret: &str, payload: &str) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(payload.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn issue_with(secret: &str, guest_id: &str, issued_at: i64) -> String {
+    let payload = format!("{}.{}", guest_id, issued_at);
+    let signature = sign(secret, &payload);
+    format!("{}.{}", payload, signature)
+}
+
+fn verify_with(secret: &str, token: &str, now: i64, ttl_days: i64) -> Result<String, String> {
+    let mut parts = token.trim().rsplitn(3, '.');
+    let (signature, issued_at, guest_id) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(signature), Some(issued_at), Some(guest_id)) if !guest_id.is_empty() => (signature, issued_at, guest_id),
+        _ => return Err("购物车令牌格式无效".to_string()),
+    };
+
+    let signature = hex::decode(signature).map_err(|_| "购物车令牌格式无效".to_string())?;
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}.{}", guest_id, issued_at).as_bytes());
+    // verify_slice 使用常量时间比较
+    mac.verify_slice(&signature).map_err(|_| "购物车令牌签名无效".to_string())?;
+
+    let issued_at = issued_at.parse::<i64>().map_err(|_| "购物车令牌格式无效".to_string())?;
+    if now - issued_at > ttl_days * 24 * 60 * 60 {
+        return Err("购物车令牌已过期".to_string());
+    }
+
+    Ok(guest_id.to_string())
+}
+
+// 为游客用户签发购物车令牌
+pub fn issue(guest_id: &str) -> String {
+    issue_with(&secret(), guest_id, chrono::Utc::now().timestamp())
+}
+
+// 校验购物车令牌，返回游客用户ID
+pub fn verify(token: &str) -> Result<String, String> {
+    verify_with(&secret(), token, chrono::Utc::now().timestamp(), ttl_days())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "test-secret";
+    const GUEST_ID: &str = "6f1c2d4e-0000-4000-8000-000000000001";
+
+    #[test]
+    fn test_token_round_trip() {
+        let token = issue_with(SECRET, GUEST_ID, 1_000);
+        assert_eq!(verify_with(SECRET, &token, 2_000, 30), Ok(GUEST_ID.to_string()));
+    }
+
+    #[test]
+    fn test_tampered_or_foreign_token_is_rejected() {
+        let token = issue_with(SECRET, GUEST_ID, 1_000);
+        let forged = token.replacen(GUEST_ID, "6f1c2d4e-0000-4000-8000-000000000002", 1);
+        assert!(verify_with(SECRET, &forged, 2_000, 30).is_err());
+        assert!(verify_with("other-secret", &token, 2_000, 30).is_err());
+        assert!(verify_with(SECRET, "not-a-token", 2_000, 30).is_err());
+    }
+
+    #[test]
+    fn test_expired_token_is_rejected() {
+        let token = issue_with(SECRET, GUEST_ID, 0);
+        assert!(verify_with(SECRET, &token, 31 * 24 * 60 * 60, 30).is_err());
+        assert!(verify_with(SECRET, &token, 29 * 24 * 60 * 60, 30).is_ok());
+    }
+}
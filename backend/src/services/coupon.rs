@@ -0,0 +1,241 @@
+use diesel::prelude::*;
+use diesel::mysql::MysqlConnection;
+use diesel::result::Error as DieselError;
+use std::fmt;
+
+use crate::models::coupon::{normalize_code, Coupon, DiscountType, NewCouponRedemption};
+use crate::models::product::Product;
+use crate::schema::{cart_coupons, coupons, coupon_redemptions};
+use crate::services::promotion::allocate;
+use crate::services::tax::round_currency;
+
+#[derive(Debug)]
+pub enum CouponError {
+    Database(DieselError),
+    NotFound,
+    Inactive,
+    NotStarted,
+    Expired,
+    // 总使用次数已用完
+    UsageLimitReached,
+    // 当前用户的使用次数已用完
+    UserLimitReached,
+    // 没有适用优惠券的商品
+    NotApplicable,
+    // 适用商品金额未达到最低消费，附带门槛金额
+    MinSpendNotMet(f64),
+}
+
+impl From<DieselError> for CouponError {
+    fn from(error: DieselError) -> Self {
+        CouponError::Database(error)
+    }
+}
+
+impl fmt::Display for CouponError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CouponError::Database(_) => write!(f, "读取优惠券失败"),
+            CouponError::NotFound => write!(f, "优惠券不存在"),
+            CouponError::Inactive => write!(f, "优惠券已停用"),
+            CouponError::NotStarted => write!(f, "优惠券尚未生效"),
+            CouponError::Expired => write!(f, "优惠券已过期"),
+            CouponError::UsageLimitReached => write!(f, "优惠券已被领完"),
+            CouponError::UserLimitReached => write!(f, "已达到该优惠券的使用次数上限"),
+            CouponError::NotApplicable => write!(f, "购物车中没有适用该优惠券的商品"),
+            CouponError::MinSpendNotMet(min_spend) => write!(f, "适用商品满 {:.2} 才能使用该优惠券", min_spend),
+        }
+    }
+}
+
+// 检查优惠券是否启用及是否在有效期内
+pub fn check_validity(coupon: &Coupon, now: chrono::NaiveDateTime) -> Result<(), CouponError> {
+    if !coupon.active {
+        return Err(CouponError::Inactive);
+    }
+    if coupon.starts_at.map(|starts_at| now < starts_at).unwrap_or(false) {
+        return Err(CouponError::NotStarted);
+    }
+    if coupon.ends_at.map(|ends_at| now >= ends_at).unwrap_or(false) {
+        return Err(CouponError::Expired);
+    }
+    Ok(())
+}
+
+// 计算每个订单项的优惠金额，与 lines 一一对应；lines 中的金额为扣除自动促销后还可以使用优惠券的金额。
+// 只对适用范围内的商品优惠，最低消费按这些商品的金额计算；
+// 固定金额优惠按金额比例分摊到各适用商品
+pub fn line_discounts(coupon: &Coupon, lines: &[(&Product, f64)]) -> Result<Vec<f64>, CouponError> {
+    let amounts: Vec<f64> = lines
+        .iter()
+        .map(|(product, amount)| {
+            if coupon.applies_to(&product.id, product.category.as_deref(), &product.vendor_id) {
+                amount.max(0.0)
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    let eligible_total = round_currency(amounts.iter().sum());
+    if eligible_total <= 0.0 {
+        return Err(CouponError::NotApplicable);
+    }
+    if eligible_total < coupon.min_spend {
+        return Err(CouponError::MinSpendNotMet(coupon.min_spend));
+    }
+
+    let discounts = match coupon.get_discount_type() {
+        DiscountType::Percentage => {
+            let percent = coupon.discount_value.clamp(0.0, 100.0);
+            amounts.iter().map(|amount| round_currency(amount * percent / 100.0)).collect()
+        }
+        DiscountType::Fixed => allocate(coupon.discount_value, &amounts),
+    };
+    Ok(discounts)
+}
+
+// 检查总使用次数和当前用户的使用次数
+fn check_usage(conn: &mut MysqlConnection, coupon: &Coupon, user_id: &str) -> Result<(), CouponError> {
+    if coupon.usage_limit.map(|limit| coupon.used_count >= limit).unwrap_or(false) {
+        return Err(CouponError::UsageLimitReached);
+    }
+    if let Some(limit) = coupon.per_user_limit {
+        let used = coupon_redemptions::table
+            .filter(coupon_redemptions::coupon_id.eq(&coupon.id))
+            .filter(coupon_redemptions::user_id.eq(user_id))
+            .count()
+            .get_result::<i64>(conn)?;
+        if used >= limit as i64 {
+            return Err(CouponError::UserLimitReached);
+        }
+    }
+    Ok(())
+}
+
+// 按优惠码查找优惠券
+pub fn find_by_code(conn: &mut MysqlConnection, code: &str) -> Result<Coupon, CouponError> {
+    coupons::table
+        .filter(coupons::code.eq(normalize_code(code)))
+        .select(Coupon::as_select())
+        .first(conn)
+        .optional()?
+        .ok_or(CouponError::NotFound)
+}
+
+// 检查当前用户能否对这些商品使用优惠券，并计算每个订单项的优惠金额
+pub fn cart_discounts(
+    conn: &mut MysqlConnection,
+    coupon: &Coupon,
+    user_id: &str,
+    lines: &[(&Product, f64)],
+) -> Result<Vec<f64>, CouponError> {
+    check_validity(coupon, chrono::Utc::now().naive_utc())?;
+    check_usage(conn, coupon, user_id)?;
+    line_discounts(coupon, lines)
+}
+
+// 购物车中已使用的优惠券
+pub fn cart_coupon(conn: &mut MysqlConnection, user_id: &str) -> QueryResult<Option<Coupon>> {
+    cart_coupons::table
+        .inner_join(coupons::table)
+        .filter(cart_coupons::user_id.eq(user_id))
+        .select(Coupon::as_select())
+        .first(conn)
+        .optional()
+}
+
+// 下单时登记优惠券使用，必须在事务中调用；
+// 优惠券行加锁后再检查使用次数，并发下单不会超过使用次数上限
+pub fn redeem(
+    conn: &mut MysqlConnection,
+    coupon_id: &str,
+    user_id: &str,
+    order_id: &str,
+    discount: f64,
+) -> Result<(), CouponError> {
+    let coupon = coupons::table
+        .find(coupon_id)
+        .select(Coupon::as_select())
+        .for_update()
+        .first(conn)
+        .optional()?
+        .ok_or(CouponError::NotFound)?;
+    check_validity(&coupon, chrono::Utc::now().naive_utc())?;
+    check_usage(conn, &coupon, user_id)?;
+
+    diesel::update(coupons::table.find(coupon_id))
+        .set(coupons::used_count.eq(coupons::used_count + 1))
+        .execute(conn)?;
+    diesel::insert_into(coupon_redemptions::table)
+        .values(&NewCouponRedemption::new(coupon_id.to_string(), user_id.to_string(), order_id.to_string(), discount))
+        .execute(conn)?;
+    Ok(())
+}
+
+// 订单取消时退回优惠券使用次数，必须在事务中调用
+pub fn release_order_coupons(conn: &mut MysqlConnection, order_ids: &[String]) -> QueryResult<()> {
+    let coupon_ids: Vec<String> = coupon_redemptions::table
+        .filter(coupon_redemptions::order_id.eq_any(order_ids))
+        .select(coupon_redemptions::coupon_id)
+        .load(conn)?;
+
+    for coupon_id in coupon_ids {
+        diesel::update(coupons::table.find(&coupon_id).filter(coupons::used_count.gt(0)))
+            .set(coupons::used_count.eq(coupons::used_count - 1))
+            .execute(conn)?;
+    }
+    diesel::delete(coupon_redemptions::table.filter(coupon_redemptions::order_id.eq_any(order_ids)))
+        .execute(conn)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{coupon, product};
+
+    #[test]
+    fn test_percentage_discount_only_applies_to_scope() {
+        let book = Product { category: Some("Books".to_string()), ..product("p1", 30.0) };
+        let phone = Product { category: Some("Electronics".to_string()), ..product("p2", 100.0) };
+        let mut coupon = coupon(DiscountType::Percentage, 10.0);
+        coupon.categories = Some("books".to_string());
+
+        let discounts = line_discounts(&coupon, &[(&book, 60.0), (&phone, 100.0)]).unwrap();
+        assert_eq!(discounts, vec![6.0, 0.0]);
+
+        assert!(matches!(line_discounts(&coupon, &[(&phone, 100.0)]), Err(CouponError::NotApplicable)));
+    }
+
+    #[test]
+    fn test_fixed_discount_is_allocated_by_amount() {
+        let a = product("p1", 10.0);
+        let b = product("p2", 20.0);
+        let discounts = line_discounts(&coupon(DiscountType::Fixed, 10.0), &[(&a, 10.0), (&b, 20.0)]).unwrap();
+        assert_eq!(discounts, vec![3.33, 6.67]);
+
+        // 优惠不超过商品金额
+        let discounts = line_discounts(&coupon(DiscountType::Fixed, 50.0), &[(&a, 10.0), (&b, 20.0)]).unwrap();
+        assert_eq!(discounts, vec![10.0, 20.0]);
+    }
+
+    #[test]
+    fn test_min_spend_and_validity() {
+        let a = product("p1", 10.0);
+        let mut coupon = coupon(DiscountType::Fixed, 5.0);
+        coupon.min_spend = 50.0;
+        assert!(matches!(line_discounts(&coupon, &[(&a, 40.0)]), Err(CouponError::MinSpendNotMet(_))));
+        assert_eq!(line_discounts(&coupon, &[(&a, 50.0)]).unwrap(), vec![5.0]);
+
+        let now = chrono::Utc::now().naive_utc();
+        assert!(check_validity(&coupon, now).is_ok());
+        coupon.ends_at = Some(now - chrono::Duration::hours(1));
+        assert!(matches!(check_validity(&coupon, now), Err(CouponError::Expired)));
+        coupon.ends_at = None;
+        coupon.starts_at = Some(now + chrono::Duration::hours(1));
+        assert!(matches!(check_validity(&coupon, now), Err(CouponError::NotStarted)));
+        coupon.active = false;
+        assert!(matches!(check_validity(&coupon, now), Err(CouponError::Inactive)));
+    }
+}
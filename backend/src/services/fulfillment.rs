@@ -0,0 +1,210 @@
+use diesel::prelude::*;
+use diesel::mysql::MysqlConnection;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::models::order::OrderStatus;
+use crate::models::order_history::NewOrderStatusHistory;
+use crate::models::vendor_profile::{return_window_days, DEFAULT_RETURN_WINDOW_DAYS};
+use crate::schema::{orders, order_items, order_fulfillments, order_status_history, vendor_profiles};
+use crate::services::coupon::release_order_coupons;
+use crate::services::inventory::restore_stock;
+use crate::services::wallet::refund_order_wallet_amounts;
+
+// 修改商家子订单状态，并记录对应的发货、送达或完成时间；
+// 送达时按各商家的退货政策计算退货期截止时间
+pub fn set_fulfillment_status(conn: &mut MysqlConnection, fulfillment_ids: &[String], status: &OrderStatus) -> QueryResult<usize> {
+    let now = chrono::Utc::now().naive_utc();
+    let target = order_fulfillments::table.filter(order_fulfillments::id.eq_any(fulfillment_ids));
+
+    match status {
+        OrderStatus::Shipped => diesel::update(target)
+            .set((
+                order_fulfillments::status.eq(status.to_string()),
+                order_fulfillments::shipped_at.eq(now),
+                order_fulfillments::updated_at.eq(now),
+            ))
+            .execute(conn),
+        OrderStatus::Delivered => {
+            let fulfillments: Vec<(String, String)> = target
+                .select((order_fulfillments::id, order_fulfillments::vendor_id))
+                .load(conn)?;
+            let vendor_ids: Vec<String> = fulfillments.iter().map(|(_, vendor_id)| vendor_id.clone()).collect();
+            let windows = load_return_windows(conn, &vendor_ids)?;
+
+            let mut updated = 0;
+            for (fulfillment_id, vendor_id) in fulfillments {
+                let days = windows.get(&vendor_id).copied().unwrap_or(DEFAULT_RETURN_WINDOW_DAYS);
+                updated += diesel::update(order_fulfillments::table.find(&fulfillment_id))
+                    .set((
+                        order_fulfillments::status.eq(status.to_string()),
+                        order_fulfillments::delivered_at.eq(now),
+                        order_fulfillments::return_window_ends_at.eq(now + chrono::Duration::days(days)),
+                        order_fulfillments::updated_at.eq(now),
+                    ))
+                    .execute(conn)?;
+            }
+            Ok(updated)
+        }
+        OrderStatus::Completed => diesel::update(target)
+            .set((
+                order_fulfillments::status.eq(status.to_string()),
+                order_fulfillments::completed_at.eq(now),
+                order_fulfillments::updated_at.eq(now),
+            ))
+            .execute(conn),
+        _ => diesel::update(target)
+            .set((
+                order_fulfillments::status.eq(status.to_string()),
+                order_fulfillments::updated_at.eq(now),
+            ))
+            .execute(conn),
+    }
+}
+
+// 读取商家的退货期天数，按商家ID索引；没有店铺资料的商家不在结果中
+fn load_return_windows(conn: &mut MysqlConnection, vendor_ids: &[String]) -> QueryResult<HashMap<String, i64>> {
+    let profiles: Vec<(String, bool, Option<String>)> = vendor_profiles::table
+        .filter(vendor_profiles::vendor_id.eq_any(vendor_ids))
+        .select((vendor_profiles::vendor_id, vendor_profiles::accepts_returns, vendor_profiles::return_policy))
+        .load(conn)?;
+
+    Ok(profiles
+        .into_iter()
+        .map(|(vendor_id, accepts_returns, return_policy)| {
+            let days = return_window_days(accepts_returns, return_policy.as_deref());
+            (vendor_id, days)
+        })
+        .collect())
+}
+
+// 根据商家子订单状态重新计算父订单状态并写回，返回推导出的状态；
+// 父订单状态发生变化时写入状态变更记录，changed_by 为空表示系统自动变更；
+// 订单没有子订单（拆单前的历史数据）时不做修改
+pub fn sync_order_status(
+    conn: &mut MysqlConnection,
+    order_id: &str,
+    reason: Option<&str>,
+    changed_by: Option<&str>,
+) -> QueryResult<Option<OrderStatus>> {
+    let statuses: Vec<OrderStatus> = order_fulfillments::table
+        .filter(order_fulfillments::order_id.eq(order_id))
+        .select(order_fulfillments::status)
+        .load::<String>(conn)?
+        .iter()
+        .map(|status| OrderStatus::from_str(status).unwrap_or(OrderStatus::Pending))
+        .collect();
+
+    if statuses.is_empty() {
+        return Ok(None);
+    }
+
+    let derived = OrderStatus::from_fulfillments(&statuses);
+    let current = orders::table
+        .find(order_id)
+        .select(orders::status)
+        .first::<String>(conn)?;
+
+    if current.to_lowercase() != derived.to_string() {
+        diesel::update(orders::table.find(order_id))
+            .set((
+                orders::status.eq(derived.to_string()),
+                orders::updated_at.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .execute(conn)?;
+
+        diesel::insert_into(order_status_history::table)
+            .values(&NewOrderStatusHistory::new(
+                order_id.to_string(),
+                Some(current),
+                derived.to_string(),
+                reason.map(str::to_string),
+                changed_by.map(str::to_string),
+            ))
+            .execute(conn)?;
+    }
+
+    Ok(Some(derived))
+}
+
+// 订单已付清：将待处理的商家子订单改为处理中并同步父订单状态，必须在事务中调用
+pub fn mark_order_paid(conn: &mut MysqlConnection, order_id: &str, reason: &str) -> QueryResult<Option<OrderStatus>> {
+    let fulfillment_ids: Vec<String> = order_fulfillments::table
+        .filter(order_fulfillments::order_id.eq(order_id))
+        .filter(order_fulfillments::status.eq(OrderStatus::Pending.to_string()))
+        .select(order_fulfillments::id)
+        .load(conn)?;
+    set_fulfillment_status(conn, &fulfillment_ids, &OrderStatus::Processing)?;
+    sync_order_status(conn, order_id, Some(reason), None)
+}
+
+// 取消尚未支付的订单：归还库存、退回抵扣的钱包余额、取消商家子订单、写入状态记录，必须在事务中调用，
+// 调用方负责锁定订单并确认订单仍为待支付状态
+pub fn cancel_pending_orders(
+    conn: &mut MysqlConnection,
+    order_ids: &[String],
+    reason: &str,
+    changed_by: Option<&str>,
+) -> QueryResult<()> {
+    cancel_orders(conn, order_ids, &OrderStatus::Pending, reason, changed_by)
+}
+
+// 取消处于 from 状态的订单，处理同 cancel_pending_orders；必须在事务中调用，
+// 调用方负责锁定订单并确认订单仍为 from 状态，已支付的订单需先退回渠道支付的款项
+pub fn cancel_orders(
+    conn: &mut MysqlConnection,
+    order_ids: &[String],
+    from: &OrderStatus,
+    reason: &str,
+    changed_by: Option<&str>,
+) -> QueryResult<()> {
+    let previous = from.to_string();
+    let cancelled = OrderStatus::Cancelled.to_string();
+
+    // 只归还已从库存扣减的部分，等待到货的数量随订单取消一并撤销
+    let items: Vec<(String, i32)> = order_items::table
+        .filter(order_items::order_id.eq_any(order_ids))
+        .select((order_items::product_id, order_items::quantity - order_items::backordered_quantity))
+        .load(conn)?;
+    diesel::update(order_items::table
+        .filter(order_items::order_id.eq_any(order_ids))
+        .filter(order_items::backordered_quantity.gt(0)))
+        .set(order_items::backordered_quantity.eq(0))
+        .execute(conn)?;
+    restore_stock(conn, &items)?;
+    refund_order_wallet_amounts(conn, order_ids, reason)?;
+    release_order_coupons(conn, order_ids)?;
+
+    let now = chrono::Utc::now().naive_utc();
+    diesel::update(order_fulfillments::table
+        .filter(order_fulfillments::order_id.eq_any(order_ids))
+        .filter(order_fulfillments::status.ne(&cancelled)))
+        .set((
+            order_fulfillments::status.eq(&cancelled),
+            order_fulfillments::updated_at.eq(now),
+        ))
+        .execute(conn)?;
+
+    diesel::update(orders::table.filter(orders::id.eq_any(order_ids)))
+        .set((
+            orders::status.eq(&cancelled),
+            orders::on_hold.eq(false),
+            orders::updated_at.eq(now),
+        ))
+        .execute(conn)?;
+
+    let history: Vec<NewOrderStatusHistory> = order_ids.iter()
+        .map(|order_id| NewOrderStatusHistory::new(
+            order_id.clone(),
+            Some(previous.clone()),
+            cancelled.clone(),
+            Some(reason.to_string()),
+            changed_by.map(str::to_string),
+        ))
+        .collect();
+    diesel::insert_into(order_status_history::table)
+        .values(&history)
+        .execute(conn)?;
+
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,169 @@
+use diesel::prelude::*;
+use diesel::mysql::MysqlConnection;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use sha2::{Digest, Sha256};
+
+use crate::models::idempotency::{IdempotencyKey, NewIdempotencyKey};
+use crate::schema::idempotency_keys;
+
+// 客户端传入幂等键的请求头
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+// 重放已保存响应时附加的响应头
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+// 幂等键最大长度
+const MAX_KEY_LENGTH: usize = 255;
+// 处理中的请求超过该时长仍未完成（进程崩溃等），允许相同请求重新接管
+const STALE_AFTER_SECONDS: i64 = 60;
+
+// 开始处理带幂等键的请求时的结果
+#[derive(Debug, PartialEq)]
+pub enum IdempotencyState {
+    // 首次请求，已登记，返回记录ID
+    Started(String),
+    // 之前的请求已完成，返回保存的响应
+    Completed { status: u16, body: String },
+    // 之前的请求已创建订单但响应未保存（例如保存前进程退出）
+    OrderCreated(String),
+    // 相同幂等键的请求正在处理中
+    InProgress,
+    // 幂等键已用于内容不同的请求
+    Mismatch,
+}
+
+// 校验幂等键格式：非空、不超过255个字符、只包含可见ASCII字符
+pub fn validate_key(key: &str) -> Result<(), String> {
+    if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+        return Err(format!("{} 长度必须在1到{}个字符之间", IDEMPOTENCY_KEY_HEADER, MAX_KEY_LENGTH));
+    }
+    if !key.chars().all(|c| c.is_ascii_graphic()) {
+        return Err(format!("{} 只能包含可见ASCII字符", IDEMPOTENCY_KEY_HEADER));
+    }
+    Ok(())
+}
+
+// 请求指纹：接口与请求体的 SHA-256，用于识别同一幂等键被用于不同请求
+pub fn request_fingerprint(endpoint: &str, body: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(endpoint.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+// 登记请求；幂等键已存在时根据已有记录决定重放、拒绝或接管
+// 依赖 (user_id, endpoint, idempotency_key) 唯一索引，并发的相同请求只有一个能登记成功
+pub fn begin_request(
+    conn: &mut MysqlConnection,
+    user_id: &str,
+    endpoint: &str,
+    key: &str,
+    fingerprint: &str,
+) -> QueryResult<IdempotencyState> {
+    let new_record = NewIdempotencyKey::new(
+        user_id.to_string(),
+        endpoint.to_string(),
+        key.to_string(),
+        fingerprint.to_string(),
+    );
+
+    match diesel::insert_into(idempotency_keys::table)
+        .values(&new_record)
+        .execute(conn) {
+        Ok(_) => return Ok(IdempotencyState::Started(new_record.id)),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {}
+        Err(e) => return Err(e),
+    }
+
+    let existing = idempotency_keys::table
+        .filter(idempotency_keys::user_id.eq(user_id))
+        .filter(idempotency_keys::endpoint.eq(endpoint))
+        .filter(idempotency_keys::idempotency_key.eq(key))
+        .select(IdempotencyKey::as_select())
+        .first(conn)?;
+
+    if existing.request_fingerprint != fingerprint {
+        return Ok(IdempotencyState::Mismatch);
+    }
+
+    if let (Some(status), Some(body)) = (existing.response_status, existing.response_body) {
+        return Ok(IdempotencyState::Completed { status: status as u16, body });
+    }
+
+    if let Some(order_id) = existing.order_id {
+        return Ok(IdempotencyState::OrderCreated(order_id));
+    }
+
+    // 处理超时的记录允许接管，条件更新保证只有一个请求接管成功
+    let now = chrono::Utc::now().naive_utc();
+    let taken_over = diesel::update(
+        idempotency_keys::table
+            .find(&existing.id)
+            .filter(idempotency_keys::order_id.is_null())
+            .filter(idempotency_keys::response_status.is_null())
+            .filter(idempotency_keys::updated_at.lt(now - chrono::Duration::seconds(STALE_AFTER_SECONDS))),
+    )
+    .set(idempotency_keys::updated_at.eq(now))
+    .execute(conn)?;
+
+    if taken_over == 1 {
+        Ok(IdempotencyState::Started(existing.id))
+    } else {
+        Ok(IdempotencyState::InProgress)
+    }
+}
+
+// 在下单事务中关联创建的订单，订单提交即与幂等键绑定
+pub fn attach_order(conn: &mut MysqlConnection, record_id: &str, order_id: &str) -> QueryResult<()> {
+    diesel::update(idempotency_keys::table.find(record_id))
+        .set((
+            idempotency_keys::order_id.eq(order_id),
+            idempotency_keys::updated_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+// 保存请求成功后的响应，供重放使用
+pub fn store_response(conn: &mut MysqlConnection, record_id: &str, status: u16, body: &str) -> QueryResult<()> {
+    diesel::update(idempotency_keys::table.find(record_id))
+        .set((
+            idempotency_keys::response_status.eq(Some(status as i32)),
+            idempotency_keys::response_body.eq(Some(body)),
+            idempotency_keys::updated_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+// 请求失败且未创建订单时释放幂等键，客户端可用同一键重试
+pub fn release(conn: &mut MysqlConnection, record_id: &str) -> QueryResult<()> {
+    diesel::delete(
+        idempotency_keys::table
+            .find(record_id)
+            .filter(idempotency_keys::order_id.is_null()),
+    )
+    .execute(conn)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_fingerprint() {
+        let fingerprint = request_fingerprint("POST /api/cart/checkout", "{\"address_id\":\"a1\"}");
+        assert_eq!(fingerprint.len(), 64);
+        assert_eq!(fingerprint, request_fingerprint("POST /api/cart/checkout", "{\"address_id\":\"a1\"}"));
+        assert_ne!(fingerprint, request_fingerprint("POST /api/cart/checkout", "{\"address_id\":\"a2\"}"));
+        assert_ne!(fingerprint, request_fingerprint("POST /api/orders", "{\"address_id\":\"a1\"}"));
+    }
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key("3f6c2a4e-checkout-1").is_ok());
+        assert!(validate_key("").is_err());
+        assert!(validate_key("has space").is_err());
+        assert!(validate_key(&"k".repeat(256)).is_err());
+    }
+}
@@ -0,0 +1,277 @@
+use diesel::prelude::*;
+use diesel::mysql::MysqlConnection;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::models::order::OrderStatus;
+use crate::models::product::BackorderMode;
+use crate::schema::{order_items, orders, products};
+
+// 扣减库存失败的原因
+#[derive(Debug)]
+pub enum StockError {
+    Database(diesel::result::Error),
+    // 商品库存（及预订额度）不足或已下架
+    OutOfStock {
+        product_id: String,
+        product_name: Option<String>,
+        requested: i32,
+        available: i32,
+    },
+}
+
+impl From<diesel::result::Error> for StockError {
+    fn from(error: diesel::result::Error) -> Self {
+        StockError::Database(error)
+    }
+}
+
+impl StockError {
+    // 库存不足时的提示，指明具体商品
+    pub fn out_of_stock_message(&self) -> Option<String> {
+        match self {
+            StockError::OutOfStock { product_name: Some(name), available, .. } => {
+                Some(format!("产品「{}」库存不足，当前可购买 {}", name, available))
+            }
+            StockError::OutOfStock { product_id, .. } => Some(format!("产品 {} 不存在", product_id)),
+            StockError::Database(_) => None,
+        }
+    }
+
+    // 与结账前库存检查相同格式的不可用商品明细
+    pub fn unavailable_product(&self) -> Option<Value> {
+        match self {
+            StockError::OutOfStock { product_id, product_name: Some(name), requested, available } => Some(json!({
+                "product_id": product_id,
+                "product_name": name,
+                "requested_quantity": requested,
+                "available_stock": available
+            })),
+            StockError::OutOfStock { product_id, .. } => Some(json!({
+                "product_id": product_id,
+                "message": "产品不存在"
+            })),
+            StockError::Database(_) => None,
+        }
+    }
+}
+
+// 订单项中等待到货的数量
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backorder {
+    pub quantity: i32,
+    pub expected_ship_date: Option<chrono::NaiveDate>,
+}
+
+// 计算一行商品从现有库存扣减的数量和超出库存需要预订的数量；
+// open_backorders 为该商品尚未到货的预订总数。数量不足时返回当前最多可购买的数量
+fn split_quantity(
+    stock: i32,
+    quantity: i32,
+    mode: BackorderMode,
+    backorder_limit: Option<i32>,
+    open_backorders: i32,
+) -> Result<(i32, i32), i32> {
+    let stock = stock.max(0);
+    if quantity <= stock {
+        return Ok((quantity, 0));
+    }
+    if mode == BackorderMode::None {
+        return Err(stock);
+    }
+
+    let backordered = quantity - stock;
+    match backorder_limit {
+        Some(limit) if open_backorders + backordered > limit => Err(stock + (limit - open_backorders).max(0)),
+        _ => Ok((stock, backordered)),
+    }
+}
+
+// 商品尚未到货的预订总数（不含已取消订单）
+fn open_backorder_quantity(conn: &mut MysqlConnection, product_id: &str) -> QueryResult<i32> {
+    let quantities = order_items::table
+        .inner_join(orders::table)
+        .filter(order_items::product_id.eq(product_id))
+        .filter(order_items::backordered_quantity.gt(0))
+        .filter(orders::status.ne(OrderStatus::Cancelled.to_string()))
+        .select(order_items::backordered_quantity)
+        .load::<i32>(conn)?;
+    Ok(quantities.into_iter().sum())
+}
+
+// 扣减库存，必须在下单事务中调用，返回各商品超出库存、需要等待到货的数量
+// 先用 SELECT ... FOR UPDATE 锁定商品行再检查和扣减，并发结账时后到的请求会看到扣减后的库存；
+// 商品允许缺货预订或预售时，库存不足的部分记为预订，受预订数量上限约束；
+// 按商品ID顺序加行锁，避免多个购物车交叉包含相同商品时死锁
+pub fn reserve_stock(conn: &mut MysqlConnection, items: &[(String, i32)]) -> Result<BTreeMap<String, Backorder>, StockError> {
+    let mut ordered: Vec<&(String, i32)> = items.iter().collect();
+    ordered.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut backorders = BTreeMap::new();
+    for (product_id, quantity) in ordered {
+        let product = products::table
+            .find(product_id)
+            .select((
+                products::name,
+                products::stock,
+                products::backorder_mode,
+                products::backorder_limit,
+                products::expected_ship_date,
+            ))
+            .for_update()
+            .first::<(String, i32, String, Option<i32>, Option<chrono::NaiveDate>)>(conn)
+            .optional()?;
+
+        let (name, stock, mode, backorder_limit, expected_ship_date) = match product {
+            Some(product) => product,
+            None => return Err(StockError::OutOfStock {
+                product_id: product_id.clone(),
+                product_name: None,
+                requested: *quantity,
+                available: 0,
+            }),
+        };
+        let mode = BackorderMode::from_str(&mode).unwrap_or(BackorderMode::None);
+
+        // 只有超出库存时才需要统计已有预订
+        let open_backorders = if *quantity > stock && backorder_limit.is_some() {
+            open_backorder_quantity(conn, product_id)?
+        } else {
+            0
+        };
+
+        let (from_stock, backordered) = split_quantity(stock, *quantity, mode, backorder_limit, open_backorders)
+            .map_err(|available| StockError::OutOfStock {
+                product_id: product_id.clone(),
+                product_name: Some(name),
+                requested: *quantity,
+                available,
+            })?;
+
+        if from_stock > 0 {
+            diesel::update(products::table.find(product_id))
+                .set((
+                    products::stock.eq(products::stock - from_stock),
+                    products::updated_at.eq(chrono::Utc::now().naive_utc()),
+                ))
+                .execute(conn)?;
+        }
+        if backordered > 0 {
+            backorders.insert(product_id.clone(), Backorder { quantity: backordered, expected_ship_date });
+        }
+    }
+
+    Ok(backorders)
+}
+
+// 归还库存（订单取消时调用），同一商品的数量先合并，再按商品ID顺序更新；
+// 商品已被删除时跳过。归还的库存优先分配给等待到货的订单项
+pub fn restore_stock(conn: &mut MysqlConnection, items: &[(String, i32)]) -> QueryResult<()> {
+    let mut totals: BTreeMap<&str, i32> = BTreeMap::new();
+    for (product_id, quantity) in items {
+        if *quantity > 0 {
+            *totals.entry(product_id.as_str()).or_insert(0) += *quantity;
+        }
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    for (product_id, quantity) in totals {
+        diesel::update(products::table.find(product_id))
+            .set((
+                products::stock.eq(products::stock + quantity),
+                products::updated_at.eq(now),
+            ))
+            .execute(conn)?;
+        allocate_backorders(conn, product_id)?;
+    }
+
+    Ok(())
+}
+
+// 按顺序把可用库存分配给等待到货的订单项，返回每项分配到的数量
+fn allocate_fifo(stock: i32, waiting: &[i32]) -> Vec<i32> {
+    let mut left = stock.max(0);
+    waiting
+        .iter()
+        .map(|quantity| {
+            let allocated = (*quantity).min(left);
+            left -= allocated;
+            allocated
+        })
+        .collect()
+}
+
+// 把商品的现有库存按下单先后分配给等待到货的订单项，库存增加后调用，必须在事务中调用；
+// 分配到的数量从库存中扣除，返回本次分配的总数
+pub fn allocate_backorders(conn: &mut MysqlConnection, product_id: &str) -> QueryResult<i32> {
+    let stock = match products::table
+        .find(product_id)
+        .select(products::stock)
+        .for_update()
+        .first::<i32>(conn)
+        .optional()? {
+        Some(stock) if stock > 0 => stock,
+        _ => return Ok(0),
+    };
+
+    let waiting = order_items::table
+        .inner_join(orders::table)
+        .filter(order_items::product_id.eq(product_id))
+        .filter(order_items::backordered_quantity.gt(0))
+        .filter(orders::status.ne(OrderStatus::Cancelled.to_string()))
+        .order((orders::created_at.asc(), order_items::id.asc()))
+        .select((order_items::id, order_items::backordered_quantity))
+        .for_update()
+        .load::<(String, i32)>(conn)?;
+    if waiting.is_empty() {
+        return Ok(0);
+    }
+
+    let quantities: Vec<i32> = waiting.iter().map(|(_, quantity)| *quantity).collect();
+    let mut total = 0;
+    for ((order_item_id, _), allocated) in waiting.iter().zip(allocate_fifo(stock, &quantities)) {
+        if allocated == 0 {
+            break;
+        }
+        diesel::update(order_items::table.find(order_item_id))
+            .set(order_items::backordered_quantity.eq(order_items::backordered_quantity - allocated))
+            .execute(conn)?;
+        total += allocated;
+    }
+
+    diesel::update(products::table.find(product_id))
+        .set((
+            products::stock.eq(products::stock - total),
+            products::updated_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .execute(conn)?;
+    println!("商品 {} 到货 {} 件已分配给等待中的订单", product_id, total);
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_quantity() {
+        // 库存充足时全部从库存扣减
+        assert_eq!(split_quantity(5, 3, BackorderMode::None, None, 0), Ok((3, 0)));
+        assert_eq!(split_quantity(2, 3, BackorderMode::None, None, 0), Err(2));
+        // 超出库存的部分记为预订
+        assert_eq!(split_quantity(2, 5, BackorderMode::Backorder, None, 0), Ok((2, 3)));
+        assert_eq!(split_quantity(0, 4, BackorderMode::Preorder, Some(10), 6), Ok((0, 4)));
+        // 超过预订上限时返回库存加剩余预订额度
+        assert_eq!(split_quantity(1, 5, BackorderMode::Backorder, Some(10), 8), Err(3));
+        assert_eq!(split_quantity(-1, 1, BackorderMode::Backorder, Some(2), 5), Err(0));
+    }
+
+    #[test]
+    fn test_allocate_fifo() {
+        assert_eq!(allocate_fifo(5, &[2, 2, 3]), vec![2, 2, 1]);
+        assert_eq!(allocate_fifo(10, &[3, 1]), vec![3, 1]);
+        assert_eq!(allocate_fifo(0, &[1]), vec![0]);
+    }
+}
@@ -0,0 +1,604 @@
+use chrono::Datelike;
+use diesel::prelude::*;
+use diesel::mysql::MysqlConnection;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use diesel::sql_types::{BigInt, Integer};
+use printpdf::{IndirectFontRef, Mm, PdfDocument, PdfLayerReference};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::sync::OnceLock;
+
+use crate::models::admin_profile::AdminProfile;
+use crate::models::invoice::{Invoice, InvoiceDocument, InvoiceLine, InvoiceSeller, NewInvoice};
+use crate::models::order::{Order, OrderAddress, OrderItem};
+use crate::models::vendor_profile::VendorProfile;
+use crate::schema::{admin_profiles, invoices, order_addresses, order_items, products, vendor_profiles};
+use crate::services::tax::round_currency;
+
+#[derive(QueryableByName)]
+struct SequenceValue {
+    #[diesel(sql_type = BigInt)]
+    value: i64,
+}
+
+// 生成发票号，例如 INV-2026-000001
+pub fn format_invoice_number(year: i32, sequence: i64) -> String {
+    format!("INV-{}-{:06}", year, sequence)
+}
+
+// 获取订单的发票，尚未开具时分配发票号并创建
+// 序号递增与发票插入在同一事务中，失败时一起回滚，保证发票号连续无空号
+pub fn issue_invoice(conn: &mut MysqlConnection, order_id: &str) -> QueryResult<Invoice> {
+    let existing = invoices::table
+        .filter(invoices::order_id.eq(order_id))
+        .select(Invoice::as_select())
+        .first(conn)
+        .optional()?;
+    if let Some(invoice) = existing {
+        return Ok(invoice);
+    }
+
+    let year = chrono::Utc::now().year();
+    let result = conn.transaction::<_, DieselError, _>(|conn| {
+        diesel::sql_query(
+            "INSERT INTO invoice_number_sequences (sequence_year, last_value) VALUES (?, LAST_INSERT_ID(1)) \
+             ON DUPLICATE KEY UPDATE last_value = LAST_INSERT_ID(last_value + 1)",
+        )
+        .bind::<Integer, _>(year)
+        .execute(conn)?;
+
+        let sequence = diesel::sql_query("SELECT CAST(LAST_INSERT_ID() AS SIGNED) AS value")
+            .get_result::<SequenceValue>(conn)?;
+
+        let new_invoice = NewInvoice::new(order_id.to_string(), format_invoice_number(year, sequence.value));
+        diesel::insert_into(invoices::table)
+            .values(&new_invoice)
+            .execute(conn)?;
+
+        invoices::table
+            .find(&new_invoice.id)
+            .select(Invoice::as_select())
+            .first(conn)
+    });
+
+    match result {
+        // 并发请求已为该订单开具发票，直接返回已有发票
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => invoices::table
+            .filter(invoices::order_id.eq(order_id))
+            .select(Invoice::as_select())
+            .first(conn),
+        other => other,
+    }
+}
+
+// 汇总订单、商品、卖方和站点设置，生成发票数据
+pub fn build_invoice_document(conn: &mut MysqlConnection, order: &Order, invoice: &Invoice) -> QueryResult<InvoiceDocument> {
+    let settings = admin_profiles::table
+        .order(admin_profiles::created_at.asc())
+        .select(AdminProfile::as_select())
+        .first(conn)
+        .optional()?;
+
+    let (site_name, currency_symbol) = match settings {
+        Some(settings) => (settings.site_name, settings.currency_symbol),
+        None => ("Online Shopping System".to_string(), "¥".to_string()),
+    };
+
+    let items: Vec<(OrderItem, String, String)> = order_items::table
+        .inner_join(products::table)
+        .filter(order_items::order_id.eq(&order.id))
+        .select((OrderItem::as_select(), products::name, products::vendor_id))
+        .load(conn)?;
+
+    let vendor_ids: Vec<String> = items.iter().map(|(_, _, vendor_id)| vendor_id.clone()).collect();
+    let profiles: HashMap<String, VendorProfile> = vendor_profiles::table
+        .filter(vendor_profiles::vendor_id.eq_any(&vendor_ids))
+        .select(VendorProfile::as_select())
+        .load(conn)?
+        .into_iter()
+        .map(|profile| (profile.vendor_id.clone(), profile))
+        .collect();
+
+    let mut sellers: Vec<InvoiceSeller> = Vec::new();
+    let mut lines = Vec::new();
+    for (item, product_name, vendor_id) in items {
+        let seller = match sellers.iter().find(|seller| seller.vendor_id == vendor_id) {
+            Some(seller) => seller.clone(),
+            None => {
+                let profile = profiles.get(&vendor_id);
+                let seller = InvoiceSeller {
+                    vendor_id: vendor_id.clone(),
+                    name: profile
+                        .and_then(|profile| profile.store_name.clone())
+                        .unwrap_or_else(|| vendor_id.clone()),
+                    address: profile.and_then(|profile| profile.store_address.clone()),
+                    email: profile.and_then(|profile| profile.contact_email.clone()),
+                };
+                sellers.push(seller.clone());
+                seller
+            }
+        };
+
+        lines.push(InvoiceLine {
+            product_id: item.product_id,
+            description: product_name,
+            seller: seller.name,
+            quantity: item.quantity,
+            unit_price: item.price,
+            discount: item.discount,
+            tax_rate: item.tax_rate,
+            tax_amount: item.tax_amount,
+            amount: item.total,
+        });
+    }
+
+    let address = order_addresses::table
+        .filter(order_addresses::order_id.eq(&order.id))
+        .select(OrderAddress::as_select())
+        .first(conn)
+        .optional()?;
+
+    let (bill_to_name, bill_to_phone, bill_to_address) = match address {
+        Some(address) => {
+            let full_address = address.full_address();
+            (address.recipient_name, address.phone, full_address)
+        }
+        None => (String::new(), String::new(), String::new()),
+    };
+
+    let list_total = round_currency(lines.iter().map(|line| line.unit_price * line.quantity as f64).sum());
+
+    Ok(InvoiceDocument {
+        invoice_number: invoice.invoice_number.clone(),
+        issued_at: invoice.issued_at,
+        site_name,
+        currency_symbol,
+        order_id: order.id.clone(),
+        order_number: order.order_number.clone(),
+        order_date: order.created_at,
+        bill_to_name,
+        bill_to_phone,
+        bill_to_address,
+        sellers,
+        lines,
+        list_total,
+        discount: order.discount,
+        coupon_code: order.coupon_code.clone(),
+        subtotal: order.subtotal,
+        tax: order.tax,
+        total: order.total,
+    })
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn money(symbol: &str, amount: f64) -> String {
+    format!("{}{:.2}", symbol, amount)
+}
+
+// 渲染HTML发票
+pub fn render_html(document: &InvoiceDocument) -> String {
+    let currency = escape_html(&document.currency_symbol);
+
+    let sellers = document.sellers
+        .iter()
+        .map(|seller| {
+            let mut details = vec![escape_html(&seller.name)];
+            if let Some(address) = &seller.address {
+                details.push(escape_html(address));
+            }
+            if let Some(email) = &seller.email {
+                details.push(escape_html(email));
+            }
+            format!("<p>{}</p>", details.join("<br>"))
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    // 优惠包含自动促销和优惠券，使用了优惠券时注明优惠码
+    let discount_label = match &document.coupon_code {
+        Some(code) => format!("优惠（优惠券 {}）", escape_html(code)),
+        None => "优惠".to_string(),
+    };
+
+    let rows = document.lines
+        .iter()
+        .map(|line| {
+            format!(
+                "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">-{}</td><td class=\"num\">{}%</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+                escape_html(&line.description),
+                escape_html(&line.seller),
+                line.quantity,
+                money(&currency, line.unit_price),
+                money(&currency, line.discount),
+                line.tax_rate,
+                money(&currency, line.tax_amount),
+                money(&currency, line.amount),
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>发票 {invoice_number}</title>
+<style>
+body {{ font-family: sans-serif; margin: 40px; color: #222; }}
+table {{ width: 100%; border-collapse: collapse; margin-top: 24px; }}
+th, td {{ border-bottom: 1px solid #ddd; padding: 8px; text-align: left; }}
+.num {{ text-align: right; }}
+.totals td {{ border: none; }}
+</style>
+</head>
+<body>
+<h1>{site_name}</h1>
+<h2>发票 / Invoice {invoice_number}</h2>
+<p>开票日期: {issued_at}<br>订单号: {order_number}<br>下单时间: {order_date}</p>
+<h3>购买方</h3>
+<p>{bill_to_name}<br>{bill_to_phone}<br>{bill_to_address}</p>
+<h3>销售方</h3>
+{sellers}
+<table>
+<thead><tr><th>商品</th><th>销售方</th><th class="num">数量</th><th class="num">单价</th><th class="num">优惠</th><th class="num">税率</th><th class="num">税额</th><th class="num">金额</th></tr></thead>
+<tbody>
+{rows}
+</tbody>
+</table>
+<table class="totals">
+<tr><td class="num">商品金额</td><td class="num">{list_total}</td></tr>
+<tr><td class="num">{discount_label}</td><td class="num">-{discount}</td></tr>
+<tr><td class="num">不含税金额</td><td class="num">{subtotal}</td></tr>
+<tr><td class="num">税额</td><td class="num">{tax}</td></tr>
+<tr><td class="num"><strong>价税合计</strong></td><td class="num"><strong>{total}</strong></td></tr>
+</table>
+</body>
+</html>"#,
+        invoice_number = escape_html(&document.invoice_number),
+        site_name = escape_html(&document.site_name),
+        issued_at = document.issued_at.format("%Y-%m-%d"),
+        order_number = escape_html(&document.order_number),
+        order_date = document.order_date.format("%Y-%m-%d %H:%M"),
+        bill_to_name = escape_html(&document.bill_to_name),
+        bill_to_phone = escape_html(&document.bill_to_phone),
+        bill_to_address = escape_html(&document.bill_to_address),
+        sellers = sellers,
+        rows = rows,
+        list_total = money(&currency, document.list_total),
+        discount_label = discount_label,
+        discount = money(&currency, document.discount),
+        subtotal = money(&currency, document.subtotal),
+        tax = money(&currency, document.tax),
+        total = money(&currency, document.total),
+    )
+}
+
+// PDF发票字体，需包含中文字形；默认使用随项目提供的 Noto Sans SC，可通过 INVOICE_PDF_FONT 指定其他 TrueType/OpenType 字体
+const DEFAULT_PDF_FONT: &str = "assets/fonts/NotoSansSC-Regular.ttf";
+
+static PDF_FONT: OnceLock<Vec<u8>> = OnceLock::new();
+
+// 读取PDF发票字体，读取成功后缓存
+pub fn load_pdf_font() -> std::io::Result<&'static [u8]> {
+    if let Some(font) = PDF_FONT.get() {
+        return Ok(font);
+    }
+    let path = env::var("INVOICE_PDF_FONT").unwrap_or_else(|_| DEFAULT_PDF_FONT.to_string());
+    let font = fs::read(path)?;
+    Ok(PDF_FONT.get_or_init(|| font))
+}
+
+// 逐行写入PDF，超出页面时自动换页
+struct PdfWriter {
+    document: printpdf::PdfDocumentReference,
+    layer: PdfLayerReference,
+    font: IndirectFontRef,
+    y: f32,
+}
+
+impl PdfWriter {
+    const PAGE_WIDTH: f32 = 210.0;
+    const PAGE_HEIGHT: f32 = 297.0;
+    const MARGIN: f32 = 20.0;
+
+    fn line(&mut self, columns: &[(f32, &str)], size: f32) {
+        if self.y < Self::MARGIN {
+            let (page, layer) = self.document.add_page(Mm(Self::PAGE_WIDTH), Mm(Self::PAGE_HEIGHT), "Layer 1");
+            self.layer = self.document.get_page(page).get_layer(layer);
+            self.y = Self::PAGE_HEIGHT - Self::MARGIN;
+        }
+
+        for (x, text) in columns {
+            self.layer.use_text(*text, size, Mm(*x), Mm(self.y), &self.font);
+        }
+        self.y -= size * 0.5 + 2.0;
+    }
+
+    fn gap(&mut self) {
+        self.y -= 4.0;
+    }
+}
+
+// 渲染PDF发票，内容与HTML发票一致；font 为嵌入PDF的字体文件
+pub fn render_pdf(document: &InvoiceDocument, font: &[u8]) -> Result<Vec<u8>, printpdf::Error> {
+    let (pdf, page, layer) = PdfDocument::new(
+        format!("发票 {}", document.invoice_number),
+        Mm(PdfWriter::PAGE_WIDTH),
+        Mm(PdfWriter::PAGE_HEIGHT),
+        "Layer 1",
+    );
+    let font = pdf.add_external_font(font)?;
+    let layer = pdf.get_page(page).get_layer(layer);
+
+    let mut writer = PdfWriter {
+        document: pdf,
+        layer,
+        font,
+        y: PdfWriter::PAGE_HEIGHT - PdfWriter::MARGIN,
+    };
+    let currency = &document.currency_symbol;
+    let left = PdfWriter::MARGIN;
+
+    writer.line(&[(left, &document.site_name)], 18.0);
+    writer.line(&[(left, &format!("发票 / Invoice {}", document.invoice_number))], 14.0);
+    writer.line(&[(left, &format!("开票日期: {}", document.issued_at.format("%Y-%m-%d")))], 10.0);
+    writer.line(&[(left, &format!("订单号: {}", document.order_number))], 10.0);
+    writer.line(&[(left, &format!("下单时间: {}", document.order_date.format("%Y-%m-%d %H:%M")))], 10.0);
+    writer.gap();
+
+    writer.line(&[(left, "购买方")], 11.0);
+    for text in [&document.bill_to_name, &document.bill_to_phone, &document.bill_to_address] {
+        if !text.is_empty() {
+            writer.line(&[(left, text)], 10.0);
+        }
+    }
+    writer.gap();
+
+    writer.line(&[(left, "销售方")], 11.0);
+    for seller in &document.sellers {
+        writer.line(&[(left, &seller.name)], 10.0);
+        if let Some(address) = &seller.address {
+            writer.line(&[(left + 4.0, address)], 9.0);
+        }
+        if let Some(email) = &seller.email {
+            writer.line(&[(left + 4.0, email)], 9.0);
+        }
+    }
+    writer.gap();
+
+    writer.line(
+        &[(left, "商品"), (70.0, "销售方"), (104.0, "数量"), (116.0, "单价"), (134.0, "优惠"), (150.0, "税率"), (162.0, "税额"), (178.0, "金额")],
+        9.0,
+    );
+    for line in &document.lines {
+        let quantity = line.quantity.to_string();
+        let unit_price = money(currency, line.unit_price);
+        let discount = format!("-{}", money(currency, line.discount));
+        let tax_rate = format!("{}%", line.tax_rate);
+        let tax_amount = money(currency, line.tax_amount);
+        let amount = money(currency, line.amount);
+        writer.line(
+            &[
+                (left, &line.description),
+                (70.0, &line.seller),
+                (104.0, &quantity),
+                (116.0, &unit_price),
+                (134.0, &discount),
+                (150.0, &tax_rate),
+                (162.0, &tax_amount),
+                (178.0, &amount),
+            ],
+            9.0,
+        );
+    }
+    writer.gap();
+
+    // 优惠包含自动促销和优惠券，使用了优惠券时注明优惠码
+    let discount_label = match &document.coupon_code {
+        Some(code) => format!("优惠（优惠券 {}）", code),
+        None => "优惠".to_string(),
+    };
+    let list_total = money(currency, document.list_total);
+    let discount = format!("-{}", money(currency, document.discount));
+    let subtotal = money(currency, document.subtotal);
+    let tax = money(currency, document.tax);
+    let total = money(currency, document.total);
+    writer.line(&[(134.0, "商品金额"), (178.0, &list_total)], 10.0);
+    writer.line(&[(134.0, &discount_label), (178.0, &discount)], 10.0);
+    writer.line(&[(134.0, "不含税金额"), (178.0, &subtotal)], 10.0);
+    writer.line(&[(134.0, "税额"), (178.0, &tax)], 10.0);
+    writer.line(&[(134.0, "价税合计"), (178.0, &total)], 11.0);
+
+    writer.document.save_to_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_document() -> InvoiceDocument {
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 10, 17)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap();
+        InvoiceDocument {
+            invoice_number: "INV-2026-000001".to_string(),
+            issued_at: date,
+            site_name: "Shop <Pro>".to_string(),
+            currency_symbol: "$".to_string(),
+            order_id: "order-1".to_string(),
+            order_number: "ORD-20261017-000123".to_string(),
+            order_date: date,
+            bill_to_name: "张三".to_string(),
+            bill_to_phone: "13800000000".to_string(),
+            bill_to_address: "Main Street 1".to_string(),
+            sellers: vec![InvoiceSeller {
+                vendor_id: "vendor-001".to_string(),
+                name: "Digital Dreams".to_string(),
+                address: None,
+                email: Some("shop@example.com".to_string()),
+            }],
+            lines: vec![InvoiceLine {
+                product_id: "p1".to_string(),
+                description: "USB-C Cable".to_string(),
+                seller: "Digital Dreams".to_string(),
+                quantity: 2,
+                unit_price: 50.0,
+                discount: 10.0,
+                tax_rate: 13.0,
+                tax_amount: 11.7,
+                amount: 101.7,
+            }],
+            list_total: 100.0,
+            discount: 10.0,
+            coupon_code: Some("SAVE10".to_string()),
+            subtotal: 90.0,
+            tax: 11.7,
+            total: 101.7,
+        }
+    }
+
+    // 测试字体：ASCII、中文标点、汉字和全角字符依次映射到空字形，只用于核对PDF中写入的文字
+    const TEST_FONT_RANGES: [(u32, u32); 4] = [(0x20, 0x7E), (0x3000, 0x303F), (0x4E00, 0x9FFF), (0xFF00, 0xFFEF)];
+
+    fn test_font() -> Vec<u8> {
+        let be16 = |values: &[u16]| values.iter().flat_map(|v| v.to_be_bytes()).collect::<Vec<u8>>();
+        let glyph_count = 1 + TEST_FONT_RANGES.iter().map(|(start, end)| end - start + 1).sum::<u32>() as u16;
+
+        // cmap：Windows Unicode 完整字符集，format 12
+        let mut cmap = be16(&[0, 1, 3, 10, 0, 12, 12, 0]);
+        cmap.extend((16 + 12 * TEST_FONT_RANGES.len() as u32).to_be_bytes());
+        cmap.extend(0u32.to_be_bytes());
+        cmap.extend((TEST_FONT_RANGES.len() as u32).to_be_bytes());
+        let mut glyph = 1u32;
+        for (start, end) in TEST_FONT_RANGES {
+            for value in [start, end, glyph] {
+                cmap.extend(value.to_be_bytes());
+            }
+            glyph += end - start + 1;
+        }
+
+        let mut head = be16(&[1, 0, 1, 0, 0, 0, 0x5F0F, 0x3CF5, 0, 1000]);
+        head.extend([0; 16]);
+        head.extend(be16(&[0, (-200i16) as u16, 1000, 800, 0, 8, 2, 0, 0]));
+        let hhea = be16(&[1, 0, 800, (-200i16) as u16, 0, 1000, 0, 0, 1000, 1, 0, 0, 0, 0, 0, 0, 0, 1]);
+        let mut hmtx = be16(&[1000, 0]);
+        hmtx.resize(2 + 2 * glyph_count as usize, 0);
+        let maxp = be16(&[0, 0x5000, glyph_count]);
+
+        let tables: [(&[u8; 4], Vec<u8>); 5] = [(b"cmap", cmap), (b"head", head), (b"hhea", hhea), (b"hmtx", hmtx), (b"maxp", maxp)];
+        let mut font = be16(&[1, 0, tables.len() as u16, 0, 0, 0]);
+        let mut data = Vec::new();
+        let data_offset = 12 + 16 * tables.len();
+        for (tag, table) in &tables {
+            font.extend(*tag);
+            font.extend(0u32.to_be_bytes());
+            font.extend(((data_offset + data.len()) as u32).to_be_bytes());
+            font.extend((table.len() as u32).to_be_bytes());
+            data.extend(table);
+            data.resize(data.len().div_ceil(4) * 4, 0);
+        }
+        font.extend(data);
+        font
+    }
+
+    // PDF中各段文字，按测试字体的映射把字形编号还原为字符
+    fn pdf_text(bytes: &[u8]) -> Vec<String> {
+        let document = printpdf::lopdf::Document::load_mem(bytes).unwrap();
+        let mut texts = Vec::new();
+        for page_id in document.get_pages().into_values() {
+            let content = document.get_and_decode_page_content(page_id).unwrap();
+            for operation in content.operations.iter().filter(|operation| operation.operator == "Tj") {
+                let glyphs = operation.operands[0].as_str().unwrap();
+                let text = glyphs
+                    .chunks(2)
+                    .filter_map(|pair| {
+                        // 字形 0 为 .notdef，其后按范围顺序排列
+                        let mut glyph = (u16::from_be_bytes([pair[0], pair[1]]) as u32).checked_sub(1)?;
+                        for (start, end) in TEST_FONT_RANGES {
+                            if glyph <= end - start {
+                                return char::from_u32(start + glyph);
+                            }
+                            glyph -= end - start + 1;
+                        }
+                        None
+                    })
+                    .collect();
+                texts.push(text);
+            }
+        }
+        texts
+    }
+
+    #[test]
+    fn test_format_invoice_number() {
+        assert_eq!(format_invoice_number(2026, 1), "INV-2026-000001");
+        assert_eq!(format_invoice_number(2026, 123456), "INV-2026-123456");
+    }
+
+    #[test]
+    fn test_render_html_escapes_and_includes_totals() {
+        let html = render_html(&sample_document());
+        assert!(html.contains("Shop &lt;Pro&gt;"));
+        assert!(html.contains("INV-2026-000001"));
+        assert!(html.contains("ORD-20261017-000123"));
+        assert!(html.contains("$50.00"));
+        assert!(html.contains("-$10.00"));
+        assert!(html.contains("优惠（优惠券 SAVE10）"));
+        assert!(html.contains("13%"));
+        assert!(html.contains("$101.70"));
+    }
+
+    #[test]
+    fn test_invoice_figures_reconcile() {
+        let document = sample_document();
+        // 单价 × 数量 - 优惠 + 税额 = 金额（价格不含税时）
+        for line in &document.lines {
+            let amount = round_currency(line.unit_price * line.quantity as f64 - line.discount + line.tax_amount);
+            assert_eq!(amount, line.amount);
+        }
+        let lines_discount = round_currency(document.lines.iter().map(|line| line.discount).sum());
+        let lines_total = round_currency(document.lines.iter().map(|line| line.amount).sum());
+        assert_eq!(lines_discount, document.discount);
+        assert_eq!(lines_total, document.total);
+        assert_eq!(round_currency(document.list_total - document.discount), document.subtotal);
+        assert_eq!(round_currency(document.subtotal + document.tax), document.total);
+    }
+
+    #[test]
+    fn test_render_pdf_embeds_chinese_text() {
+        let mut document = sample_document();
+        document.lines[0].description = "蓝牙耳机（黑色）".to_string();
+        document.sellers[0].name = "数码梦想旗舰店".to_string();
+        document.lines[0].seller = "数码梦想旗舰店".to_string();
+
+        let bytes = render_pdf(&document, &test_font()).unwrap();
+        assert!(bytes.starts_with(b"%PDF"));
+
+        let texts = pdf_text(&bytes);
+        assert!(texts.iter().any(|text| text == "蓝牙耳机（黑色）"), "{:?}", texts);
+        assert_eq!(texts.iter().filter(|text| *text == "数码梦想旗舰店").count(), 2);
+        assert!(texts.iter().any(|text| text == "张三"));
+        assert!(texts.iter().any(|text| text == "优惠（优惠券 SAVE10）"));
+        assert!(texts.iter().any(|text| text == "$101.70"));
+    }
+
+    #[test]
+    fn test_render_html_keeps_chinese_text() {
+        let mut document = sample_document();
+        document.lines[0].description = "蓝牙耳机（黑色）".to_string();
+        let html = render_html(&document);
+        assert!(html.contains("蓝牙耳机（黑色）"));
+        assert!(html.contains("张三"));
+        assert!(!html.contains('?'));
+    }
+}
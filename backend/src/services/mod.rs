@@ -0,0 +1,17 @@
+pub mod carrier;
+pub mod order_number;
+pub mod invoice;
+pub mod tax;
+pub mod idempotency;
+pub mod inventory;
+pub mod order_expiry;
+pub mod fulfillment;
+pub mod order_completion;
+pub mod cart_token;
+pub mod order_export;
+pub mod risk;
+pub mod subscription;
+pub mod payment_gateway;
+pub mod wallet;
+pub mod coupon;
+pub mod promotion;
\ No newline at end of file
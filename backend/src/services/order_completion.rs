@@ -0,0 +1,204 @@
+use diesel::prelude::*;
+use diesel::mysql::MysqlConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use std::collections::BTreeSet;
+use std::env;
+use std::time::Duration;
+
+use crate::models::order::OrderStatus;
+use crate::schema::order_fulfillments;
+use crate::services::fulfillment::{set_fulfillment_status, sync_order_status};
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+// 默认发货后自动确认收货的天数和扫描间隔（秒）
+const DEFAULT_AUTO_DELIVER_DAYS: i64 = 10;
+const DEFAULT_INTERVAL_SECONDS: u64 = 300;
+// 每个事务最多处理的子订单数，避免长时间持有行锁
+const BATCH_SIZE: i64 = 100;
+// 写入订单状态记录的原因
+pub const AUTO_DELIVER_REASON: &str = "发货后超时未确认收货，系统自动确认";
+pub const COMPLETION_REASON: &str = "退货期已过，订单自动完成";
+
+// 自动确认收货和订单自动完成的配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderCompletionConfig {
+    // 发货超过该天数仍未确认收货则自动确认，0 表示关闭自动确认收货（退货期结束后的自动完成不受影响）
+    pub auto_deliver_days: i64,
+    // 扫描间隔
+    pub interval: Duration,
+}
+
+impl OrderCompletionConfig {
+    // 从环境变量读取：AUTO_DELIVER_AFTER_DAYS（默认10）、ORDER_COMPLETION_INTERVAL_SECONDS（默认300）
+    pub fn from_env() -> Self {
+        Self::from_values(
+            env::var("AUTO_DELIVER_AFTER_DAYS").ok().as_deref(),
+            env::var("ORDER_COMPLETION_INTERVAL_SECONDS").ok().as_deref(),
+        )
+    }
+
+    fn from_values(auto_deliver_days: Option<&str>, interval_seconds: Option<&str>) -> Self {
+        let auto_deliver_days = auto_deliver_days
+            .and_then(|value| value.trim().parse::<i64>().ok())
+            .filter(|days| *days >= 0)
+            .unwrap_or(DEFAULT_AUTO_DELIVER_DAYS);
+        let interval_seconds = interval_seconds
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .filter(|seconds| *seconds > 0)
+            .unwrap_or(DEFAULT_INTERVAL_SECONDS);
+
+        Self {
+            auto_deliver_days,
+            interval: Duration::from_secs(interval_seconds),
+        }
+    }
+
+    pub fn auto_deliver_enabled(&self) -> bool {
+        self.auto_deliver_days > 0
+    }
+}
+
+// 将已认领的子订单改为目标状态并同步各父订单状态，返回处理的子订单数
+fn advance_fulfillments(
+    conn: &mut MysqlConnection,
+    claimed: Vec<(String, String)>,
+    status: OrderStatus,
+    reason: &str,
+) -> QueryResult<usize> {
+    if claimed.is_empty() {
+        return Ok(0);
+    }
+
+    let fulfillment_ids: Vec<String> = claimed.iter().map(|(id, _)| id.clone()).collect();
+    set_fulfillment_status(conn, &fulfillment_ids, &status)?;
+
+    let order_ids: BTreeSet<&String> = claimed.iter().map(|(_, order_id)| order_id).collect();
+    for order_id in order_ids {
+        sync_order_status(conn, order_id, Some(reason), None)?;
+    }
+
+    Ok(claimed.len())
+}
+
+// 发货超过指定天数的子订单自动确认收货
+// 候选子订单通过 SELECT ... FOR UPDATE SKIP LOCKED 加行锁认领，多个实例同时扫描时各自处理不同的子订单
+pub fn auto_deliver_fulfillments(conn: &mut MysqlConnection, auto_deliver_days: i64, limit: i64) -> QueryResult<usize> {
+    let cutoff = chrono::Utc::now().naive_utc() - chrono::Duration::days(auto_deliver_days);
+
+    conn.transaction(|conn| {
+        let claimed: Vec<(String, String)> = order_fulfillments::table
+            .filter(order_fulfillments::status.eq(OrderStatus::Shipped.to_string()))
+            .filter(order_fulfillments::shipped_at.lt(cutoff))
+            .order(order_fulfillments::shipped_at.asc())
+            .limit(limit)
+            .select((order_fulfillments::id, order_fulfillments::order_id))
+            .for_update()
+            .skip_locked()
+            .load(conn)?;
+
+        advance_fulfillments(conn, claimed, OrderStatus::Delivered, AUTO_DELIVER_REASON)
+    })
+}
+
+// 退货期已过的已送达子订单自动完成，行锁认领方式同上
+pub fn complete_fulfillments(conn: &mut MysqlConnection, limit: i64) -> QueryResult<usize> {
+    let now = chrono::Utc::now().naive_utc();
+
+    conn.transaction(|conn| {
+        let claimed: Vec<(String, String)> = order_fulfillments::table
+            .filter(order_fulfillments::status.eq(OrderStatus::Delivered.to_string()))
+            .filter(order_fulfillments::return_window_ends_at.le(now))
+            .order(order_fulfillments::return_window_ends_at.asc())
+            .limit(limit)
+            .select((order_fulfillments::id, order_fulfillments::order_id))
+            .for_update()
+            .skip_locked()
+            .load(conn)?;
+
+        advance_fulfillments(conn, claimed, OrderStatus::Completed, COMPLETION_REASON)
+    })
+}
+
+// 分批处理所有到期的子订单，返回自动确认收货和自动完成的子订单数量
+fn run_once(pool: &DbPool, config: &OrderCompletionConfig) -> Result<(usize, usize), String> {
+    let mut conn = pool.get().map_err(|e| e.to_string())?;
+    let mut delivered = 0;
+    let mut completed = 0;
+
+    if config.auto_deliver_enabled() {
+        loop {
+            let batch = auto_deliver_fulfillments(&mut conn, config.auto_deliver_days, BATCH_SIZE)
+                .map_err(|e| e.to_string())?;
+            delivered += batch;
+            if (batch as i64) < BATCH_SIZE {
+                break;
+            }
+        }
+    }
+
+    loop {
+        let batch = complete_fulfillments(&mut conn, BATCH_SIZE).map_err(|e| e.to_string())?;
+        completed += batch;
+        if (batch as i64) < BATCH_SIZE {
+            return Ok((delivered, completed));
+        }
+    }
+}
+
+// 在服务器内启动后台任务，按配置的间隔自动确认收货并关闭退货期已过的订单
+pub fn spawn(pool: DbPool, config: OrderCompletionConfig) {
+    if config.auto_deliver_enabled() {
+        println!(
+            "启动订单自动确认收货及完成任务：发货 {} 天后自动确认收货，每 {} 秒扫描一次",
+            config.auto_deliver_days,
+            config.interval.as_secs()
+        );
+    } else {
+        println!(
+            "自动确认收货已关闭，启动订单自动完成任务：每 {} 秒扫描一次",
+            config.interval.as_secs()
+        );
+    }
+
+    actix_web::rt::spawn(async move {
+        let mut ticker = actix_web::rt::time::interval(config.interval);
+        loop {
+            ticker.tick().await;
+
+            let pool = pool.clone();
+            let config = config.clone();
+            // 数据库操作是阻塞调用，放到线程池中执行
+            match actix_web::web::block(move || run_once(&pool, &config)).await {
+                Ok(Ok((0, 0))) => {}
+                Ok(Ok((delivered, completed))) => println!(
+                    "已自动确认收货 {} 个子订单，自动完成 {} 个子订单",
+                    delivered, completed
+                ),
+                Ok(Err(e)) => println!("订单自动确认收货或完成失败: {}", e),
+                Err(e) => println!("订单自动完成任务异常: {:?}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_from_values() {
+        let config = OrderCompletionConfig::from_values(None, None);
+        assert_eq!(config.auto_deliver_days, 10);
+        assert_eq!(config.interval, Duration::from_secs(300));
+        assert!(config.auto_deliver_enabled());
+
+        let config = OrderCompletionConfig::from_values(Some("7"), Some("60"));
+        assert_eq!(config.auto_deliver_days, 7);
+        assert_eq!(config.interval, Duration::from_secs(60));
+
+        let config = OrderCompletionConfig::from_values(Some("0"), Some("-1"));
+        assert!(!config.auto_deliver_enabled());
+        assert_eq!(config.interval, Duration::from_secs(300));
+    }
+}
@@ -0,0 +1,161 @@
+use diesel::prelude::*;
+use diesel::dsl::{exists, not};
+use diesel::mysql::MysqlConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use std::env;
+use std::time::Duration;
+
+use crate::models::order::OrderStatus;
+use crate::schema::{orders, order_risk_assessments};
+use crate::services::fulfillment::cancel_pending_orders;
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+// 默认超时时间（分钟）和扫描间隔（秒）
+const DEFAULT_TIMEOUT_MINUTES: i64 = 30;
+const DEFAULT_INTERVAL_SECONDS: u64 = 60;
+// 每个事务最多取消的订单数，避免长时间持有行锁
+const BATCH_SIZE: i64 = 100;
+// 写入订单状态记录的取消原因
+pub const EXPIRY_REASON: &str = "超时未支付，系统自动取消";
+
+// 超时未支付订单自动取消的配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderExpiryConfig {
+    // 下单后超过该时间仍未支付则取消，0 表示关闭自动取消
+    pub timeout_minutes: i64,
+    // 扫描间隔
+    pub interval: Duration,
+}
+
+impl OrderExpiryConfig {
+    // 从环境变量读取：PENDING_ORDER_TIMEOUT_MINUTES（默认30）、ORDER_EXPIRY_INTERVAL_SECONDS（默认60）
+    pub fn from_env() -> Self {
+        Self::from_values(
+            env::var("PENDING_ORDER_TIMEOUT_MINUTES").ok().as_deref(),
+            env::var("ORDER_EXPIRY_INTERVAL_SECONDS").ok().as_deref(),
+        )
+    }
+
+    fn from_values(timeout_minutes: Option<&str>, interval_seconds: Option<&str>) -> Self {
+        let timeout_minutes = timeout_minutes
+            .and_then(|value| value.trim().parse::<i64>().ok())
+            .filter(|minutes| *minutes >= 0)
+            .unwrap_or(DEFAULT_TIMEOUT_MINUTES);
+        let interval_seconds = interval_seconds
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .filter(|seconds| *seconds > 0)
+            .unwrap_or(DEFAULT_INTERVAL_SECONDS);
+
+        Self {
+            timeout_minutes,
+            interval: Duration::from_secs(interval_seconds),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.timeout_minutes > 0
+    }
+}
+
+// 取消一批超时未支付的订单：归还库存、取消商家子订单、写入状态记录，返回被取消的订单ID
+// 候选订单通过 SELECT ... FOR UPDATE SKIP LOCKED 加行锁认领，多个实例同时扫描时各自处理不同的订单，
+// 已被其他实例锁定的订单直接跳过，同一订单只会被取消一次、库存只归还一次
+pub fn expire_pending_orders(conn: &mut MysqlConnection, timeout_minutes: i64, limit: i64) -> QueryResult<Vec<String>> {
+    let cutoff = chrono::Utc::now().naive_utc() - chrono::Duration::minutes(timeout_minutes);
+    let pending = OrderStatus::Pending.to_string();
+
+    conn.transaction(|conn| {
+        // 风控待审核的订单由管理员处理，不自动取消；审核通过的订单从审核时间起重新计算支付时限
+        let order_ids: Vec<String> = orders::table
+            .filter(orders::status.eq(&pending))
+            .filter(orders::on_hold.eq(false))
+            .filter(orders::created_at.lt(cutoff))
+            .filter(not(exists(order_risk_assessments::table
+                .filter(order_risk_assessments::order_id.eq(orders::id.nullable()))
+                .filter(order_risk_assessments::reviewed_at.ge(cutoff)))))
+            .order(orders::created_at.asc())
+            .limit(limit)
+            .select(orders::id)
+            .for_update()
+            .skip_locked()
+            .load(conn)?;
+
+        if order_ids.is_empty() {
+            return Ok(order_ids);
+        }
+
+        cancel_pending_orders(conn, &order_ids, EXPIRY_REASON, None)?;
+
+        Ok(order_ids)
+    })
+}
+
+// 分批取消所有超时订单，返回被取消的订单数量
+fn run_once(pool: &DbPool, timeout_minutes: i64) -> Result<usize, String> {
+    let mut conn = pool.get().map_err(|e| e.to_string())?;
+    let mut expired = 0;
+
+    loop {
+        let batch = expire_pending_orders(&mut conn, timeout_minutes, BATCH_SIZE).map_err(|e| e.to_string())?;
+        expired += batch.len();
+        if (batch.len() as i64) < BATCH_SIZE {
+            return Ok(expired);
+        }
+    }
+}
+
+// 在服务器内启动后台任务，按配置的间隔扫描并取消超时未支付的订单
+pub fn spawn(pool: DbPool, config: OrderExpiryConfig) {
+    if !config.enabled() {
+        println!("超时未支付订单自动取消已关闭");
+        return;
+    }
+
+    println!(
+        "启动超时未支付订单自动取消任务：超时 {} 分钟，每 {} 秒扫描一次",
+        config.timeout_minutes,
+        config.interval.as_secs()
+    );
+
+    actix_web::rt::spawn(async move {
+        let mut ticker = actix_web::rt::time::interval(config.interval);
+        loop {
+            ticker.tick().await;
+
+            let pool = pool.clone();
+            let timeout_minutes = config.timeout_minutes;
+            // 数据库操作是阻塞调用，放到线程池中执行
+            match actix_web::web::block(move || run_once(&pool, timeout_minutes)).await {
+                Ok(Ok(0)) => {}
+                Ok(Ok(expired)) => println!("已自动取消 {} 个超时未支付订单", expired),
+                Ok(Err(e)) => println!("自动取消超时订单失败: {}", e),
+                Err(e) => println!("自动取消超时订单任务异常: {:?}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_from_values() {
+        let config = OrderExpiryConfig::from_values(None, None);
+        assert_eq!(config.timeout_minutes, 30);
+        assert_eq!(config.interval, Duration::from_secs(60));
+        assert!(config.enabled());
+
+        let config = OrderExpiryConfig::from_values(Some("15"), Some("5"));
+        assert_eq!(config.timeout_minutes, 15);
+        assert_eq!(config.interval, Duration::from_secs(5));
+
+        let config = OrderExpiryConfig::from_values(Some("0"), Some("0"));
+        assert!(!config.enabled());
+        assert_eq!(config.interval, Duration::from_secs(60));
+
+        let config = OrderExpiryConfig::from_values(Some("-5"), Some("abc"));
+        assert_eq!(config.timeout_minutes, 30);
+    }
+}
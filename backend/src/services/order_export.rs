@@ -0,0 +1,201 @@
+use actix_web::web::Bytes;
+use diesel::QueryResult;
+use futures::Stream;
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+use std::fs::File;
+use std::io::{self, Read};
+use tokio::sync::mpsc;
+
+use crate::models::order_export::{ExportFormat, VendorOrderExportRow, VENDOR_ORDER_EXPORT_HEADERS};
+
+// 每批读取的订单数
+pub const EXPORT_BATCH_SIZE: i64 = 200;
+// 待发送数据块的缓冲数量，客户端下载慢时读取线程会在此等待
+const CHANNEL_CAPACITY: usize = 8;
+// XLSX 文件分块发送的大小
+const FILE_CHUNK_SIZE: usize = 64 * 1024;
+// Excel 打开 UTF-8 编码的 CSV 时依赖 BOM 识别编码
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+type Chunk = Result<Bytes, io::Error>;
+
+// 将一个数据块交给下载连接，连接已断开时返回错误以停止导出
+fn send(sender: &mpsc::Sender<Chunk>, bytes: Vec<u8>) -> Result<(), String> {
+    if bytes.is_empty() {
+        return Ok(());
+    }
+    sender
+        .blocking_send(Ok(Bytes::from(bytes)))
+        .map_err(|_| "下载连接已断开".to_string())
+}
+
+// 将若干行追加写入缓冲区，字段中的逗号、引号和换行由 csv 负责转义
+fn append_csv_records<I, R>(buffer: Vec<u8>, records: I) -> Result<Vec<u8>, String>
+where
+    I: IntoIterator<Item = R>,
+    R: IntoIterator,
+    R::Item: AsRef<[u8]>,
+{
+    let mut writer = csv::Writer::from_writer(buffer);
+    for record in records {
+        writer.write_record(record).map_err(|e| e.to_string())?;
+    }
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+// CSV 每写完一批就发送，内存中只保留当前一批数据
+fn write_csv<F>(sender: &mpsc::Sender<Chunk>, next_batch: &mut F) -> Result<(), String>
+where
+    F: FnMut() -> QueryResult<Option<Vec<VendorOrderExportRow>>>,
+{
+    let mut buffer = append_csv_records(UTF8_BOM.to_vec(), [VENDOR_ORDER_EXPORT_HEADERS])?;
+
+    while let Some(rows) = next_batch().map_err(|e| e.to_string())? {
+        buffer = append_csv_records(buffer, rows.iter().map(VendorOrderExportRow::csv_record))?;
+        send(sender, std::mem::take(&mut buffer))?;
+    }
+
+    send(sender, buffer)
+}
+
+fn write_xlsx_row(workbook: &mut Workbook, row_num: u32, row: &VendorOrderExportRow) -> Result<(), XlsxError> {
+    let worksheet = workbook.worksheet_from_index(0)?;
+    worksheet.write_string(row_num, 0, &row.order_number)?;
+    worksheet.write_string(row_num, 1, row.created_at_text())?;
+    worksheet.write_string(row_num, 2, &row.order_status)?;
+    worksheet.write_string(row_num, 3, &row.fulfillment_status)?;
+    worksheet.write_string(row_num, 4, &row.product_id)?;
+    worksheet.write_string(row_num, 5, &row.product_name)?;
+    worksheet.write_number(row_num, 6, row.quantity)?;
+    worksheet.write_number(row_num, 7, row.price)?;
+    worksheet.write_number(row_num, 8, row.discount)?;
+    worksheet.write_number(row_num, 9, row.tax_amount)?;
+    worksheet.write_number(row_num, 10, row.total)?;
+    worksheet.write_string(row_num, 11, &row.recipient_name)?;
+    worksheet.write_string(row_num, 12, &row.phone)?;
+    worksheet.write_string(row_num, 13, &row.address)?;
+    worksheet.write_string(row_num, 14, &row.postal_code)?;
+    Ok(())
+}
+
+// XLSX 为 zip 压缩包，需要完整写出后才能发送：工作表使用常量内存模式逐行落盘，
+// 保存到临时文件后再分块发送
+fn write_xlsx<F>(sender: &mpsc::Sender<Chunk>, next_batch: &mut F) -> Result<(), String>
+where
+    F: FnMut() -> QueryResult<Option<Vec<VendorOrderExportRow>>>,
+{
+    let mut workbook = Workbook::new();
+    let header_format = Format::new().set_bold();
+    let worksheet = workbook.add_worksheet_with_constant_memory();
+    worksheet.set_name("订单").map_err(|e| e.to_string())?;
+    for (col, header) in VENDOR_ORDER_EXPORT_HEADERS.iter().enumerate() {
+        worksheet
+            .write_string_with_format(0, col as u16, *header, &header_format)
+            .map_err(|e| e.to_string())?;
+    }
+
+    let mut row_num: u32 = 1;
+    while let Some(rows) = next_batch().map_err(|e| e.to_string())? {
+        for row in &rows {
+            write_xlsx_row(&mut workbook, row_num, row).map_err(|e| e.to_string())?;
+            row_num += 1;
+        }
+    }
+
+    let path = std::env::temp_dir().join(format!("order-export-{}.xlsx", uuid::Uuid::new_v4()));
+    let result = workbook
+        .save(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|_| send_file(sender, &path));
+    if let Err(e) = std::fs::remove_file(&path) {
+        println!("删除导出临时文件失败: {:?}", e);
+    }
+    result
+}
+
+fn send_file(sender: &mpsc::Sender<Chunk>, path: &std::path::Path) -> Result<(), String> {
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    loop {
+        let mut buffer = vec![0; FILE_CHUNK_SIZE];
+        let read = file.read(&mut buffer).map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Ok(());
+        }
+        buffer.truncate(read);
+        send(sender, buffer)?;
+    }
+}
+
+// 在阻塞线程中分批读取导出数据并写出，返回可直接用于 HttpResponse::streaming 的数据流；
+// next_batch 每次返回一批数据行，返回 None 表示全部读取完毕
+pub fn stream_export<F>(format: ExportFormat, mut next_batch: F) -> impl Stream<Item = Chunk>
+where
+    F: FnMut() -> QueryResult<Option<Vec<VendorOrderExportRow>>> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel::<Chunk>(CHANNEL_CAPACITY);
+
+    tokio::task::spawn_blocking(move || {
+        let result = match format {
+            ExportFormat::Csv => write_csv(&sender, &mut next_batch),
+            ExportFormat::Xlsx => write_xlsx(&sender, &mut next_batch),
+        };
+        if let Err(message) = result {
+            println!("导出订单失败: {}", message);
+            // 响应头已发出，只能中断数据流让客户端感知下载失败
+            let _ = sender.blocking_send(Err(io::Error::other(message)));
+        }
+    });
+
+    futures::stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_row(product_name: &str) -> VendorOrderExportRow {
+        VendorOrderExportRow {
+            order_number: "ORD-20261018-000001".to_string(),
+            created_at: chrono::NaiveDate::from_ymd_opt(2026, 10, 18).unwrap().and_hms_opt(9, 30, 0).unwrap(),
+            order_status: "Processing".to_string(),
+            fulfillment_status: "Pending".to_string(),
+            product_id: "p-1".to_string(),
+            product_name: product_name.to_string(),
+            quantity: 2,
+            price: 9.5,
+            discount: 0.0,
+            tax_amount: 1.9,
+            total: 20.9,
+            recipient_name: "张三".to_string(),
+            phone: "13800000000".to_string(),
+            address: "上海市 浦东新区 世纪大道 1 号".to_string(),
+            postal_code: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_csv_export_is_sent_batch_by_batch() {
+        let (sender, mut receiver) = mpsc::channel::<Chunk>(CHANNEL_CAPACITY);
+        let mut batches = vec![vec![sample_row("杯子")], vec![sample_row("毛巾, \"加厚\"")]].into_iter();
+        write_csv(&sender, &mut || Ok(batches.next())).unwrap();
+        drop(sender);
+
+        let mut chunks = Vec::new();
+        while let Ok(chunk) = receiver.try_recv() {
+            chunks.push(chunk.unwrap());
+        }
+        assert_eq!(chunks.len(), 2);
+
+        let text = String::from_utf8(chunks.concat()).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(lines.next(), Some("\u{feff}订单号,下单时间,订单状态,发货状态,商品ID,商品名称,数量,单价,优惠,税额,金额,收货人,联系电话,收货地址,邮编"));
+        assert_eq!(
+            lines.next(),
+            Some("ORD-20261018-000001,2026-10-18 09:30:00,Processing,Pending,p-1,杯子,2,9.50,0.00,1.90,20.90,张三,13800000000,上海市 浦东新区 世纪大道 1 号,")
+        );
+        assert!(lines.next().unwrap().contains(",\"毛巾, \"\"加厚\"\"\","));
+        assert_eq!(lines.next(), None);
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel::mysql::MysqlConnection;
+use diesel::sql_types::{BigInt, Date};
+
+use crate::schema::admin_profiles;
+
+// 管理员未配置前缀时使用的默认订单号前缀
+pub const DEFAULT_ORDER_PREFIX: &str = "ORD-";
+
+#[derive(QueryableByName)]
+struct SequenceValue {
+    #[diesel(sql_type = BigInt)]
+    value: i64,
+}
+
+// 生成订单号，例如 ORD-20261017-000123
+pub fn format_order_number(prefix: &str, date: NaiveDate, sequence: i64) -> String {
+    format!("{}{}-{:06}", prefix, date.format("%Y%m%d"), sequence)
+}
+
+// 读取系统设置中的订单号前缀
+fn order_prefix(conn: &mut MysqlConnection) -> String {
+    admin_profiles::table
+        .order(admin_profiles::created_at.asc())
+        .select(admin_profiles::order_prefix)
+        .first::<String>(conn)
+        .ok()
+        .map(|prefix| prefix.trim().to_string())
+        .filter(|prefix| !prefix.is_empty())
+        .unwrap_or_else(|| DEFAULT_ORDER_PREFIX.to_string())
+}
+
+// 分配下一个订单号
+// 当日序号通过 INSERT ... ON DUPLICATE KEY UPDATE 原子递增，并发下单不会拿到相同序号；
+// 必须在下单事务之外调用，行锁只在这一条语句内持有，下单失败时该序号作废，订单号允许出现空号
+pub fn next_order_number(conn: &mut MysqlConnection) -> QueryResult<String> {
+    let today = chrono::Utc::now().date_naive();
+
+    diesel::sql_query(
+        "INSERT INTO order_number_sequences (sequence_date, last_value) VALUES (?, LAST_INSERT_ID(1)) \
+         ON DUPLICATE KEY UPDATE last_value = LAST_INSERT_ID(last_value + 1)",
+    )
+    .bind::<Date, _>(today)
+    .execute(conn)?;
+
+    // LAST_INSERT_ID 按连接隔离，读取到的是本连接刚分配的序号
+    let sequence = diesel::sql_query("SELECT CAST(LAST_INSERT_ID() AS SIGNED) AS value")
+        .get_result::<SequenceValue>(conn)?;
+
+    Ok(format_order_number(&order_prefix(conn), today, sequence.value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_order_number() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        assert_eq!(format_order_number("ORD-", date, 123), "ORD-20261017-000123");
+        assert_eq!(format_order_number("SHOP", date, 1), "SHOP20261017-000001");
+        assert_eq!(format_order_number("ORD-", date, 1234567), "ORD-20261017-1234567");
+    }
+}
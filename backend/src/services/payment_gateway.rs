@@ -0,0 +1,407 @@
+use chrono::NaiveDateTime;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::env;
+use std::fmt;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// 回调通知发出时间与当前时间允许的默认最大偏差（秒）
+const DEFAULT_CALLBACK_TOLERANCE_SECONDS: i64 = 300;
+
+// 支付渠道侧的支付状态
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GatewayPaymentStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+// 发起支付的结果：渠道侧支付单号和用户完成支付的地址
+#[derive(Debug, Clone)]
+pub struct PaymentIntent {
+    pub gateway_payment_id: String,
+    pub payment_url: Option<String>,
+}
+
+// 支付渠道回调的原始请求
+#[derive(Debug, Clone, Copy)]
+pub struct CallbackRequest<'a> {
+    // 渠道签名请求头的值，见 PaymentGateway::signature_header
+    pub signature: Option<&'a str>,
+    pub body: &'a [u8],
+}
+
+// 验签通过后的支付结果通知
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentNotification {
+    // 渠道为每条通知分配的唯一编号，同一编号的通知只处理一次
+    pub notification_id: String,
+    // 通知发出时间（UTC），包含在签名内容中
+    pub sent_at: NaiveDateTime,
+    pub gateway_payment_id: String,
+    pub status: GatewayPaymentStatus,
+    pub amount: f64,
+    pub failure_reason: Option<String>,
+}
+
+impl PaymentNotification {
+    // 主动查询没有渠道通知编号，按一次性的查询编号记录；金额为渠道实际收到的金额
+    pub fn from_query(gateway_payment_id: &str, result: PaymentQueryResult) -> Self {
+        Self {
+            notification_id: format!("query-{}", uuid::Uuid::new_v4()),
+            sent_at: chrono::Utc::now().naive_utc(),
+            gateway_payment_id: gateway_payment_id.to_string(),
+            status: result.status,
+            amount: result.paid_amount,
+            failure_reason: None,
+        }
+    }
+}
+
+// 主动查询到的渠道侧支付结果，paid_amount 为渠道实际收到的金额，未支付时为 0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaymentQueryResult {
+    pub status: GatewayPaymentStatus,
+    pub paid_amount: f64,
+}
+
+#[derive(Debug)]
+pub enum GatewayError {
+    // 回调签名缺失或不正确
+    InvalidSignature,
+    // 回调内容无法解析
+    InvalidPayload(String),
+    // 通知发出时间超出允许的偏差，可能是被截获后重放的旧通知
+    Stale,
+    // 渠道不认识该支付单号
+    NotFound,
+    // 渠道拒绝了请求，例如金额不合法
+    Rejected(String),
+}
+
+impl fmt::Display for GatewayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GatewayError::InvalidSignature => write!(f, "回调签名无效"),
+            GatewayError::InvalidPayload(reason) => write!(f, "回调内容无效: {}", reason),
+            GatewayError::Stale => write!(f, "回调通知已过期"),
+            GatewayError::NotFound => write!(f, "支付单号不存在"),
+            GatewayError::Rejected(reason) => write!(f, "支付渠道拒绝请求: {}", reason),
+        }
+    }
+}
+
+// 支付渠道接口，接入新的支付渠道时实现该trait并在 gateway_for 中注册
+pub trait PaymentGateway: Send + Sync {
+    // 渠道代码，与 payments.gateway 字段及后台配置的 payment_gateways 对应
+    fn code(&self) -> &'static str;
+
+    // 回调请求中携带签名的请求头
+    fn signature_header(&self) -> &'static str;
+
+    // 发起支付，payment_id 为本地支付记录ID
+    fn create_intent(&self, payment_id: &str, amount: f64, currency: &str) -> Result<PaymentIntent, GatewayError>;
+
+    // 校验回调签名并解析支付结果，签名不正确时返回 InvalidSignature
+    fn verify_callback(&self, callback: &CallbackRequest) -> Result<PaymentNotification, GatewayError>;
+
+    // 主动查询渠道侧的支付状态和实收金额
+    fn query(&self, gateway_payment_id: &str) -> Result<PaymentQueryResult, GatewayError>;
+
+    // 退款，返回渠道侧的退款单号
+    fn refund(&self, gateway_payment_id: &str, amount: f64) -> Result<String, GatewayError>;
+}
+
+// 回调通知允许的时间偏差（秒），从 PAYMENT_CALLBACK_TOLERANCE_SECONDS 读取
+pub fn callback_tolerance_seconds() -> i64 {
+    env::var("PAYMENT_CALLBACK_TOLERANCE_SECONDS")
+        .ok()
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .filter(|seconds| *seconds > 0)
+        .unwrap_or(DEFAULT_CALLBACK_TOLERANCE_SECONDS)
+}
+
+// 检查通知发出时间，早于或晚于当前时间超过允许偏差的通知一律拒绝；
+// 配合 payment_callbacks 中记录的通知编号，偏差范围内的重放也能识别
+pub fn check_freshness(sent_at: NaiveDateTime, now: NaiveDateTime, tolerance_seconds: i64) -> Result<(), GatewayError> {
+    if (now - sent_at).num_seconds().abs() > tolerance_seconds {
+        return Err(GatewayError::Stale);
+    }
+    Ok(())
+}
+
+// 模拟回调的请求体，timestamp 为通知发出时间的 Unix 秒数
+#[derive(Debug, Serialize, Deserialize)]
+struct MockCallbackBody {
+    notification_id: String,
+    timestamp: i64,
+    payment_id: String,
+    status: GatewayPaymentStatus,
+    amount: f64,
+    reason: Option<String>,
+}
+
+// 本地模拟支付渠道，不访问外部服务，用于开发和测试；
+// 只有设置 PAYMENT_MOCK_ENABLED=true 且配置了专用的 PAYMENT_MOCK_SECRET 时才会注册，生产环境不要开启；
+// 回调请求体为 JSON，X-Mock-Signature 请求头为请求体的 HMAC-SHA256 签名（十六进制）
+pub struct MockGateway {
+    secret: String,
+    // 主动查询返回的结果，为空时视为未支付
+    query_result: Option<PaymentQueryResult>,
+}
+
+impl MockGateway {
+    pub const CODE: &'static str = "mock";
+    const SIGNATURE_HEADER: &'static str = "X-Mock-Signature";
+    const ID_PREFIX: &'static str = "mock_";
+
+    // 从环境变量读取，未开启或未配置签名密钥时返回 None
+    pub fn from_env() -> Option<Self> {
+        Self::from_values(
+            env::var("PAYMENT_MOCK_ENABLED").ok().as_deref(),
+            env::var("PAYMENT_MOCK_SECRET").ok().as_deref(),
+        )
+    }
+
+    fn from_values(enabled: Option<&str>, secret: Option<&str>) -> Option<Self> {
+        let enabled = enabled
+            .map(|value| matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+        match secret.map(str::trim).filter(|secret| !secret.is_empty()) {
+            Some(secret) => Some(Self { secret: secret.to_string(), query_result: None }),
+            None => {
+                println!("已开启模拟支付渠道但未设置 PAYMENT_MOCK_SECRET，模拟支付渠道不可用");
+                None
+            }
+        }
+    }
+
+    // 使用固定密钥，便于测试构造回调
+    #[cfg(test)]
+    pub fn with_secret(secret: &str) -> Self {
+        Self { secret: secret.to_string(), query_result: None }
+    }
+
+    // 指定主动查询返回的结果，模拟渠道侧已收款
+    #[cfg(test)]
+    pub fn with_query_result(mut self, result: PaymentQueryResult) -> Self {
+        self.query_result = Some(result);
+        self
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(self.secret.as_bytes()).expect("HMAC accepts keys of any length")
+    }
+
+    // 按渠道规则为回调请求体签名
+    #[cfg(test)]
+    pub fn sign(&self, body: &[u8]) -> String {
+        let mut mac = self.mac();
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn check_payment_id(gateway_payment_id: &str) -> Result<(), GatewayError> {
+        if gateway_payment_id.starts_with(Self::ID_PREFIX) {
+            Ok(())
+        } else {
+            Err(GatewayError::NotFound)
+        }
+    }
+}
+
+impl PaymentGateway for MockGateway {
+    fn code(&self) -> &'static str {
+        Self::CODE
+    }
+
+    fn signature_header(&self) -> &'static str {
+        Self::SIGNATURE_HEADER
+    }
+
+    fn create_intent(&self, payment_id: &str, amount: f64, currency: &str) -> Result<PaymentIntent, GatewayError> {
+        if amount <= 0.0 {
+            return Err(GatewayError::Rejected("支付金额必须大于0".to_string()));
+        }
+
+        let gateway_payment_id = format!("{}{}", Self::ID_PREFIX, payment_id);
+        Ok(PaymentIntent {
+            payment_url: Some(format!("mock://pay/{}?amount={:.2}&currency={}", gateway_payment_id, amount, currency)),
+            gateway_payment_id,
+        })
+    }
+
+    fn verify_callback(&self, callback: &CallbackRequest) -> Result<PaymentNotification, GatewayError> {
+        let signature = callback.signature
+            .and_then(|signature| hex::decode(signature.trim()).ok())
+            .ok_or(GatewayError::InvalidSignature)?;
+        let mut mac = self.mac();
+        mac.update(callback.body);
+        // verify_slice 使用常量时间比较
+        mac.verify_slice(&signature).map_err(|_| GatewayError::InvalidSignature)?;
+
+        let body: MockCallbackBody = serde_json::from_slice(callback.body)
+            .map_err(|e| GatewayError::InvalidPayload(e.to_string()))?;
+        Self::check_payment_id(&body.payment_id)?;
+        if body.notification_id.trim().is_empty() {
+            return Err(GatewayError::InvalidPayload("缺少通知编号".to_string()));
+        }
+        let sent_at = chrono::DateTime::from_timestamp(body.timestamp, 0)
+            .ok_or_else(|| GatewayError::InvalidPayload("通知时间无效".to_string()))?
+            .naive_utc();
+
+        Ok(PaymentNotification {
+            notification_id: body.notification_id,
+            sent_at,
+            gateway_payment_id: body.payment_id,
+            status: body.status,
+            amount: body.amount,
+            failure_reason: body.reason,
+        })
+    }
+
+    // 模拟渠道不保存状态，支付结果只通过回调通知，查询时视为未支付
+    fn query(&self, gateway_payment_id: &str) -> Result<PaymentQueryResult, GatewayError> {
+        Self::check_payment_id(gateway_payment_id)?;
+        Ok(self.query_result.unwrap_or(PaymentQueryResult {
+            status: GatewayPaymentStatus::Pending,
+            paid_amount: 0.0,
+        }))
+    }
+
+    fn refund(&self, gateway_payment_id: &str, amount: f64) -> Result<String, GatewayError> {
+        Self::check_payment_id(gateway_payment_id)?;
+        if amount <= 0.0 {
+            return Err(GatewayError::Rejected("退款金额必须大于0".to_string()));
+        }
+        Ok(format!("{}refund_{}", Self::ID_PREFIX, uuid::Uuid::new_v4().simple()))
+    }
+}
+
+// 根据渠道代码获取支付渠道实现，未接入或未开启的渠道返回 None
+pub fn gateway_for(code: &str) -> Option<Box<dyn PaymentGateway>> {
+    let mut gateways: Vec<Box<dyn PaymentGateway>> = Vec::new();
+    if let Some(mock) = MockGateway::from_env() {
+        gateways.push(Box::new(mock));
+    }
+
+    gateways.into_iter().find(|gateway| gateway.code().eq_ignore_ascii_case(code.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::payment::PaymentStatus;
+
+    const SENT_AT: i64 = 1_792_300_000;
+
+    fn callback_body(payment_id: &str, status: &str) -> Vec<u8> {
+        format!(
+            r#"{{"notification_id":"n-1","timestamp":{},"payment_id":"{}","status":"{}","amount":99.5,"reason":null}}"#,
+            SENT_AT, payment_id, status
+        ).into_bytes()
+    }
+
+    #[test]
+    fn test_mock_gateway_create_intent() {
+        let gateway = MockGateway::with_secret("secret");
+        let intent = gateway.create_intent("p-1", 99.5, "CNY").unwrap();
+        assert_eq!(intent.gateway_payment_id, "mock_p-1");
+        assert!(intent.payment_url.unwrap().starts_with("mock://pay/mock_p-1"));
+        assert!(matches!(gateway.create_intent("p-2", 0.0, "CNY"), Err(GatewayError::Rejected(_))));
+    }
+
+    #[test]
+    fn test_mock_gateway_verifies_signed_callback() {
+        let gateway = MockGateway::with_secret("secret");
+        let body = callback_body("mock_p-1", "succeeded");
+        let signature = gateway.sign(&body);
+
+        let notification = gateway
+            .verify_callback(&CallbackRequest { signature: Some(&signature), body: &body })
+            .unwrap();
+        assert_eq!(notification.notification_id, "n-1");
+        assert_eq!(notification.sent_at.and_utc().timestamp(), SENT_AT);
+        assert_eq!(notification.gateway_payment_id, "mock_p-1");
+        assert_eq!(notification.status, GatewayPaymentStatus::Succeeded);
+        assert_eq!(notification.amount, 99.5);
+
+        // 签名缺失、密钥不同或请求体被篡改时拒绝
+        assert!(matches!(
+            gateway.verify_callback(&CallbackRequest { signature: None, body: &body }),
+            Err(GatewayError::InvalidSignature)
+        ));
+        let forged = MockGateway::with_secret("other").sign(&body);
+        assert!(matches!(
+            gateway.verify_callback(&CallbackRequest { signature: Some(&forged), body: &body }),
+            Err(GatewayError::InvalidSignature)
+        ));
+        let tampered = callback_body("mock_p-1", "failed");
+        assert!(matches!(
+            gateway.verify_callback(&CallbackRequest { signature: Some(&signature), body: &tampered }),
+            Err(GatewayError::InvalidSignature)
+        ));
+
+        let malformed = b"not json".to_vec();
+        let signature = gateway.sign(&malformed);
+        assert!(matches!(
+            gateway.verify_callback(&CallbackRequest { signature: Some(&signature), body: &malformed }),
+            Err(GatewayError::InvalidPayload(_))
+        ));
+    }
+
+    #[test]
+    fn test_queried_amount_is_reconciled() {
+        // 渠道实收 80.00，支付单和订单应付均为 99.50
+        let gateway = MockGateway::with_secret("secret").with_query_result(PaymentQueryResult {
+            status: GatewayPaymentStatus::Succeeded,
+            paid_amount: 80.0,
+        });
+        let notification = PaymentNotification::from_query("mock_p-1", gateway.query("mock_p-1").unwrap());
+        assert_eq!(notification.status, GatewayPaymentStatus::Succeeded);
+        assert_eq!(notification.amount, 80.0);
+        assert!(notification.notification_id.starts_with("query-"));
+        assert_eq!(PaymentStatus::settled(notification.amount, 99.5, 99.5), PaymentStatus::Mismatched);
+
+        let gateway = MockGateway::with_secret("secret").with_query_result(PaymentQueryResult {
+            status: GatewayPaymentStatus::Succeeded,
+            paid_amount: 99.5,
+        });
+        let notification = PaymentNotification::from_query("mock_p-1", gateway.query("mock_p-1").unwrap());
+        assert_eq!(PaymentStatus::settled(notification.amount, 99.5, 99.5), PaymentStatus::Succeeded);
+    }
+
+    #[test]
+    fn test_check_freshness() {
+        let sent_at = chrono::DateTime::from_timestamp(SENT_AT, 0).unwrap().naive_utc();
+        assert!(check_freshness(sent_at, sent_at + chrono::Duration::seconds(300), 300).is_ok());
+        assert!(check_freshness(sent_at, sent_at - chrono::Duration::seconds(60), 300).is_ok());
+        assert!(matches!(check_freshness(sent_at, sent_at + chrono::Duration::seconds(301), 300), Err(GatewayError::Stale)));
+        // 发出时间远在未来的通知同样拒绝
+        assert!(matches!(check_freshness(sent_at, sent_at - chrono::Duration::hours(1), 300), Err(GatewayError::Stale)));
+    }
+
+    #[test]
+    fn test_gateway_registry() {
+        assert!(gateway_for("alipay").is_none());
+
+        // 模拟渠道需要显式开启，并且必须配置专用密钥
+        assert!(MockGateway::from_values(None, Some("secret")).is_none());
+        assert!(MockGateway::from_values(Some("false"), Some("secret")).is_none());
+        assert!(MockGateway::from_values(Some("true"), None).is_none());
+        assert!(MockGateway::from_values(Some("true"), Some("  ")).is_none());
+        assert!(MockGateway::from_values(Some("TRUE"), Some("secret")).is_some());
+
+        let gateway = MockGateway::with_secret("secret");
+        assert_eq!(gateway.query("mock_p-1").unwrap().status, GatewayPaymentStatus::Pending);
+        assert!(matches!(gateway.query("other"), Err(GatewayError::NotFound)));
+        assert!(gateway.refund("mock_p-1", 10.0).unwrap().starts_with("mock_refund_"));
+        assert!(matches!(gateway.refund("mock_p-1", 0.0), Err(GatewayError::Rejected(_))));
+    }
+}
@@ -0,0 +1,277 @@
+use diesel::prelude::*;
+use diesel::mysql::MysqlConnection;
+
+use crate::models::product::Product;
+use crate::models::promotion::{AppliedPromotion, Promotion, PromotionKind};
+use crate::schema::promotions;
+use crate::services::tax::round_currency;
+
+// 订单项的定价结果：标价金额、享受的促销和优惠券优惠
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinePricing {
+    // 标价金额
+    pub amount: f64,
+    pub promotions: Vec<AppliedPromotion>,
+    pub coupon_discount: f64,
+    // 享受了不可叠加的促销时为 false，不再参与满减和优惠券
+    pub stackable: bool,
+}
+
+impl LinePricing {
+    pub fn promotion_discount(&self) -> f64 {
+        round_currency(self.promotions.iter().map(|promotion| promotion.discount).sum())
+    }
+
+    // 促销和优惠券的优惠合计
+    pub fn discount(&self) -> f64 {
+        round_currency(self.promotion_discount() + self.coupon_discount)
+    }
+
+    // 还可以参与满减和优惠券的金额
+    pub fn remaining(&self) -> f64 {
+        if self.stackable {
+            round_currency(self.amount - self.promotion_discount()).max(0.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+// 按金额比例分摊优惠，分摊的尾差计入最后一个参与分摊的订单项；金额为0的订单项不参与分摊
+pub fn allocate(total_discount: f64, amounts: &[f64]) -> Vec<f64> {
+    let base = round_currency(amounts.iter().filter(|amount| **amount > 0.0).sum());
+    if base <= 0.0 {
+        return vec![0.0; amounts.len()];
+    }
+    let total_discount = round_currency(total_discount.clamp(0.0, base));
+    let last = amounts.iter().rposition(|amount| *amount > 0.0);
+    let mut allocated = 0.0;
+    amounts
+        .iter()
+        .enumerate()
+        .map(|(index, amount)| {
+            if *amount <= 0.0 {
+                return 0.0;
+            }
+            let discount = if Some(index) == last {
+                round_currency(total_discount - allocated)
+            } else {
+                round_currency(total_discount * amount / base)
+            };
+            let discount = discount.clamp(0.0, *amount);
+            allocated = round_currency(allocated + discount);
+            discount
+        })
+        .collect()
+}
+
+// 单个订单项的商品级促销优惠：买 X 送 Y 按同一商品的件数计算，百分比折扣按标价计算
+fn line_promotion_discount(promotion: &Promotion, kind: PromotionKind, product: &Product, quantity: i32) -> f64 {
+    match kind {
+        PromotionKind::BuyXGetY => {
+            let buy = promotion.buy_quantity.unwrap_or(0);
+            let free = promotion.free_quantity.unwrap_or(0);
+            if buy <= 0 || free <= 0 {
+                return 0.0;
+            }
+            let free_units = quantity / (buy + free) * free;
+            round_currency(product.price * free_units as f64)
+        }
+        PromotionKind::PercentageOff => {
+            let percentage = promotion.percentage.unwrap_or(0.0).clamp(0.0, 100.0);
+            round_currency(product.price * quantity as f64 * percentage / 100.0)
+        }
+        PromotionKind::Tiered => 0.0,
+    }
+}
+
+fn applied(promotion: &Promotion, kind: PromotionKind, discount: f64) -> AppliedPromotion {
+    AppliedPromotion {
+        promotion_id: promotion.id.clone(),
+        name: promotion.name.clone(),
+        kind,
+        discount,
+        funded_by: promotion.funded_by.clone(),
+    }
+}
+
+// 计算购物车的自动促销，结果与 lines 一一对应。规则：
+// 1. 促销按 priority 从高到低、创建时间从早到晚依次计算；
+// 2. 每个订单项最多享受一个商品级促销（买 X 送 Y 或百分比折扣），取第一个有优惠的；
+// 3. 商品级促销之后计算满减，每个订单最多享受一个满减，取第一个达到门槛的；
+//    门槛按适用商品扣除商品级促销后的金额计算，优惠按金额比例分摊到这些商品；
+// 4. 享受了不可叠加促销的订单项不再参与之后的满减和优惠券
+pub fn apply_promotions(promotions: &[Promotion], lines: &[(&Product, i32)], now: chrono::NaiveDateTime) -> Vec<LinePricing> {
+    let mut running: Vec<&Promotion> = promotions.iter().filter(|promotion| promotion.is_running(now)).collect();
+    running.sort_by(|a, b| b.priority.cmp(&a.priority).then(a.created_at.cmp(&b.created_at)).then(a.id.cmp(&b.id)));
+
+    let mut pricing: Vec<LinePricing> = lines
+        .iter()
+        .map(|(product, quantity)| LinePricing {
+            amount: round_currency(product.price * *quantity as f64),
+            promotions: Vec::new(),
+            coupon_discount: 0.0,
+            stackable: true,
+        })
+        .collect();
+
+    // 商品级促销
+    for (line, (product, quantity)) in pricing.iter_mut().zip(lines) {
+        for promotion in &running {
+            let kind = match promotion.get_kind() {
+                Some(kind) if kind != PromotionKind::Tiered => kind,
+                _ => continue,
+            };
+            if !promotion.applies_to(&product.id, product.category.as_deref(), &product.vendor_id) {
+                continue;
+            }
+            let discount = line_promotion_discount(promotion, kind, product, *quantity).min(line.amount);
+            if discount > 0.0 {
+                line.promotions.push(applied(promotion, kind, discount));
+                line.stackable = promotion.stackable;
+                break;
+            }
+        }
+    }
+
+    // 满减
+    for promotion in &running {
+        if promotion.get_kind() != Some(PromotionKind::Tiered) {
+            continue;
+        }
+        let amounts: Vec<f64> = pricing
+            .iter()
+            .zip(lines)
+            .map(|(line, (product, _))| {
+                if promotion.applies_to(&product.id, product.category.as_deref(), &product.vendor_id) {
+                    line.remaining()
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        let base = round_currency(amounts.iter().sum());
+        let tier = promotion
+            .get_tiers()
+            .into_iter()
+            .filter(|tier| tier.threshold <= base && tier.discount > 0.0)
+            .max_by(|a, b| a.threshold.total_cmp(&b.threshold));
+        let tier = match tier {
+            Some(tier) => tier,
+            None => continue,
+        };
+
+        for (line, discount) in pricing.iter_mut().zip(allocate(tier.discount, &amounts)) {
+            if discount > 0.0 {
+                line.promotions.push(applied(promotion, PromotionKind::Tiered, discount));
+                line.stackable = line.stackable && promotion.stackable;
+            }
+        }
+        break;
+    }
+
+    pricing
+}
+
+// 读取启用中的促销，有效期在计算时检查
+pub fn load_active(conn: &mut MysqlConnection) -> QueryResult<Vec<Promotion>> {
+    promotions::table
+        .filter(promotions::active.eq(true))
+        .select(Promotion::as_select())
+        .load(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{product, promotion};
+
+    fn tiered(id: &str, priority: i32) -> Promotion {
+        let mut promotion = promotion(id, PromotionKind::Tiered, priority);
+        promotion.tiers = Some("300:30,500:60".to_string());
+        promotion
+    }
+
+    fn buy_two_get_one(id: &str, priority: i32) -> Promotion {
+        let mut promotion = promotion(id, PromotionKind::BuyXGetY, priority);
+        promotion.buy_quantity = Some(2);
+        promotion.free_quantity = Some(1);
+        promotion
+    }
+
+    fn ids(line: &LinePricing) -> Vec<&str> {
+        line.promotions.iter().map(|promotion| promotion.promotion_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tiered_uses_highest_tier_reached() {
+        let a = product("p1", 200.0);
+        let b = product("p2", 150.0);
+        let now = chrono::Utc::now().naive_utc();
+
+        let pricing = apply_promotions(&[tiered("t", 0)], &[(&a, 1), (&b, 1)], now);
+        // 满 300 减 30，按金额比例分摊
+        assert_eq!(pricing.iter().map(LinePricing::discount).collect::<Vec<_>>(), vec![17.14, 12.86]);
+
+        let pricing = apply_promotions(&[tiered("t", 0)], &[(&a, 2), (&b, 1)], now);
+        assert_eq!(round_currency(pricing.iter().map(LinePricing::discount).sum()), 60.0);
+
+        let pricing = apply_promotions(&[tiered("t", 0)], &[(&a, 1)], now);
+        assert!(pricing[0].promotions.is_empty());
+    }
+
+    #[test]
+    fn test_buy_x_get_y() {
+        let a = product("p1", 10.0);
+        let now = chrono::Utc::now().naive_utc();
+        let pricing = apply_promotions(&[buy_two_get_one("b", 0)], &[(&a, 7)], now);
+        // 7 件中 2 件免费
+        assert_eq!(pricing[0].discount(), 20.0);
+        assert_eq!(pricing[0].remaining(), 50.0);
+    }
+
+    #[test]
+    fn test_priority_and_stacking() {
+        let book = Product { category: Some("Books".to_string()), ..product("p1", 100.0) };
+        let phone = Product {
+            category: Some("Electronics".to_string()),
+            vendor_id: "v2".to_string(),
+            ..product("p2", 250.0)
+        };
+        let now = chrono::Utc::now().naive_utc();
+
+        // 商家出资的分类促销只适用于该商家的商品
+        let mut sale = promotion("s", PromotionKind::PercentageOff, 10);
+        sale.percentage = Some(20.0);
+        sale.categories = Some("books".to_string());
+        sale.funded_by = Some("v1".to_string());
+        let lines = [(&book, 1), (&phone, 1)];
+
+        // 每个订单项只享受优先级最高的商品级促销
+        let mut low = promotion("low", PromotionKind::PercentageOff, 1);
+        low.percentage = Some(50.0);
+        let pricing = apply_promotions(&[low.clone(), sale.clone()], &lines, now);
+        assert_eq!(ids(&pricing[0]), vec!["s"]);
+        assert_eq!(pricing[0].promotions[0].funded_by.as_deref(), Some("v1"));
+        assert_eq!(ids(&pricing[1]), vec!["low"]);
+
+        // 可叠加时满减按促销后的金额计算门槛：80 + 250 满 300
+        let pricing = apply_promotions(&[sale.clone(), tiered("t", 0)], &lines, now);
+        assert_eq!(ids(&pricing[0]), vec!["s", "t"]);
+        assert_eq!(ids(&pricing[1]), vec!["t"]);
+
+        // 不可叠加的促销商品不参与满减，剩余 250 未达门槛
+        sale.stackable = false;
+        let pricing = apply_promotions(&[sale, tiered("t", 0)], &lines, now);
+        assert_eq!(ids(&pricing[0]), vec!["s"]);
+        assert!(pricing[1].promotions.is_empty());
+        assert_eq!(pricing[0].remaining(), 0.0);
+    }
+
+    #[test]
+    fn test_allocate() {
+        assert_eq!(allocate(10.0, &[10.0, 0.0, 20.0]), vec![3.33, 0.0, 6.67]);
+        assert_eq!(allocate(50.0, &[10.0, 20.0]), vec![10.0, 20.0]);
+        assert_eq!(allocate(5.0, &[0.0]), vec![0.0]);
+    }
+}
@@ -0,0 +1,316 @@
+use diesel::prelude::*;
+use diesel::mysql::MysqlConnection;
+use std::env;
+
+use crate::models::order::{OrderStatus, ShippingAddressDto};
+use crate::models::risk::{
+    RiskDecision, RiskReason, RiskRule, RULE_ADDRESS_MISMATCH, RULE_FIRST_ORDER_VALUE, RULE_IP_VELOCITY,
+    RULE_NEW_ACCOUNT, RULE_ORDER_VALUE_VS_HISTORY, RULE_USER_VELOCITY,
+};
+use crate::schema::{addresses, order_risk_assessments, orders, risk_rules, users};
+
+// 默认的待审核分数线和拒绝分数线
+const DEFAULT_REVIEW_SCORE: i32 = 50;
+const DEFAULT_REJECT_SCORE: i32 = 80;
+
+// 风险分达到 review_score 时暂扣订单等待审核，达到 reject_score 时直接拒绝下单
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskThresholds {
+    pub review_score: i32,
+    pub reject_score: i32,
+}
+
+impl RiskThresholds {
+    // 从环境变量读取：RISK_REVIEW_SCORE（默认50）、RISK_REJECT_SCORE（默认80）
+    pub fn from_env() -> Self {
+        Self::from_values(
+            env::var("RISK_REVIEW_SCORE").ok().as_deref(),
+            env::var("RISK_REJECT_SCORE").ok().as_deref(),
+        )
+    }
+
+    fn from_values(review_score: Option<&str>, reject_score: Option<&str>) -> Self {
+        let parse = |value: Option<&str>, default: i32| {
+            value
+                .and_then(|value| value.trim().parse::<i32>().ok())
+                .filter(|score| *score > 0)
+                .unwrap_or(default)
+        };
+        let review_score = parse(review_score, DEFAULT_REVIEW_SCORE);
+        // 拒绝线不低于审核线
+        let reject_score = parse(reject_score, DEFAULT_REJECT_SCORE).max(review_score);
+
+        Self { review_score, reject_score }
+    }
+
+    pub fn decide(&self, score: i32) -> RiskDecision {
+        if score >= self.reject_score {
+            RiskDecision::Reject
+        } else if score >= self.review_score {
+            RiskDecision::Review
+        } else {
+            RiskDecision::Accept
+        }
+    }
+}
+
+// 风控规则使用的下单特征
+#[derive(Debug, Clone, Default)]
+pub struct CheckoutSignals {
+    pub order_total: f64,
+    // 账户注册至今的小时数
+    pub account_age_hours: f64,
+    // 此前未取消的订单数及其平均金额
+    pub previous_orders: i64,
+    pub average_order_total: Option<f64>,
+    // 最近一小时内该用户、该 IP 的下单次数（含被拒绝的下单）
+    pub user_orders_last_hour: i64,
+    pub ip_orders_last_hour: Option<i64>,
+    // 收货地址是否与地址簿中的地址一致，地址簿为空时为 None
+    pub address_matches_saved: Option<bool>,
+}
+
+// 风控评估结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskOutcome {
+    pub score: i32,
+    pub decision: RiskDecision,
+    pub reasons: Vec<RiskReason>,
+}
+
+// 依次检查已启用的规则，累加命中规则的分值后得出结论
+pub fn evaluate(rules: &[RiskRule], signals: &CheckoutSignals, thresholds: &RiskThresholds) -> RiskOutcome {
+    let mut reasons = Vec::new();
+
+    for rule in rules.iter().filter(|rule| rule.enabled) {
+        let detail = match rule.rule.as_str() {
+            RULE_NEW_ACCOUNT if signals.account_age_hours < rule.threshold => Some(format!(
+                "账户注册 {:.1} 小时，不满 {} 小时",
+                signals.account_age_hours, rule.threshold
+            )),
+            RULE_FIRST_ORDER_VALUE if signals.previous_orders == 0 && signals.order_total > rule.threshold => Some(format!(
+                "首单金额 {:.2} 超过 {:.2}",
+                signals.order_total, rule.threshold
+            )),
+            RULE_ORDER_VALUE_VS_HISTORY => match signals.average_order_total {
+                Some(average) if signals.previous_orders > 0 && signals.order_total > average * rule.threshold => Some(format!(
+                    "订单金额 {:.2} 超过历史平均金额 {:.2} 的 {} 倍",
+                    signals.order_total, average, rule.threshold
+                )),
+                _ => None,
+            },
+            RULE_USER_VELOCITY if (signals.user_orders_last_hour + 1) as f64 > rule.threshold => Some(format!(
+                "该用户一小时内已下单 {} 次",
+                signals.user_orders_last_hour
+            )),
+            RULE_IP_VELOCITY => match signals.ip_orders_last_hour {
+                Some(count) if (count + 1) as f64 > rule.threshold => Some(format!("该 IP 一小时内已下单 {} 次", count)),
+                _ => None,
+            },
+            RULE_ADDRESS_MISMATCH if signals.address_matches_saved == Some(false) => {
+                Some("收货人和电话与地址簿中的地址均不一致".to_string())
+            }
+            _ => None,
+        };
+
+        if let Some(detail) = detail {
+            reasons.push(RiskReason {
+                rule: rule.rule.clone(),
+                score: rule.score,
+                detail,
+            });
+        }
+    }
+
+    let score = reasons.iter().map(|reason| reason.score).sum();
+    RiskOutcome {
+        score,
+        decision: thresholds.decide(score),
+        reasons,
+    }
+}
+
+fn normalize_phone(phone: &str) -> String {
+    phone.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+// 读取下单特征
+fn collect_signals(
+    conn: &mut MysqlConnection,
+    user_id: &str,
+    client_ip: Option<&str>,
+    order_total: f64,
+    shipping_address: &ShippingAddressDto,
+) -> QueryResult<CheckoutSignals> {
+    let now = chrono::Utc::now().naive_utc();
+    let one_hour_ago = now - chrono::Duration::hours(1);
+
+    let registered_at = users::table
+        .find(user_id)
+        .select(users::created_at)
+        .first::<chrono::NaiveDateTime>(conn)?;
+    let account_age_hours = (now - registered_at).num_minutes().max(0) as f64 / 60.0;
+
+    let (previous_orders, average_order_total) = orders::table
+        .filter(orders::user_id.eq(user_id))
+        .filter(orders::status.ne(OrderStatus::Cancelled.to_string()))
+        .select((diesel::dsl::count_star(), diesel::dsl::avg(orders::total)))
+        .first::<(i64, Option<f64>)>(conn)?;
+
+    let user_orders_last_hour = order_risk_assessments::table
+        .filter(order_risk_assessments::user_id.eq(user_id))
+        .filter(order_risk_assessments::created_at.gt(one_hour_ago))
+        .count()
+        .get_result::<i64>(conn)?;
+
+    let ip_orders_last_hour = match client_ip {
+        Some(ip) => Some(order_risk_assessments::table
+            .filter(order_risk_assessments::client_ip.eq(ip))
+            .filter(order_risk_assessments::created_at.gt(one_hour_ago))
+            .count()
+            .get_result::<i64>(conn)?),
+        None => None,
+    };
+
+    let saved_contacts = addresses::table
+        .filter(addresses::user_id.eq(user_id))
+        .select((addresses::recipient_name, addresses::phone))
+        .load::<(String, String)>(conn)?;
+    let address_matches_saved = if saved_contacts.is_empty() {
+        None
+    } else {
+        let recipient_name = shipping_address.recipient_name.trim();
+        let phone = normalize_phone(&shipping_address.phone);
+        Some(saved_contacts.iter().any(|(saved_name, saved_phone)| {
+            saved_name.trim() == recipient_name && normalize_phone(saved_phone) == phone
+        }))
+    };
+
+    Ok(CheckoutSignals {
+        order_total,
+        account_age_hours,
+        previous_orders,
+        average_order_total,
+        user_orders_last_hour,
+        ip_orders_last_hour,
+        address_matches_saved,
+    })
+}
+
+// 结账风控：读取规则和下单特征并评估
+pub fn screen_checkout(
+    conn: &mut MysqlConnection,
+    user_id: &str,
+    client_ip: Option<&str>,
+    order_total: f64,
+    shipping_address: &ShippingAddressDto,
+) -> QueryResult<RiskOutcome> {
+    let rules = risk_rules::table
+        .filter(risk_rules::enabled.eq(true))
+        .select(RiskRule::as_select())
+        .load(conn)?;
+    if rules.is_empty() {
+        return Ok(RiskOutcome {
+            score: 0,
+            decision: RiskDecision::Accept,
+            reasons: Vec::new(),
+        });
+    }
+
+    let signals = collect_signals(conn, user_id, client_ip, order_total, shipping_address)?;
+    Ok(evaluate(&rules, &signals, &RiskThresholds::from_env()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(code: &str, threshold: f64, score: i32) -> RiskRule {
+        let now = chrono::Utc::now().naive_utc();
+        RiskRule {
+            id: code.to_string(),
+            rule: code.to_string(),
+            enabled: true,
+            threshold,
+            score,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn default_rules() -> Vec<RiskRule> {
+        vec![
+            rule(RULE_NEW_ACCOUNT, 24.0, 20),
+            rule(RULE_FIRST_ORDER_VALUE, 3000.0, 30),
+            rule(RULE_ORDER_VALUE_VS_HISTORY, 5.0, 30),
+            rule(RULE_USER_VELOCITY, 3.0, 40),
+            rule(RULE_IP_VELOCITY, 5.0, 40),
+            rule(RULE_ADDRESS_MISMATCH, 0.0, 20),
+        ]
+    }
+
+    fn thresholds() -> RiskThresholds {
+        RiskThresholds::from_values(None, None)
+    }
+
+    #[test]
+    fn test_regular_customer_is_accepted() {
+        let signals = CheckoutSignals {
+            order_total: 300.0,
+            account_age_hours: 24.0 * 90.0,
+            previous_orders: 6,
+            average_order_total: Some(250.0),
+            user_orders_last_hour: 0,
+            ip_orders_last_hour: Some(1),
+            address_matches_saved: Some(true),
+        };
+        let outcome = evaluate(&default_rules(), &signals, &thresholds());
+        assert_eq!(outcome.score, 0);
+        assert_eq!(outcome.decision, RiskDecision::Accept);
+    }
+
+    #[test]
+    fn test_bulk_order_from_fresh_account_is_held_or_rejected() {
+        let signals = CheckoutSignals {
+            order_total: 8000.0,
+            account_age_hours: 0.5,
+            previous_orders: 0,
+            average_order_total: None,
+            user_orders_last_hour: 0,
+            ip_orders_last_hour: Some(0),
+            address_matches_saved: None,
+        };
+        let outcome = evaluate(&default_rules(), &signals, &thresholds());
+        assert_eq!(outcome.score, 50);
+        assert_eq!(outcome.decision, RiskDecision::Review);
+        let rules: Vec<&str> = outcome.reasons.iter().map(|reason| reason.rule.as_str()).collect();
+        assert_eq!(rules, vec![RULE_NEW_ACCOUNT, RULE_FIRST_ORDER_VALUE]);
+
+        // 同一 IP 短时间内反复下单时直接拒绝
+        let signals = CheckoutSignals { ip_orders_last_hour: Some(5), ..signals };
+        let outcome = evaluate(&default_rules(), &signals, &thresholds());
+        assert_eq!(outcome.score, 90);
+        assert_eq!(outcome.decision, RiskDecision::Reject);
+    }
+
+    #[test]
+    fn test_disabled_rules_are_skipped() {
+        let mut rules = default_rules();
+        rules.iter_mut().for_each(|rule| rule.enabled = rule.rule != RULE_NEW_ACCOUNT);
+        let signals = CheckoutSignals {
+            order_total: 100.0,
+            account_age_hours: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(evaluate(&rules, &signals, &thresholds()).score, 0);
+    }
+
+    #[test]
+    fn test_thresholds_from_values() {
+        assert_eq!(thresholds(), RiskThresholds { review_score: 50, reject_score: 80 });
+        let thresholds = RiskThresholds::from_values(Some("60"), Some("40"));
+        assert_eq!(thresholds, RiskThresholds { review_score: 60, reject_score: 60 });
+        assert_eq!(thresholds.decide(59), RiskDecision::Accept);
+        assert_eq!(thresholds.decide(60), RiskDecision::Reject);
+    }
+}
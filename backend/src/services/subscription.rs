@@ -0,0 +1,287 @@
+use diesel::prelude::*;
+use diesel::mysql::MysqlConnection;
+use diesel::r2d2::{ConnectionManager, Pool};
+use std::env;
+use std::time::Duration;
+
+use crate::handlers::checkout::{place_subscription_order, CheckoutError};
+use crate::models::notification::{
+    NewNotification, NOTIFICATION_SUBSCRIPTION_ORDER_CREATED, NOTIFICATION_SUBSCRIPTION_RUN_FAILED,
+};
+use crate::models::order::{Order, OrderItemDto};
+use crate::models::subscription::{
+    NewSubscriptionRun, Subscription, SubscriptionItem, SubscriptionRunStatus, SubscriptionStatus,
+};
+use crate::schema::{notifications, subscription_runs, subscriptions};
+
+type DbPool = Pool<ConnectionManager<MysqlConnection>>;
+
+// 默认扫描间隔（秒）
+const DEFAULT_INTERVAL_SECONDS: u64 = 300;
+// 每次认领的到期订购数
+const BATCH_SIZE: i64 = 50;
+
+// 定期订购任务的配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubscriptionConfig {
+    // 扫描间隔
+    pub interval: Duration,
+}
+
+impl SubscriptionConfig {
+    // 从环境变量读取：SUBSCRIPTION_INTERVAL_SECONDS（默认300）
+    pub fn from_env() -> Self {
+        Self::from_values(env::var("SUBSCRIPTION_INTERVAL_SECONDS").ok().as_deref())
+    }
+
+    fn from_values(interval_seconds: Option<&str>) -> Self {
+        let interval_seconds = interval_seconds
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .filter(|seconds| *seconds > 0)
+            .unwrap_or(DEFAULT_INTERVAL_SECONDS);
+
+        Self {
+            interval: Duration::from_secs(interval_seconds),
+        }
+    }
+}
+
+// 认领一批到期的订购：候选订购通过 SELECT ... FOR UPDATE SKIP LOCKED 加行锁，并在同一事务中推进到下一周期，
+// 多个实例同时扫描时同一周期只会被一个实例认领，返回认领的订购（next_run_at 仍为本次的计划时间）
+fn claim_due_subscriptions(conn: &mut MysqlConnection, limit: i64) -> QueryResult<Vec<Subscription>> {
+    let now = chrono::Utc::now().naive_utc();
+
+    conn.transaction(|conn| {
+        let due: Vec<Subscription> = subscriptions::table
+            .filter(subscriptions::status.eq(SubscriptionStatus::Active.to_string()))
+            .filter(subscriptions::next_run_at.le(now))
+            .order(subscriptions::next_run_at.asc())
+            .limit(limit)
+            .select(Subscription::as_select())
+            .for_update()
+            .skip_locked()
+            .load(conn)?;
+
+        for subscription in &due {
+            let next_run_at = subscription.next_run_after(now);
+            diesel::update(subscriptions::table.find(&subscription.id))
+                .set((
+                    subscriptions::next_run_at.eq(next_run_at),
+                    subscriptions::updated_at.eq(now),
+                ))
+                .execute(conn)?;
+        }
+
+        Ok(due)
+    })
+}
+
+// 本期执行记录：成功时关联生成的订单，失败时记录原因
+fn run_record(subscription: &Subscription, result: &Result<Order, CheckoutError>) -> NewSubscriptionRun {
+    match result {
+        Ok(order) => NewSubscriptionRun::new(
+            subscription.id.clone(),
+            subscription.next_run_at,
+            SubscriptionRunStatus::Succeeded,
+            Some(order.id.clone()),
+            None,
+        ),
+        Err(reason) => NewSubscriptionRun::new(
+            subscription.id.clone(),
+            subscription.next_run_at,
+            SubscriptionRunStatus::Failed,
+            None,
+            Some(reason.to_string()),
+        ),
+    }
+}
+
+// 执行一次订购：按商品清单下单，记录结果并通知用户；下单失败不影响后续周期
+pub fn run_subscription(conn: &mut MysqlConnection, subscription: &Subscription) -> QueryResult<SubscriptionRunStatus> {
+    let items: Vec<OrderItemDto> = SubscriptionItem::belonging_to(subscription)
+        .select(SubscriptionItem::as_select())
+        .load(conn)?
+        .into_iter()
+        .map(|item| OrderItemDto {
+            product_id: item.product_id,
+            quantity: item.quantity,
+        })
+        .collect();
+
+    let result = place_subscription_order(conn, &subscription.user_id, items, subscription.address_id.clone());
+    let run = run_record(subscription, &result);
+    let now = chrono::Utc::now().naive_utc();
+
+    conn.transaction(|conn| {
+        diesel::insert_into(subscription_runs::table)
+            .values(&run)
+            .execute(conn)?;
+
+        match &result {
+            Ok(order) => {
+                diesel::update(subscriptions::table.find(&subscription.id))
+                    .set((
+                        subscriptions::last_run_at.eq(now),
+                        subscriptions::last_order_id.eq(&order.id),
+                        subscriptions::consecutive_failures.eq(0),
+                        subscriptions::updated_at.eq(now),
+                    ))
+                    .execute(conn)?;
+
+                diesel::insert_into(notifications::table)
+                    .values(&NewNotification::new(
+                        subscription.user_id.clone(),
+                        NOTIFICATION_SUBSCRIPTION_ORDER_CREATED,
+                        "定期订购已下单".to_string(),
+                        format!("定期订购已生成订单 {}，请及时完成支付", order.order_number),
+                        Some(subscription.id.clone()),
+                    ))
+                    .execute(conn)?;
+
+                Ok(SubscriptionRunStatus::Succeeded)
+            }
+            Err(reason) => {
+                println!("定期订购 {} 下单失败: {}", subscription.id, reason);
+                diesel::update(subscriptions::table.find(&subscription.id))
+                    .set((
+                        subscriptions::last_run_at.eq(now),
+                        subscriptions::consecutive_failures.eq(subscriptions::consecutive_failures + 1),
+                        subscriptions::updated_at.eq(now),
+                    ))
+                    .execute(conn)?;
+
+                diesel::insert_into(notifications::table)
+                    .values(&NewNotification::new(
+                        subscription.user_id.clone(),
+                        NOTIFICATION_SUBSCRIPTION_RUN_FAILED,
+                        "定期订购下单失败".to_string(),
+                        format!("本期定期订购未能下单：{}", reason),
+                        Some(subscription.id.clone()),
+                    ))
+                    .execute(conn)?;
+
+                Ok(SubscriptionRunStatus::Failed)
+            }
+        }
+    })
+}
+
+// 分批处理所有到期的订购，返回（成功数，失败数）
+fn run_once(pool: &DbPool) -> Result<(usize, usize), String> {
+    let mut conn = pool.get().map_err(|e| e.to_string())?;
+    let mut succeeded = 0;
+    let mut failed = 0;
+
+    loop {
+        let batch = claim_due_subscriptions(&mut conn, BATCH_SIZE).map_err(|e| e.to_string())?;
+        for subscription in &batch {
+            match run_subscription(&mut conn, subscription) {
+                Ok(SubscriptionRunStatus::Succeeded) => succeeded += 1,
+                Ok(_) => failed += 1,
+                Err(e) => {
+                    println!("记录定期订购 {} 执行结果失败: {:?}", subscription.id, e);
+                    failed += 1;
+                }
+            }
+        }
+        if (batch.len() as i64) < BATCH_SIZE {
+            return Ok((succeeded, failed));
+        }
+    }
+}
+
+// 在服务器内启动后台任务，按配置的间隔处理到期的定期订购
+pub fn spawn(pool: DbPool, config: SubscriptionConfig) {
+    println!("启动定期订购任务：每 {} 秒扫描一次", config.interval.as_secs());
+
+    actix_web::rt::spawn(async move {
+        let mut ticker = actix_web::rt::time::interval(config.interval);
+        loop {
+            ticker.tick().await;
+
+            let pool = pool.clone();
+            // 数据库操作是阻塞调用，放到线程池中执行
+            match actix_web::web::block(move || run_once(&pool)).await {
+                Ok(Ok((0, 0))) => {}
+                Ok(Ok((succeeded, failed))) => println!("定期订购已下单 {} 个，失败 {} 个", succeeded, failed),
+                Ok(Err(e)) => println!("处理定期订购失败: {}", e),
+                Err(e) => println!("定期订购任务异常: {:?}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::inventory::StockError;
+
+    #[test]
+    fn test_config_from_values() {
+        assert_eq!(SubscriptionConfig::from_values(None).interval, Duration::from_secs(300));
+        assert_eq!(SubscriptionConfig::from_values(Some("60")).interval, Duration::from_secs(60));
+        assert_eq!(SubscriptionConfig::from_values(Some("0")).interval, Duration::from_secs(300));
+    }
+
+    fn subscription() -> Subscription {
+        let now = chrono::Utc::now().naive_utc();
+        Subscription {
+            id: "sub-1".to_string(),
+            user_id: "user-1".to_string(),
+            frequency: "monthly".to_string(),
+            status: "active".to_string(),
+            address_id: None,
+            next_run_at: now,
+            anchor_day: 1,
+            last_run_at: None,
+            last_order_id: None,
+            consecutive_failures: 0,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_run_record_links_created_order() {
+        let subscription = subscription();
+        let now = chrono::Utc::now().naive_utc();
+        let order = Order {
+            id: "order-1".to_string(),
+            order_number: "ORD-1".to_string(),
+            user_id: subscription.user_id.clone(),
+            subtotal: 100.0,
+            tax: 13.0,
+            total: 113.0,
+            status: "pending".to_string(),
+            created_at: now,
+            updated_at: now,
+            contact_email: None,
+            on_hold: false,
+            wallet_amount: 0.0,
+            discount: 0.0,
+            coupon_code: None,
+        };
+
+        let run = run_record(&subscription, &Ok(order));
+        assert_eq!(run.subscription_id, "sub-1");
+        assert_eq!(run.scheduled_for, subscription.next_run_at);
+        assert_eq!(run.status, SubscriptionRunStatus::Succeeded.to_string());
+        assert_eq!(run.order_id.as_deref(), Some("order-1"));
+        assert_eq!(run.failure_reason, None);
+    }
+
+    #[test]
+    fn test_run_record_keeps_failure_reason() {
+        let result = Err(CheckoutError::Unavailable(vec![StockError::OutOfStock {
+            product_id: "p-1".to_string(),
+            product_name: Some("咖啡豆".to_string()),
+            requested: 2,
+            available: 0,
+        }]));
+
+        let run = run_record(&subscription(), &result);
+        assert_eq!(run.status, SubscriptionRunStatus::Failed.to_string());
+        assert_eq!(run.order_id, None);
+        assert_eq!(run.failure_reason.as_deref(), Some("部分产品库存不足或不可用：咖啡豆"));
+    }
+}
@@ -0,0 +1,152 @@
+use diesel::prelude::*;
+use diesel::mysql::MysqlConnection;
+use std::collections::HashMap;
+
+use crate::schema::{admin_profiles, tax_rates};
+
+// 未配置管理员设置时使用的默认税率（百分比）
+pub const DEFAULT_TAX_RATE: f64 = 13.0;
+
+// 金额保留两位小数
+pub fn round_currency(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+// 单个订单项的计税结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxLine {
+    // 适用税率（百分比）
+    pub rate: f64,
+    // 不含税金额
+    pub net: f64,
+    pub tax: f64,
+    // 含税金额
+    pub gross: f64,
+}
+
+// 订单计税汇总
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaxSummary {
+    pub subtotal: f64,
+    pub tax: f64,
+    pub total: f64,
+}
+
+impl TaxSummary {
+    pub fn add(&mut self, line: &TaxLine) {
+        self.subtotal = round_currency(self.subtotal + line.net);
+        self.tax = round_currency(self.tax + line.tax);
+        self.total = round_currency(self.total + line.gross);
+    }
+}
+
+// 税务设置：默认税率、商品价格是否含税、按商品分类的税率
+#[derive(Debug, Clone)]
+pub struct TaxSettings {
+    pub default_rate: f64,
+    pub prices_include_tax: bool,
+    pub category_rates: HashMap<String, f64>,
+}
+
+impl TaxSettings {
+    // 从管理员设置和分类税率表读取
+    pub fn load(conn: &mut MysqlConnection) -> QueryResult<Self> {
+        let settings = admin_profiles::table
+            .order(admin_profiles::created_at.asc())
+            .select((admin_profiles::tax_rate, admin_profiles::prices_include_tax))
+            .first::<(f64, bool)>(conn)
+            .optional()?;
+
+        let (default_rate, prices_include_tax) = settings.unwrap_or((DEFAULT_TAX_RATE, false));
+
+        let category_rates = tax_rates::table
+            .select((tax_rates::category, tax_rates::rate))
+            .load::<(String, f64)>(conn)?
+            .into_iter()
+            .map(|(category, rate)| (category.to_lowercase(), rate))
+            .collect();
+
+        Ok(Self {
+            default_rate,
+            prices_include_tax,
+            category_rates,
+        })
+    }
+
+    // 商品适用的税率，分类未单独配置时使用默认税率
+    pub fn rate_for(&self, category: Option<&str>) -> f64 {
+        category
+            .and_then(|category| self.category_rates.get(&category.trim().to_lowercase()))
+            .copied()
+            .unwrap_or(self.default_rate)
+            .max(0.0)
+    }
+
+    // 计算单个订单项的税额，逐行四舍五入到分；按扣除优惠后的金额计税，优惠金额与标价同样按是否含税计算
+    pub fn compute_line(&self, unit_price: f64, quantity: i32, discount: f64, category: Option<&str>) -> TaxLine {
+        let rate = self.rate_for(category);
+        let amount = round_currency((round_currency(unit_price * quantity as f64) - discount).max(0.0));
+
+        if self.prices_include_tax {
+            let net = round_currency(amount / (1.0 + rate / 100.0));
+            TaxLine { rate, net, tax: round_currency(amount - net), gross: amount }
+        } else {
+            let tax = round_currency(amount * rate / 100.0);
+            TaxLine { rate, net: amount, tax, gross: round_currency(amount + tax) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(prices_include_tax: bool) -> TaxSettings {
+        let mut category_rates = HashMap::new();
+        category_rates.insert("books".to_string(), 9.0);
+        category_rates.insert("groceries & gourmet food".to_string(), 0.0);
+        TaxSettings {
+            default_rate: 13.0,
+            prices_include_tax,
+            category_rates,
+        }
+    }
+
+    #[test]
+    fn test_tax_exclusive_line() {
+        let line = settings(false).compute_line(50.0, 2, 0.0, Some("Electronics"));
+        assert_eq!(line, TaxLine { rate: 13.0, net: 100.0, tax: 13.0, gross: 113.0 });
+    }
+
+    #[test]
+    fn test_tax_inclusive_line() {
+        let line = settings(true).compute_line(56.5, 2, 0.0, None);
+        assert_eq!(line, TaxLine { rate: 13.0, net: 100.0, tax: 13.0, gross: 113.0 });
+    }
+
+    #[test]
+    fn test_category_rates() {
+        let settings = settings(false);
+        assert_eq!(settings.rate_for(Some("Books")), 9.0);
+        assert_eq!(settings.rate_for(Some("Groceries & Gourmet Food")), 0.0);
+        assert_eq!(settings.rate_for(Some("Toys & Games")), 13.0);
+        assert_eq!(settings.compute_line(10.0, 1, 0.0, Some("books")).gross, 10.9);
+    }
+
+    #[test]
+    fn test_discounted_line() {
+        let line = settings(false).compute_line(50.0, 2, 20.0, None);
+        assert_eq!(line, TaxLine { rate: 13.0, net: 80.0, tax: 10.4, gross: 90.4 });
+        let line = settings(true).compute_line(56.5, 2, 13.0, None);
+        assert_eq!(line, TaxLine { rate: 13.0, net: 88.5, tax: 11.5, gross: 100.0 });
+    }
+
+    #[test]
+    fn test_summary_adds_rounded_lines() {
+        let settings = settings(false);
+        let mut summary = TaxSummary::default();
+        summary.add(&settings.compute_line(0.99, 3, 0.0, None));
+        summary.add(&settings.compute_line(10.0, 1, 0.0, Some("books")));
+        assert_eq!(summary, TaxSummary { subtotal: 12.97, tax: 1.29, total: 14.26 });
+    }
+}
@@ -0,0 +1,163 @@
+use diesel::prelude::*;
+use diesel::mysql::MysqlConnection;
+use diesel::result::Error as DieselError;
+use uuid::Uuid;
+
+use crate::models::wallet::{NewWalletTransaction, WalletTransaction, WalletTransactionKind};
+use crate::schema::{orders, wallets, wallet_transactions};
+use crate::services::tax::round_currency;
+
+// 订单由钱包余额付清时写入订单状态记录的原因
+pub const WALLET_PAID_REASON: &str = "钱包余额支付";
+
+#[derive(Debug)]
+pub enum WalletError {
+    Database(DieselError),
+    // 余额不足，附带当前余额
+    InsufficientBalance(f64),
+}
+
+impl From<DieselError> for WalletError {
+    fn from(error: DieselError) -> Self {
+        WalletError::Database(error)
+    }
+}
+
+// 记账后的余额，按分取整，余额不能为负
+fn apply_amount(balance: f64, amount: f64) -> Result<f64, WalletError> {
+    let balance_after = round_currency(balance + amount);
+    if balance_after < 0.0 {
+        return Err(WalletError::InsufficientBalance(round_currency(balance)));
+    }
+    Ok(balance_after)
+}
+
+// 下单时实际使用的钱包金额：不能超过余额，超过订单金额时只扣订单金额
+pub fn wallet_portion(requested: f64, balance: f64, total: f64) -> Result<f64, WalletError> {
+    let requested = round_currency(requested);
+    if requested > round_currency(balance) {
+        return Err(WalletError::InsufficientBalance(round_currency(balance)));
+    }
+    Ok(requested.min(round_currency(total)).max(0.0))
+}
+
+// 当前余额，尚未开通钱包的用户余额为0
+pub fn balance(conn: &mut MysqlConnection, user_id: &str) -> QueryResult<f64> {
+    Ok(wallets::table
+        .find(user_id)
+        .select(wallets::balance)
+        .first::<f64>(conn)
+        .optional()?
+        .unwrap_or(0.0))
+}
+
+// 记一笔钱包流水并更新余额，必须在事务中调用；
+// 钱包行加锁后再计算余额，并发记账按顺序执行，扣减后余额为负时返回 InsufficientBalance
+pub fn post_transaction(
+    conn: &mut MysqlConnection,
+    user_id: &str,
+    kind: WalletTransactionKind,
+    amount: f64,
+    reason: Option<&str>,
+    order_id: Option<&str>,
+    created_by: Option<&str>,
+) -> Result<WalletTransaction, WalletError> {
+    let now = chrono::Utc::now().naive_utc();
+
+    // 首次记账时开通钱包
+    diesel::insert_or_ignore_into(wallets::table)
+        .values((
+            wallets::user_id.eq(user_id),
+            wallets::balance.eq(0.0),
+            wallets::created_at.eq(now),
+            wallets::updated_at.eq(now),
+        ))
+        .execute(conn)?;
+
+    let balance = wallets::table
+        .find(user_id)
+        .select(wallets::balance)
+        .for_update()
+        .first::<f64>(conn)?;
+    let balance_after = apply_amount(balance, amount)?;
+
+    diesel::update(wallets::table.find(user_id))
+        .set((
+            wallets::balance.eq(balance_after),
+            wallets::updated_at.eq(now),
+        ))
+        .execute(conn)?;
+
+    let transaction = NewWalletTransaction {
+        id: Uuid::new_v4().to_string(),
+        user_id: user_id.to_string(),
+        kind: kind.to_string(),
+        amount: round_currency(amount),
+        balance_after,
+        reason: reason.map(str::to_string),
+        order_id: order_id.map(str::to_string),
+        created_by: created_by.map(str::to_string),
+        created_at: now,
+    };
+    diesel::insert_into(wallet_transactions::table)
+        .values(&transaction)
+        .execute(conn)?;
+
+    Ok(wallet_transactions::table
+        .find(&transaction.id)
+        .select(WalletTransaction::as_select())
+        .first(conn)?)
+}
+
+// 入账，只会因数据库错误失败
+pub fn credit(
+    conn: &mut MysqlConnection,
+    user_id: &str,
+    kind: WalletTransactionKind,
+    amount: f64,
+    reason: Option<&str>,
+    order_id: Option<&str>,
+    created_by: Option<&str>,
+) -> QueryResult<WalletTransaction> {
+    match post_transaction(conn, user_id, kind, amount.abs(), reason, order_id, created_by) {
+        Ok(transaction) => Ok(transaction),
+        Err(WalletError::Database(e)) => Err(e),
+        Err(WalletError::InsufficientBalance(_)) => Err(DieselError::RollbackTransaction),
+    }
+}
+
+// 订单取消时把下单抵扣的钱包金额退回钱包，必须在事务中调用
+pub fn refund_order_wallet_amounts(conn: &mut MysqlConnection, order_ids: &[String], reason: &str) -> QueryResult<()> {
+    let paid: Vec<(String, String, f64)> = orders::table
+        .filter(orders::id.eq_any(order_ids))
+        .filter(orders::wallet_amount.gt(0.0))
+        .select((orders::id, orders::user_id, orders::wallet_amount))
+        .load(conn)?;
+
+    for (order_id, user_id, amount) in paid {
+        credit(conn, &user_id, WalletTransactionKind::OrderRefund, amount, Some(reason), Some(&order_id), None)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_amount() {
+        assert_eq!(apply_amount(10.0, 5.5).unwrap(), 15.5);
+        assert_eq!(apply_amount(10.0, -10.0).unwrap(), 0.0);
+        assert_eq!(apply_amount(0.1, 0.2).unwrap(), 0.3);
+        assert!(matches!(apply_amount(10.0, -10.01), Err(WalletError::InsufficientBalance(balance)) if balance == 10.0));
+    }
+
+    #[test]
+    fn test_wallet_portion() {
+        // 部分抵扣
+        assert_eq!(wallet_portion(30.0, 50.0, 100.0).unwrap(), 30.0);
+        // 超过订单金额时只扣订单金额
+        assert_eq!(wallet_portion(50.0, 50.0, 20.0).unwrap(), 20.0);
+        assert!(matches!(wallet_portion(60.0, 50.0, 100.0), Err(WalletError::InsufficientBalance(_))));
+    }
+}
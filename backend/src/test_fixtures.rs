@@ -0,0 +1,73 @@
+// 单元测试共用的测试数据，字段取最常用的默认值，测试中按需修改
+use crate::models::coupon::{Coupon, DiscountType};
+use crate::models::product::{BackorderMode, Product};
+use crate::models::promotion::{Promotion, PromotionKind};
+
+// 商家 v1 的商品：库存 10，无分类，不接受缺货预订
+pub fn product(id: &str, price: f64) -> Product {
+    let now = chrono::Utc::now().naive_utc();
+    Product {
+        id: id.to_string(),
+        name: format!("商品{}", id),
+        description: String::new(),
+        price,
+        vendor_id: "v1".to_string(),
+        stock: 10,
+        created_at: now,
+        updated_at: now,
+        category: None,
+        backorder_mode: BackorderMode::None.to_string(),
+        backorder_limit: None,
+        expected_ship_date: None,
+    }
+}
+
+// 已启用的优惠券：适用全部商品，无门槛、无有效期和使用次数限制
+pub fn coupon(discount_type: DiscountType, discount_value: f64) -> Coupon {
+    let now = chrono::Utc::now().naive_utc();
+    Coupon {
+        id: "c-1".to_string(),
+        code: "SAVE10".to_string(),
+        discount_type: discount_type.to_string(),
+        discount_value,
+        min_spend: 0.0,
+        starts_at: None,
+        ends_at: None,
+        usage_limit: None,
+        per_user_limit: None,
+        used_count: 0,
+        product_ids: None,
+        categories: None,
+        vendor_ids: None,
+        active: true,
+        created_by: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+// 已启用、可叠加、适用全部商品的促销，优惠内容由测试按类型设置
+pub fn promotion(id: &str, kind: PromotionKind, priority: i32) -> Promotion {
+    let now = chrono::Utc::now().naive_utc();
+    Promotion {
+        id: id.to_string(),
+        name: format!("促销{}", id),
+        kind: kind.to_string(),
+        priority,
+        stackable: true,
+        tiers: None,
+        buy_quantity: None,
+        free_quantity: None,
+        percentage: None,
+        product_ids: None,
+        categories: None,
+        vendor_ids: None,
+        funded_by: None,
+        starts_at: None,
+        ends_at: None,
+        active: true,
+        created_by: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
@@ -3,6 +3,9 @@ use lazy_static::lazy_static;
 
 lazy_static! {
     static ref EMAIL_REGEX: Regex = Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap();
+    // 11位手机号，或带区号的固定电话（如 010-12345678）
+    static ref PHONE_REGEX: Regex = Regex::new(r"^(1\d{10}|0\d{2,3}-?\d{7,8})$").unwrap();
+    static ref POSTAL_CODE_REGEX: Regex = Regex::new(r"^\d{6}$").unwrap();
 }
 
 /// 验证邮箱格式
@@ -10,6 +13,16 @@ pub fn is_valid_email(email: &str) -> bool {
     EMAIL_REGEX.is_match(email)
 }
 
+/// 验证电话号码格式
+pub fn is_valid_phone(phone: &str) -> bool {
+    PHONE_REGEX.is_match(phone)
+}
+
+/// 验证邮政编码格式（6位数字）
+pub fn is_valid_postal_code(postal_code: &str) -> bool {
+    POSTAL_CODE_REGEX.is_match(postal_code)
+}
+
 /// 验证密码强度
 /// 密码至少需要6个字符
 pub fn is_valid_password(password: &str) -> bool {
@@ -44,6 +57,42 @@ pub fn is_not_empty(value: &str) -> bool {
     !value.trim().is_empty()
 }
 
+/// 验证收货地址
+pub fn validate_shipping_address(
+    recipient_name: &str,
+    phone: &str,
+    street: &str,
+    postal_code: Option<&str>,
+) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    if !is_not_empty(recipient_name) {
+        errors.push("收货人不能为空".to_string());
+    }
+
+    if !is_not_empty(phone) {
+        errors.push("联系电话不能为空".to_string());
+    } else if !is_valid_phone(phone.trim()) {
+        errors.push("联系电话格式无效".to_string());
+    }
+
+    if !is_not_empty(street) {
+        errors.push("详细地址不能为空".to_string());
+    }
+
+    if let Some(code) = postal_code.filter(|code| is_not_empty(code)) {
+        if !is_valid_postal_code(code.trim()) {
+            errors.push("邮政编码格式无效".to_string());
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -75,4 +124,36 @@ mod tests {
         assert!(!is_valid_password("12345"));
         assert!(!is_valid_password("abcd"));
     }
+
+    #[test]
+    fn test_valid_phone() {
+        assert!(is_valid_phone("13800138000"));
+        assert!(is_valid_phone("010-12345678"));
+        assert!(is_valid_phone("075512345678"));
+    }
+
+    #[test]
+    fn test_invalid_phone() {
+        assert!(!is_valid_phone(""));
+        assert!(!is_valid_phone("1380013800"));
+        assert!(!is_valid_phone("23800138000"));
+        assert!(!is_valid_phone("138-0013-8000"));
+    }
+
+    #[test]
+    fn test_postal_code() {
+        assert!(is_valid_postal_code("100000"));
+        assert!(!is_valid_postal_code("10000"));
+        assert!(!is_valid_postal_code("10000a"));
+    }
+
+    #[test]
+    fn test_validate_shipping_address() {
+        assert!(validate_shipping_address("张三", "13800138000", "中山路1号", None).is_ok());
+        assert!(validate_shipping_address("张三", "13800138000", "中山路1号", Some("518000")).is_ok());
+        assert_eq!(validate_shipping_address(" ", "", "中山路1号", None).unwrap_err().len(), 2);
+        assert!(validate_shipping_address("张三", "12345", "中山路1号", None).is_err());
+        assert!(validate_shipping_address("张三", "13800138000", "  ", None).is_err());
+        assert!(validate_shipping_address("张三", "13800138000", "中山路1号", Some("51800")).is_err());
+    }
 }
\ No newline at end of file
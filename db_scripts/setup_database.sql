@@ -10,7 +10,8 @@ CREATE TABLE IF NOT EXISTS users (
     password_hash VARCHAR(255) NOT NULL,
     role VARCHAR(50) NOT NULL DEFAULT 'customer',
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    is_guest BOOLEAN NOT NULL DEFAULT FALSE
 );
 
 -- Products table
@@ -24,18 +25,61 @@ CREATE TABLE IF NOT EXISTS products (
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
     category VARCHAR(255),
+    backorder_mode VARCHAR(20) NOT NULL DEFAULT 'none',
+    backorder_limit INT NULL,
+    expected_ship_date DATE NULL,
     FOREIGN KEY (vendor_id) REFERENCES users(id)
 );
 
 -- Orders table
 CREATE TABLE IF NOT EXISTS orders (
     id VARCHAR(36) PRIMARY KEY,
+    order_number VARCHAR(50) NOT NULL UNIQUE,
     user_id VARCHAR(36) NOT NULL,
+    subtotal DOUBLE NOT NULL DEFAULT 0,
+    tax DOUBLE NOT NULL DEFAULT 0,
     total DOUBLE NOT NULL,
     status VARCHAR(50) NOT NULL DEFAULT 'pending',
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
-    FOREIGN KEY (user_id) REFERENCES users(id)
+    contact_email VARCHAR(255) NULL,
+    on_hold BOOLEAN NOT NULL DEFAULT FALSE,
+    wallet_amount DOUBLE NOT NULL DEFAULT 0,
+    discount DOUBLE NOT NULL DEFAULT 0,
+    coupon_code VARCHAR(50) NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    INDEX idx_orders_created_at_id (created_at, id),
+    INDEX idx_orders_total_id (total, id),
+    INDEX idx_orders_status_created_at (status, created_at)
+);
+
+-- Order number sequences table (last allocated sequence per day)
+CREATE TABLE IF NOT EXISTS order_number_sequences (
+    sequence_date DATE PRIMARY KEY,
+    last_value BIGINT NOT NULL
+);
+
+-- Order fulfillments table (per-vendor sub-orders)
+CREATE TABLE IF NOT EXISTS order_fulfillments (
+    id VARCHAR(36) PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    vendor_id VARCHAR(36) NOT NULL,
+    status VARCHAR(50) NOT NULL DEFAULT 'pending',
+    subtotal DOUBLE NOT NULL,
+    tax DOUBLE NOT NULL DEFAULT 0,
+    shipping_fee DOUBLE NOT NULL DEFAULT 0,
+    total DOUBLE NOT NULL,
+    shipped_at TIMESTAMP NULL,
+    delivered_at TIMESTAMP NULL,
+    return_window_ends_at TIMESTAMP NULL,
+    completed_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (vendor_id) REFERENCES users(id),
+    UNIQUE KEY order_vendor (order_id, vendor_id),
+    INDEX idx_fulfillments_status_shipped_at (status, shipped_at),
+    INDEX idx_fulfillments_status_return_window (status, return_window_ends_at)
 );
 
 -- Order items table
@@ -45,8 +89,193 @@ CREATE TABLE IF NOT EXISTS order_items (
     product_id VARCHAR(36) NOT NULL,
     quantity INT NOT NULL,
     price DOUBLE NOT NULL,
+    fulfillment_id VARCHAR(36),
+    tax_rate DOUBLE NOT NULL DEFAULT 0,
+    tax_amount DOUBLE NOT NULL DEFAULT 0,
+    total DOUBLE NOT NULL DEFAULT 0,
+    backordered_quantity INT NOT NULL DEFAULT 0,
+    expected_ship_date DATE NULL,
+    discount DOUBLE NOT NULL DEFAULT 0,
     FOREIGN KEY (order_id) REFERENCES orders(id),
-    FOREIGN KEY (product_id) REFERENCES products(id)
+    FOREIGN KEY (product_id) REFERENCES products(id),
+    FOREIGN KEY (fulfillment_id) REFERENCES order_fulfillments(id),
+    INDEX idx_order_items_product_backordered (product_id, backordered_quantity)
+);
+
+-- Order addresses table (immutable shipping address snapshot per order)
+CREATE TABLE IF NOT EXISTS order_addresses (
+    id VARCHAR(36) PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    recipient_name VARCHAR(100) NOT NULL,
+    phone VARCHAR(20) NOT NULL,
+    province VARCHAR(100) NOT NULL DEFAULT '',
+    city VARCHAR(100) NOT NULL DEFAULT '',
+    district VARCHAR(100) NOT NULL DEFAULT '',
+    street TEXT NOT NULL,
+    postal_code VARCHAR(20),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    UNIQUE KEY (order_id)
+);
+
+-- Shipments table (vendor parcels with carrier and tracking number)
+CREATE TABLE IF NOT EXISTS shipments (
+    id VARCHAR(36) PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    fulfillment_id VARCHAR(36) NOT NULL,
+    vendor_id VARCHAR(36) NOT NULL,
+    carrier VARCHAR(50) NOT NULL,
+    tracking_number VARCHAR(100) NOT NULL,
+    status VARCHAR(50) NOT NULL DEFAULT 'shipped',
+    shipped_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    delivered_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (fulfillment_id) REFERENCES order_fulfillments(id) ON DELETE CASCADE,
+    FOREIGN KEY (vendor_id) REFERENCES users(id),
+    UNIQUE KEY (carrier, tracking_number)
+);
+
+-- Shipment items table (order items and quantities contained in each shipment)
+CREATE TABLE IF NOT EXISTS shipment_items (
+    id VARCHAR(36) PRIMARY KEY,
+    shipment_id VARCHAR(36) NOT NULL,
+    order_item_id VARCHAR(36) NOT NULL,
+    quantity INT NOT NULL,
+    FOREIGN KEY (shipment_id) REFERENCES shipments(id) ON DELETE CASCADE,
+    FOREIGN KEY (order_item_id) REFERENCES order_items(id) ON DELETE CASCADE
+);
+
+-- Order status history table (changed_by is NULL for automatic changes)
+CREATE TABLE IF NOT EXISTS order_status_history (
+    id VARCHAR(36) PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    from_status VARCHAR(50) NULL,
+    to_status VARCHAR(50) NOT NULL,
+    reason VARCHAR(255) NULL,
+    changed_by VARCHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    INDEX (order_id, created_at)
+);
+
+-- Order messages table (per-order thread between customer, vendors and admins)
+CREATE TABLE IF NOT EXISTS order_messages (
+    id VARCHAR(36) PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    sender_id VARCHAR(36) NOT NULL,
+    sender_role VARCHAR(20) NOT NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMP(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (sender_id) REFERENCES users(id),
+    INDEX (order_id, created_at)
+);
+
+-- Order message attachments table (files are referenced by URL)
+CREATE TABLE IF NOT EXISTS order_message_attachments (
+    id VARCHAR(36) PRIMARY KEY,
+    message_id VARCHAR(36) NOT NULL,
+    url VARCHAR(1024) NOT NULL,
+    file_name VARCHAR(255) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (message_id) REFERENCES order_messages(id) ON DELETE CASCADE
+);
+
+-- Order message reads table (last read position per user and order)
+CREATE TABLE IF NOT EXISTS order_message_reads (
+    id VARCHAR(36) PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    user_id VARCHAR(36) NOT NULL,
+    last_read_at TIMESTAMP(6) NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE KEY (order_id, user_id)
+);
+
+-- Invoices table (at most one invoice per order)
+CREATE TABLE IF NOT EXISTS invoices (
+    id VARCHAR(36) PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL UNIQUE,
+    invoice_number VARCHAR(50) NOT NULL UNIQUE,
+    issued_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE
+);
+
+-- Invoice number sequences table (last allocated sequence per year)
+CREATE TABLE IF NOT EXISTS invoice_number_sequences (
+    sequence_year INT PRIMARY KEY,
+    last_value BIGINT NOT NULL
+);
+
+-- Tax rates table (per product category overrides of the admin tax rate, in percent)
+CREATE TABLE IF NOT EXISTS tax_rates (
+    id VARCHAR(36) PRIMARY KEY,
+    category VARCHAR(100) NOT NULL UNIQUE,
+    rate DOUBLE NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+);
+
+-- Risk rules table (checkout screening rules; threshold meaning depends on the rule)
+CREATE TABLE IF NOT EXISTS risk_rules (
+    id VARCHAR(36) PRIMARY KEY,
+    rule VARCHAR(50) NOT NULL UNIQUE,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    threshold DOUBLE NOT NULL,
+    score INT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+);
+
+INSERT INTO risk_rules (id, rule, enabled, threshold, score) VALUES
+(UUID(), 'new_account', TRUE, 24, 20),
+(UUID(), 'first_order_value', TRUE, 3000, 30),
+(UUID(), 'order_value_vs_history', TRUE, 5, 30),
+(UUID(), 'user_velocity', TRUE, 3, 40),
+(UUID(), 'ip_velocity', TRUE, 5, 40),
+(UUID(), 'address_mismatch', TRUE, 0, 20);
+
+-- Order risk assessments table (one per checkout attempt; order_id is NULL for rejected checkouts)
+CREATE TABLE IF NOT EXISTS order_risk_assessments (
+    id VARCHAR(36) PRIMARY KEY,
+    order_id VARCHAR(36) NULL UNIQUE,
+    user_id VARCHAR(36) NOT NULL,
+    client_ip VARCHAR(45) NULL,
+    order_total DOUBLE NOT NULL,
+    score INT NOT NULL,
+    decision VARCHAR(20) NOT NULL,
+    reasons TEXT NOT NULL,
+    review_status VARCHAR(20) NULL,
+    reviewed_by VARCHAR(36) NULL,
+    reviewed_at TIMESTAMP NULL,
+    review_note TEXT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (reviewed_by) REFERENCES users(id) ON DELETE SET NULL,
+    INDEX idx_risk_assessments_user_created_at (user_id, created_at),
+    INDEX idx_risk_assessments_ip_created_at (client_ip, created_at),
+    INDEX idx_risk_assessments_review_status (review_status, created_at)
+);
+
+-- Idempotency keys table (checkout request fingerprints and their responses)
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    endpoint VARCHAR(100) NOT NULL,
+    idempotency_key VARCHAR(255) NOT NULL,
+    request_fingerprint CHAR(64) NOT NULL,
+    order_id VARCHAR(36) NULL,
+    response_status INT NULL,
+    response_body MEDIUMTEXT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE SET NULL,
+    UNIQUE KEY user_endpoint_key (user_id, endpoint, idempotency_key)
 );
 
 -- Cart items table
@@ -89,6 +318,25 @@ CREATE TABLE IF NOT EXISTS user_profiles (
     UNIQUE KEY (user_id)
 );
 
+-- Addresses table (customer address book)
+CREATE TABLE IF NOT EXISTS addresses (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    recipient_name VARCHAR(100) NOT NULL,
+    phone VARCHAR(20) NOT NULL,
+    province VARCHAR(100) NOT NULL DEFAULT '',
+    city VARCHAR(100) NOT NULL DEFAULT '',
+    district VARCHAR(100) NOT NULL DEFAULT '',
+    street TEXT NOT NULL,
+    postal_code VARCHAR(20),
+    is_default_shipping BOOLEAN NOT NULL DEFAULT FALSE,
+    is_default_billing BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX (user_id)
+);
+
 -- Vendor profiles table
 CREATE TABLE IF NOT EXISTS vendor_profiles (
     id VARCHAR(36) PRIMARY KEY,
@@ -127,12 +375,224 @@ CREATE TABLE IF NOT EXISTS admin_profiles (
     tax_rate DOUBLE DEFAULT 13.0,
     payment_gateways TEXT,
     log_level VARCHAR(20) DEFAULT 'info',
+    prices_include_tax BOOLEAN NOT NULL DEFAULT FALSE,
     created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
     updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
     FOREIGN KEY (admin_id) REFERENCES users(id) ON DELETE CASCADE,
     UNIQUE KEY (admin_id)
 );
 
+-- Subscriptions table (recurring orders created from a saved item list every week or month)
+CREATE TABLE IF NOT EXISTS subscriptions (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    frequency VARCHAR(20) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'active',
+    address_id VARCHAR(36) NULL,
+    next_run_at TIMESTAMP NOT NULL,
+    anchor_day INT NOT NULL DEFAULT 1,
+    last_run_at TIMESTAMP NULL,
+    last_order_id VARCHAR(36) NULL,
+    consecutive_failures INT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    FOREIGN KEY (address_id) REFERENCES addresses(id) ON DELETE SET NULL,
+    INDEX idx_subscriptions_user_id (user_id),
+    INDEX idx_subscriptions_status_next_run (status, next_run_at)
+);
+
+-- Subscription items table (saved item list of each subscription)
+CREATE TABLE IF NOT EXISTS subscription_items (
+    id VARCHAR(36) PRIMARY KEY,
+    subscription_id VARCHAR(36) NOT NULL,
+    product_id VARCHAR(36) NOT NULL,
+    quantity INT NOT NULL,
+    FOREIGN KEY (subscription_id) REFERENCES subscriptions(id) ON DELETE CASCADE,
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE,
+    UNIQUE KEY subscription_product (subscription_id, product_id)
+);
+
+-- Subscription runs table (outcome of each scheduled run: succeeded, failed or skipped)
+CREATE TABLE IF NOT EXISTS subscription_runs (
+    id VARCHAR(36) PRIMARY KEY,
+    subscription_id VARCHAR(36) NOT NULL,
+    scheduled_for TIMESTAMP NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    order_id VARCHAR(36) NULL,
+    failure_reason TEXT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (subscription_id) REFERENCES subscriptions(id) ON DELETE CASCADE,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE SET NULL,
+    INDEX idx_subscription_runs_subscription_created_at (subscription_id, created_at)
+);
+
+-- Notifications table (in-app notifications)
+CREATE TABLE IF NOT EXISTS notifications (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    kind VARCHAR(50) NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    content TEXT NOT NULL,
+    related_id VARCHAR(36) NULL,
+    read_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    INDEX idx_notifications_user_created_at (user_id, created_at)
+);
+
+-- Payments table (one row per payment attempt; orders move to processing only after a verified gateway callback)
+CREATE TABLE IF NOT EXISTS payments (
+    id VARCHAR(36) PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    user_id VARCHAR(36) NOT NULL,
+    gateway VARCHAR(30) NOT NULL,
+    gateway_payment_id VARCHAR(100) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    amount DOUBLE NOT NULL,
+    paid_amount DOUBLE NULL,
+    currency VARCHAR(10) NOT NULL DEFAULT 'CNY',
+    payment_url VARCHAR(500) NULL,
+    refunded_amount DOUBLE NOT NULL DEFAULT 0,
+    failure_reason TEXT NULL,
+    paid_at TIMESTAMP NULL,
+    refunded_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    UNIQUE KEY gateway_payment (gateway, gateway_payment_id),
+    INDEX idx_payments_order_id (order_id)
+);
+
+-- Payment callbacks table (one row per verified gateway notification, used to detect replays)
+CREATE TABLE IF NOT EXISTS payment_callbacks (
+    id VARCHAR(36) PRIMARY KEY,
+    payment_id VARCHAR(36) NOT NULL,
+    gateway VARCHAR(30) NOT NULL,
+    notification_id VARCHAR(100) NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    amount DOUBLE NOT NULL,
+    sent_at TIMESTAMP NOT NULL,
+    outcome VARCHAR(20) NOT NULL,
+    received_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (payment_id) REFERENCES payments(id) ON DELETE CASCADE,
+    UNIQUE KEY gateway_notification (gateway, notification_id),
+    INDEX idx_payment_callbacks_payment_id (payment_id)
+);
+
+-- Wallets table (store credit balance per user, changed only through the ledger)
+CREATE TABLE IF NOT EXISTS wallets (
+    user_id VARCHAR(36) PRIMARY KEY,
+    balance DOUBLE NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Wallet transactions table (append-only ledger; positive amounts are credits, negative amounts are debits)
+CREATE TABLE IF NOT EXISTS wallet_transactions (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    kind VARCHAR(30) NOT NULL,
+    amount DOUBLE NOT NULL,
+    balance_after DOUBLE NOT NULL,
+    reason TEXT NULL,
+    order_id VARCHAR(36) NULL,
+    created_by VARCHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES wallets(user_id) ON DELETE CASCADE,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE SET NULL,
+    INDEX idx_wallet_transactions_user_created_at (user_id, created_at)
+);
+
+-- Coupons table (percentage or fixed discounts with optional product/category/vendor scope, comma separated)
+CREATE TABLE IF NOT EXISTS coupons (
+    id VARCHAR(36) PRIMARY KEY,
+    code VARCHAR(50) NOT NULL UNIQUE,
+    discount_type VARCHAR(20) NOT NULL,
+    discount_value DOUBLE NOT NULL,
+    min_spend DOUBLE NOT NULL DEFAULT 0,
+    starts_at TIMESTAMP NULL,
+    ends_at TIMESTAMP NULL,
+    usage_limit INT NULL,
+    per_user_limit INT NULL,
+    used_count INT NOT NULL DEFAULT 0,
+    product_ids TEXT NULL,
+    categories TEXT NULL,
+    vendor_ids TEXT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by VARCHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+);
+
+-- Coupon redemptions table (one row per order that used a coupon, removed when the order is cancelled)
+CREATE TABLE IF NOT EXISTS coupon_redemptions (
+    id VARCHAR(36) PRIMARY KEY,
+    coupon_id VARCHAR(36) NOT NULL,
+    user_id VARCHAR(36) NOT NULL,
+    order_id VARCHAR(36) NOT NULL,
+    discount DOUBLE NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (coupon_id) REFERENCES coupons(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    INDEX idx_coupon_redemptions_coupon_user (coupon_id, user_id),
+    INDEX idx_coupon_redemptions_order_id (order_id)
+);
+
+-- Cart coupons table (coupon applied to a user's cart, at most one per cart)
+CREATE TABLE IF NOT EXISTS cart_coupons (
+    user_id VARCHAR(36) PRIMARY KEY,
+    coupon_id VARCHAR(36) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (coupon_id) REFERENCES coupons(id) ON DELETE CASCADE
+);
+
+-- Promotions table (automatic tiered, buy-x-get-y and percentage-off promotions; tiers are "threshold:discount" pairs, comma separated)
+CREATE TABLE IF NOT EXISTS promotions (
+    id VARCHAR(36) PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    kind VARCHAR(20) NOT NULL,
+    priority INT NOT NULL DEFAULT 0,
+    stackable BOOLEAN NOT NULL DEFAULT TRUE,
+    tiers TEXT NULL,
+    buy_quantity INT NULL,
+    free_quantity INT NULL,
+    percentage DOUBLE NULL,
+    product_ids TEXT NULL,
+    categories TEXT NULL,
+    vendor_ids TEXT NULL,
+    funded_by VARCHAR(36) NULL,
+    starts_at TIMESTAMP NULL,
+    ends_at TIMESTAMP NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by VARCHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    FOREIGN KEY (funded_by) REFERENCES users(id),
+    INDEX idx_promotions_active (active)
+);
+
+-- Order item promotions table (snapshot of the promotions applied to each order item)
+CREATE TABLE IF NOT EXISTS order_item_promotions (
+    id VARCHAR(36) PRIMARY KEY,
+    order_id VARCHAR(36) NOT NULL,
+    order_item_id VARCHAR(36) NOT NULL,
+    promotion_id VARCHAR(36) NOT NULL,
+    promotion_name VARCHAR(100) NOT NULL,
+    kind VARCHAR(20) NOT NULL,
+    discount DOUBLE NOT NULL,
+    funded_by VARCHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (order_item_id) REFERENCES order_items(id) ON DELETE CASCADE,
+    INDEX idx_order_item_promotions_order_id (order_id),
+    INDEX idx_order_item_promotions_promotion_id (promotion_id)
+);
+
 -- Clean up old sample data (if exists)
 -- Note: If this is the first run, these DELETE statements may not affect any rows, which is normal.
 -- For safety, delete in reverse order of dependencies
@@ -200,7 +660,7 @@ INSERT INTO vendor_profiles (id, vendor_id, store_name, store_description, conta
 INSERT INTO admin_profiles (
     id, admin_id, site_name, site_description, contact_email, order_prefix, items_per_page, allow_registration, maintenance_mode, theme, currency_symbol, tax_rate, payment_gateways, log_level
 ) VALUES (
-    UUID(), 'admin-001', 'Online Shopping System Pro', 'Upgraded online shopping system with powerful features!', 'admin_pro@example.com', 'PRO-', 12, TRUE, FALSE, 'dark', '$', 13.5, 'alipay,wechatpay,card', 'debug'
+    UUID(), 'admin-001', 'Online Shopping System Pro', 'Upgraded online shopping system with powerful features!', 'admin_pro@example.com', 'PRO-', 12, TRUE, FALSE, 'dark', '$', 13.5, 'alipay,wechatpay,card,mock', 'debug'
 );
 
 -- Initialize product data